@@ -0,0 +1,20 @@
+-- Normalized work items and pull requests cached from providers
+CREATE TABLE IF NOT EXISTS work_items (
+    id TEXT PRIMARY KEY,
+    provider TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('issue', 'pull_request')),
+    updated_at TEXT NOT NULL,
+    data TEXT NOT NULL,
+    synced_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_items_provider ON work_items (provider, kind);
+
+-- Per-provider background sync schedule and last outcome
+CREATE TABLE IF NOT EXISTS provider_sync (
+    provider TEXT PRIMARY KEY,
+    interval_minutes INTEGER NOT NULL DEFAULT 5 CHECK (interval_minutes BETWEEN 1 AND 60),
+    last_attempt_at TEXT,
+    last_synced_at TEXT,
+    last_error TEXT
+);
@@ -0,0 +1,67 @@
+-- Connections for every provider, with several accounts per provider.
+-- SQLite cannot alter a CHECK constraint, so the table is rebuilt.
+CREATE TABLE connections_new (
+    id TEXT PRIMARY KEY,
+    provider TEXT NOT NULL CHECK (provider IN ('github', 'jira', 'fogbugz', 'azure_devops')),
+    label TEXT NOT NULL,
+    auth_method TEXT NOT NULL CHECK (auth_method IN ('cli', 'pat', 'api_token', 'password')),
+    username TEXT,
+    -- Provider-specific settings as JSON (instance URL, organization, ...).
+    -- Secrets are kept in the OS keychain under the connection id.
+    config TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT INTO connections_new (id, provider, label, auth_method, username, created_at, updated_at)
+    SELECT id, provider, label, auth_method, username, created_at, updated_at FROM connections;
+
+DROP TABLE connections;
+ALTER TABLE connections_new RENAME TO connections;
+
+-- GitHub and Azure DevOps used to be queried implicitly through their CLIs
+INSERT OR IGNORE INTO connections (id, provider, label, auth_method) VALUES
+    ('github', 'github', 'GitHub', 'cli'),
+    ('azure_devops', 'azure_devops', 'Azure DevOps', 'cli');
+
+-- Jira and FogBugz were configured through the settings table. Their ids
+-- match the keychain entries the secrets were already stored under.
+INSERT OR IGNORE INTO connections (id, provider, label, auth_method, username, config)
+    SELECT 'jira', 'jira', 'Jira', 'api_token', email.value,
+           json_object('instance_url', url.value, 'email', email.value)
+    FROM settings url JOIN settings email
+        ON url.key = 'jira_instance_url' AND email.key = 'jira_email';
+
+INSERT OR IGNORE INTO connections (id, provider, label, auth_method, username, config)
+    SELECT 'fogbugz', 'fogbugz', 'FogBugz', 'password', email.value,
+           json_object('instance_url', url.value, 'email', email.value)
+    FROM settings url JOIN settings email
+        ON url.key = 'fogbugz_instance_url' AND email.key = 'fogbugz_email';
+
+DELETE FROM settings
+    WHERE key IN ('jira_instance_url', 'jira_email', 'fogbugz_instance_url', 'fogbugz_email');
+
+-- The work item cache and sync schedule are now per connection. Both are
+-- rebuilt by the next sync.
+DROP TABLE work_items;
+DROP TABLE provider_sync;
+
+CREATE TABLE work_items (
+    id TEXT PRIMARY KEY,
+    connection_id TEXT NOT NULL REFERENCES connections (id) ON DELETE CASCADE,
+    provider TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('issue', 'pull_request')),
+    updated_at TEXT NOT NULL,
+    data TEXT NOT NULL,
+    synced_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_work_items_connection ON work_items (connection_id, kind);
+
+CREATE TABLE connection_sync (
+    connection_id TEXT PRIMARY KEY REFERENCES connections (id) ON DELETE CASCADE,
+    interval_minutes INTEGER NOT NULL DEFAULT 5 CHECK (interval_minutes BETWEEN 1 AND 60),
+    last_attempt_at TEXT,
+    last_synced_at TEXT,
+    last_error TEXT
+);
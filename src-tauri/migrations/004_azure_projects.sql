@@ -0,0 +1,22 @@
+-- Azure DevOps organization/project pairs, several per connection. Work
+-- items and pull requests are fetched from every pair of a connection.
+CREATE TABLE azure_projects (
+    id TEXT PRIMARY KEY,
+    connection_id TEXT NOT NULL REFERENCES connections (id) ON DELETE CASCADE,
+    organization TEXT NOT NULL,
+    project TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (connection_id, organization, project)
+);
+
+-- The single pair a connection could hold in its config moves here
+INSERT INTO azure_projects (id, connection_id, organization, project)
+    SELECT lower(hex(randomblob(16))), id,
+           json_extract(config, '$.organization'), json_extract(config, '$.project')
+    FROM connections
+    WHERE provider = 'azure_devops'
+      AND json_extract(config, '$.organization') IS NOT NULL
+      AND json_extract(config, '$.project') IS NOT NULL;
+
+UPDATE connections SET config = json_remove(config, '$.organization', '$.project')
+    WHERE provider = 'azure_devops';
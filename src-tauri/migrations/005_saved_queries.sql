@@ -0,0 +1,12 @@
+-- Named queries in a provider's own syntax (JQL, WIQL, GitHub search,
+-- FogBugz search), run against one connection in place of the default
+-- "assigned to me" list.
+CREATE TABLE saved_queries (
+    id TEXT PRIMARY KEY,
+    connection_id TEXT NOT NULL REFERENCES connections (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (connection_id, name)
+);
@@ -0,0 +1,18 @@
+-- Links between work items and local artifacts (FR-003). `work_item_id` is
+-- the unified id `{connection_id}:{provider_id}`. Depending on `kind`:
+--   branch:       repo_path + branch name in target
+--   pull_request: unified id of the pull request in target
+--   repo:         repo_path, target empty
+-- Auto-detected links are rebuilt on every scan; manual ones are kept.
+CREATE TABLE links (
+    id TEXT PRIMARY KEY,
+    work_item_id TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('branch', 'pull_request', 'repo')),
+    repo_path TEXT NOT NULL DEFAULT '',
+    target TEXT NOT NULL DEFAULT '',
+    source TEXT NOT NULL CHECK (source IN ('manual', 'auto')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (work_item_id, kind, repo_path, target)
+);
+
+CREATE INDEX idx_links_repo ON links (repo_path);
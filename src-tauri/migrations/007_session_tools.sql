@@ -0,0 +1,16 @@
+-- User-defined session tools (FR-016). A row whose id matches a built-in
+-- tool overrides it; deleting the row restores the built-in.
+CREATE TABLE session_tools (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    command TEXT NOT NULL,
+    -- JSON array of arguments; may use {repo}, {branch} and {issue_key}
+    args TEXT NOT NULL DEFAULT '[]',
+    category TEXT NOT NULL CHECK (category IN ('editor', 'terminal', 'ai', 'other')),
+    -- JSON object of environment variables, same placeholders as args
+    env TEXT NOT NULL DEFAULT '{}',
+    -- JSON ContextDelivery, or NULL for tools that take no work item context
+    context TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
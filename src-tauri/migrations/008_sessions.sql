@@ -0,0 +1,18 @@
+-- Sessions launched from Aura (FR-016). Rows left `running` by a previous
+-- run of the app are marked `lost` the next time sessions are listed.
+CREATE TABLE sessions (
+    id TEXT PRIMARY KEY,
+    tool_id TEXT NOT NULL,
+    tool_name TEXT NOT NULL,
+    repo_path TEXT NOT NULL,
+    -- Unified work item id, `{connection_id}:{provider_id}`
+    work_item_id TEXT,
+    pid INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running' CHECK (status IN ('running', 'exited', 'killed', 'lost')),
+    exit_code INTEGER,
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    ended_at TEXT
+);
+
+CREATE INDEX idx_sessions_started_at ON sessions(started_at);
+CREATE INDEX idx_sessions_work_item ON sessions(work_item_id);
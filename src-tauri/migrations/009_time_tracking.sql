@@ -0,0 +1,23 @@
+-- Time tracking. Time worked on an item is the union of its finished
+-- sessions, plus manual adjustments in seconds (negative to take time off).
+CREATE TABLE time_adjustments (
+    id TEXT PRIMARY KEY,
+    work_item_id TEXT NOT NULL,
+    seconds INTEGER NOT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Time already booked with the tracker. Only the tracked total minus these
+-- is exported, so exporting again never books the same time twice.
+CREATE TABLE worklog_exports (
+    id TEXT PRIMARY KEY,
+    work_item_id TEXT NOT NULL,
+    seconds INTEGER NOT NULL,
+    -- The tracker's worklog, interval or revision id
+    remote_id TEXT,
+    exported_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_time_adjustments_work_item ON time_adjustments(work_item_id);
+CREATE INDEX idx_worklog_exports_work_item ON worklog_exports(work_item_id);
@@ -0,0 +1,15 @@
+-- Index of local repos from the last scan (FR-010). A rescan only asks git
+-- again about repos whose fingerprint (mtimes of HEAD, index, FETCH_HEAD,
+-- packed-refs and the reflogs) changed.
+CREATE TABLE repos (
+    path TEXT PRIMARY KEY,
+    -- Scan root the repo was found under
+    root TEXT NOT NULL,
+    name TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    -- JSON LocalRepo
+    data TEXT NOT NULL,
+    scanned_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_repos_root ON repos(root);
@@ -0,0 +1,6 @@
+-- Per-root scan options (FR-010). Patterns are JSON arrays of globs; a
+-- directory matching `include` is walked even when `exclude` matches it.
+ALTER TABLE scan_roots ADD COLUMN max_depth INTEGER NOT NULL DEFAULT 4;
+ALTER TABLE scan_roots ADD COLUMN include TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE scan_roots ADD COLUMN exclude TEXT NOT NULL DEFAULT '[".*","node_modules","target"]';
+ALTER TABLE scan_roots ADD COLUMN follow_symlinks INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,13 @@
+-- When a running session was last seen alive. A session still running when
+-- the app quit is counted up to its last heartbeat instead of not at all.
+ALTER TABLE sessions ADD COLUMN last_seen_at TEXT;
+
+-- Sessions marked lost so far ended at an unknown time; count them as empty
+UPDATE sessions SET ended_at = started_at WHERE status = 'lost';
+
+-- An export is recorded as `pending` before the tracker is called and
+-- becomes `exported` once the tracker confirms it. A pending row left by a
+-- call that failed midway may or may not have been booked; it counts as
+-- booked until the user settles it, so the time is never booked twice.
+ALTER TABLE worklog_exports ADD COLUMN status TEXT NOT NULL DEFAULT 'exported'
+    CHECK (status IN ('pending', 'exported'));
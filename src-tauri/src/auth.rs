@@ -1,5 +1,13 @@
 use serde::Serialize;
 use std::process::Command;
+use tauri::State;
+
+use crate::error::{AuraError, AuraResult};
+use crate::github::{api_base, fetch_user, normalize_host, GitHubUser, DEFAULT_HOST};
+use crate::http::HttpClient;
+use crate::provider::ProviderKind;
+
+const GITHUB_PAT_KEY: &str = "github-pat";
 
 #[derive(Debug, Serialize, Clone)]
 pub struct AuthStatus {
@@ -7,6 +15,8 @@ pub struct AuthStatus {
     pub cli_authenticated: bool,
     pub username: Option<String>,
     pub auth_method: String, // "cli" or "pat" or "none"
+    /// For Rust-side callers; the token never leaves the Rust side
+    #[serde(skip)]
     pub token: Option<String>,
 }
 
@@ -19,101 +29,170 @@ pub fn is_gh_installed() -> bool {
         .unwrap_or(false)
 }
 
-/// Extract token from `gh auth token`
-pub fn extract_gh_token() -> Result<String, String> {
+/// Extract the token `gh` holds for a host via `gh auth token --hostname`
+pub fn extract_gh_token(host: &str) -> AuraResult<String> {
     let output = Command::new("gh")
-        .args(["auth", "token"])
+        .args(["auth", "token", "--hostname", host])
         .output()
-        .map_err(|e| format!("Failed to run gh: {}", e))?;
+        .map_err(|e| AuraError::from_spawn("gh", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("gh auth token failed: {}", stderr.trim()));
+        return Err(AuraError::NotAuthenticated {
+            provider: ProviderKind::Github,
+            message: stderr.trim().to_string(),
+        });
     }
 
     let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
     if token.is_empty() {
-        return Err("gh auth token returned empty".to_string());
+        return Err(AuraError::cli_failed("gh auth token", "returned empty"));
     }
     Ok(token)
 }
 
-/// Get username from `gh auth status`
-pub fn get_gh_username() -> Result<String, String> {
+/// Every `(host, username)` pair in `gh auth status` output, in order.
+/// Lines look like "Logged in to HOST account USER (keyring)", or
+/// "Logged in to HOST as USER (oauth_token)" on older `gh` versions.
+pub fn parse_gh_accounts(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.split("Logged in to ").nth(1)?;
+            let mut words = rest.split_whitespace();
+            let host = words.next()?;
+            let username = match words.next()? {
+                "account" | "as" => words.next()?,
+                _ => return None,
+            };
+            Some((host.to_lowercase(), username.to_string()))
+        })
+        .collect()
+}
+
+/// Get the username `gh` is logged in with on a host. `gh` lists the
+/// active account first when a host has several.
+pub fn get_gh_username(host: &str) -> AuraResult<String> {
     let output = Command::new("gh")
-        .args(["auth", "status", "--show-token"])
+        .args(["auth", "status", "--hostname", host])
         .output()
-        .map_err(|e| format!("Failed to run gh: {}", e))?;
+        .map_err(|e| AuraError::from_spawn("gh", e))?;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
     let combined = format!("{}{}", stdout, stderr);
 
-    // gh auth status outputs "Logged in to github.com account USERNAME"
-    for line in combined.lines() {
-        if line.contains("Logged in to") {
-            if let Some(account) = line.split("account ").nth(1) {
-                let username = account.split_whitespace().next().unwrap_or("").to_string();
-                if !username.is_empty() {
-                    return Ok(username);
-                }
-            }
-        }
-    }
+    let host = normalize_host(host);
+    parse_gh_accounts(&combined)
+        .into_iter()
+        .find(|(h, _)| *h == host)
+        .map(|(_, username)| username)
+        .ok_or_else(|| {
+            AuraError::cli_failed("gh auth status", "could not determine GitHub username")
+        })
+}
+
+// -- Personal access token --
 
-    Err("Could not determine GitHub username from gh auth status".to_string())
+/// Read the PAT saved in the OS keychain, if any
+pub fn load_github_pat() -> AuraResult<Option<String>> {
+    crate::credentials::load_secret(GITHUB_PAT_KEY)
 }
 
-/// Resolve GitHub auth — CLI first, PAT fallback
-pub fn resolve_github_auth() -> AuthStatus {
-    if !is_gh_installed() {
-        return AuthStatus {
-            cli_available: false,
-            cli_authenticated: false,
-            username: None,
-            auth_method: "none".to_string(),
-            token: None,
-        };
-    }
+/// Token for API calls on a host: `gh` first, then the saved PAT, which is
+/// a github.com token. When neither is available the `gh` error is
+/// returned, since that is the primary path.
+pub fn github_token(host: &str) -> AuraResult<String> {
+    extract_gh_token(host).or_else(|cli_err| {
+        if normalize_host(host) != DEFAULT_HOST {
+            return Err(cli_err);
+        }
+        load_github_pat().ok().flatten().ok_or(cli_err)
+    })
+}
 
-    match extract_gh_token() {
-        Ok(token) => {
-            let username = get_gh_username().ok();
-            AuthStatus {
+/// Resolve GitHub auth for a host — CLI first, PAT fallback on github.com
+pub async fn resolve_github_auth(http: &HttpClient, host: &str) -> AuthStatus {
+    let cli_available = is_gh_installed();
+    if cli_available {
+        if let Ok(token) = extract_gh_token(host) {
+            return AuthStatus {
                 cli_available: true,
                 cli_authenticated: true,
-                username,
+                username: get_gh_username(host).ok(),
                 auth_method: "cli".to_string(),
                 token: Some(token),
-            }
+            };
         }
+    }
+
+    let unauthenticated = AuthStatus {
+        cli_available,
+        cli_authenticated: false,
+        username: None,
+        auth_method: "none".to_string(),
+        token: None,
+    };
+    if normalize_host(host) != DEFAULT_HOST {
+        return unauthenticated;
+    }
+    let Some(token) = load_github_pat().ok().flatten() else {
+        return unauthenticated;
+    };
+
+    match fetch_user(http, &api_base(host), &token).await {
+        Ok(user) => AuthStatus {
+            username: Some(user.login),
+            auth_method: "pat".to_string(),
+            token: Some(token),
+            ..unauthenticated
+        },
+        // Revoked or expired
+        Err(AuraError::NotAuthenticated { .. }) => unauthenticated,
+        // Offline or rate limited: the token may still be good
         Err(_) => AuthStatus {
-            cli_available: true,
-            cli_authenticated: false,
-            username: None,
-            auth_method: "none".to_string(),
-            token: None,
+            auth_method: "pat".to_string(),
+            token: Some(token),
+            ..unauthenticated
         },
     }
 }
 
 // -- Tauri commands --
 
+/// Auth status on a host; github.com when `host` is omitted
 #[tauri::command]
-pub fn check_github_auth() -> AuthStatus {
-    resolve_github_auth()
+pub async fn check_github_auth(
+    http: State<'_, HttpClient>,
+    host: Option<String>,
+) -> AuraResult<AuthStatus> {
+    let host = host.unwrap_or_else(|| DEFAULT_HOST.to_string());
+    Ok(resolve_github_auth(&http, &host).await)
 }
 
+/// Validate a PAT against `GET /user`, then save it in the OS keychain
 #[tauri::command]
-pub fn get_github_token() -> Result<String, String> {
-    let status = resolve_github_auth();
-    status.token.ok_or_else(|| {
-        if !status.cli_available {
-            "GitHub CLI not installed. Install from https://cli.github.com/ or configure a PAT in Settings.".to_string()
-        } else {
-            "GitHub CLI not authenticated. Run `gh auth login` or configure a PAT in Settings.".to_string()
-        }
-    })
+pub async fn save_github_pat(http: State<'_, HttpClient>, token: String) -> AuraResult<GitHubUser> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(AuraError::invalid_input("Token is empty"));
+    }
+    let user = fetch_user(&http, &api_base(DEFAULT_HOST), token).await?;
+    crate::credentials::store_secret(GITHUB_PAT_KEY, token)?;
+    Ok(user)
+}
+
+/// Check that the saved PAT is still accepted by GitHub
+#[tauri::command]
+pub async fn validate_github_pat(http: State<'_, HttpClient>) -> AuraResult<GitHubUser> {
+    let token =
+        load_github_pat()?.ok_or_else(|| AuraError::not_configured("No GitHub token saved."))?;
+    fetch_user(&http, &api_base(DEFAULT_HOST), &token).await
+}
+
+#[tauri::command]
+pub fn delete_github_pat() -> AuraResult<()> {
+    crate::credentials::delete_secret(GITHUB_PAT_KEY)
 }
 
 #[cfg(test)]
@@ -130,7 +209,8 @@ mod tests {
 
     #[test]
     fn test_resolve_github_auth_returns_status() {
-        let status = resolve_github_auth();
+        let http = HttpClient::new();
+        let status = tauri::async_runtime::block_on(resolve_github_auth(&http, DEFAULT_HOST));
         assert!(["cli", "pat", "none"].contains(&status.auth_method.as_str()));
         if status.auth_method == "cli" {
             assert!(status.cli_available);
@@ -138,4 +218,27 @@ mod tests {
             assert!(status.token.is_some());
         }
     }
+
+    #[test]
+    fn test_parse_gh_accounts_reads_every_host() {
+        let output = "github.com
+  \u{2713} Logged in to github.com account octocat (keyring)
+  - Active account: true
+  - Git operations protocol: https
+ghe.corp.example
+  \u{2713} Logged in to ghe.corp.example account jdoe (GH_ENTERPRISE_TOKEN)
+  - Active account: true
+legacy.example
+  \u{2713} Logged in to legacy.example as olduser (oauth_token)
+";
+        assert_eq!(
+            parse_gh_accounts(output),
+            vec![
+                ("github.com".to_string(), "octocat".to_string()),
+                ("ghe.corp.example".to_string(), "jdoe".to_string()),
+                ("legacy.example".to_string(), "olduser".to_string()),
+            ]
+        );
+        assert!(parse_gh_accounts("You are not logged into any GitHub hosts.").is_empty());
+    }
 }
@@ -1,5 +1,20 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
 use std::process::Command;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+use crate::connections::{connection_for, Connection};
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::provider::{
+    html_to_text, normalize_timestamp, Fetched, Provider, ProviderKind, ProviderStatus,
+    StatusCategory, WorkItem, WorkItemDetails, WorkItemKind, WorkLogEntry,
+};
 
 // -- Public types returned to frontend --
 
@@ -21,6 +36,9 @@ pub struct AzureWorkItem {
     pub changed_date: String,
     pub tags: Vec<String>,
     pub url: String,
+    /// Organization name and project the item was fetched from
+    pub organization: String,
+    pub project: String,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -34,15 +52,152 @@ pub struct AzurePullRequest {
     pub target_branch: String,
     pub creation_date: String,
     pub url: String,
+    /// How the signed-in user is involved
+    pub role: PullRequestRole,
+    /// The user's own review vote, if they are a reviewer
+    pub vote: Option<ReviewVote>,
+    pub organization: String,
+    pub project: String,
+}
+
+/// An organization/project pair saved on a connection
+#[derive(Debug, Serialize, Clone)]
+pub struct AzureProject {
+    pub id: String,
+    pub connection_id: String,
+    /// Organization URL
+    pub organization: String,
+    pub project: String,
+}
+
+/// Results from several organization/project pairs. A project that can't
+/// be reached or read doesn't hide the others; it is in `failures`.
+#[derive(Debug, Serialize, Clone)]
+pub struct AcrossProjects<T> {
+    pub items: Vec<T>,
+    pub failures: Vec<ProjectFailure>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectFailure {
+    /// Organization name
+    pub organization: String,
+    pub project: String,
+    pub error: AuraError,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PullRequestRole {
+    Author,
+    Reviewer,
+    RequiredReviewer,
+}
+
+impl PullRequestRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PullRequestRole::Author => "author",
+            PullRequestRole::Reviewer => "reviewer",
+            PullRequestRole::RequiredReviewer => "required_reviewer",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewVote {
+    Approved,
+    ApprovedWithSuggestions,
+    NoVote,
+    WaitingForAuthor,
+    Rejected,
+}
+
+impl ReviewVote {
+    /// Azure DevOps encodes votes as 10, 5, 0, -5 and -10
+    fn from_code(vote: i64) -> Self {
+        match vote {
+            10 => ReviewVote::Approved,
+            5 => ReviewVote::ApprovedWithSuggestions,
+            -5 => ReviewVote::WaitingForAuthor,
+            -10 => ReviewVote::Rejected,
+            _ => ReviewVote::NoVote,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewVote::Approved => "approved",
+            ReviewVote::ApprovedWithSuggestions => "approved_with_suggestions",
+            ReviewVote::NoVote => "no_vote",
+            ReviewVote::WaitingForAuthor => "waiting_for_author",
+            ReviewVote::Rejected => "rejected",
+        }
+    }
+}
+
+/// Application id of Azure DevOps, the resource `az` issues tokens for
+const DEVOPS_RESOURCE: &str = "499b84ac-1321-427f-aa17-267ca6975798";
+const API_VERSION: &str = "7.1";
+/// Hours booked against a task
+const COMPLETED_WORK: &str = "Microsoft.VSTS.Scheduling.CompletedWork";
+/// The work items endpoint accepts at most this many ids per call
+const WORK_ITEM_BATCH: usize = 200;
+/// The default list: open work items assigned to the user
+const ASSIGNED_WIQL: &str = concat!(
+    "SELECT [System.Id] FROM workitems ",
+    "WHERE [System.TeamProject] = @project AND [System.AssignedTo] = @Me ",
+    "AND [System.State] <> 'Closed' AND [System.State] <> 'Removed' ",
+    "AND [System.State] <> 'Done' ORDER BY [System.ChangedDate] DESC"
+);
+/// Tokens are refreshed this long before they expire
+const TOKEN_EXPIRY_MARGIN: ChronoDuration = ChronoDuration::minutes(5);
+
+/// Token issued by `az account get-access-token`. `az login` is per user,
+/// not per connection, so one token serves every organization.
+static TOKEN_CACHE: Mutex<Option<AccessToken>> = Mutex::new(None);
+
+#[derive(Debug, Clone)]
+struct AccessToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+// -- Raw JSON shapes from the REST API --
+
+#[derive(Deserialize)]
+struct RawAccessToken {
+    #[serde(rename = "accessToken")]
+    access_token: String,
+    /// Local time, e.g. "2024-03-01 11:00:00.000000"
+    #[serde(rename = "expiresOn")]
+    expires_on: Option<String>,
+    /// Unix timestamp; only newer `az` versions include it
+    #[serde(rename = "expires_on")]
+    expires_on_unix: Option<i64>,
+}
+
+#[derive(Deserialize)]
+struct RawList<T> {
+    value: Vec<T>,
 }
 
-// -- Raw JSON shapes from az CLI --
+#[derive(Deserialize)]
+struct RawWiqlResult {
+    #[serde(rename = "workItems")]
+    work_items: Vec<RawWiqlRef>,
+}
+
+#[derive(Deserialize)]
+struct RawWiqlRef {
+    id: u64,
+}
 
 #[derive(Deserialize)]
 struct RawQueryItem {
     id: u64,
     fields: RawWorkItemFields,
-    url: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -61,6 +216,32 @@ struct RawWorkItemFields {
     tags: Option<String>,
 }
 
+/// Rich text fields of one work item, as HTML
+#[derive(Deserialize)]
+struct RawWorkItemDetails {
+    fields: RawDetailFields,
+}
+
+#[derive(Deserialize)]
+struct RawDetailFields {
+    #[serde(rename = "System.Description")]
+    description: Option<String>,
+    #[serde(rename = "Microsoft.VSTS.Common.AcceptanceCriteria")]
+    acceptance_criteria: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawCompletedWork {
+    rev: u64,
+    fields: RawCompletedWorkFields,
+}
+
+#[derive(Deserialize)]
+struct RawCompletedWorkFields {
+    #[serde(rename = "Microsoft.VSTS.Scheduling.CompletedWork")]
+    completed_work: Option<f64>,
+}
+
 #[derive(Deserialize)]
 struct RawPullRequest {
     #[serde(rename = "pullRequestId")]
@@ -76,14 +257,32 @@ struct RawPullRequest {
     target_ref_name: Option<String>,
     #[serde(rename = "creationDate")]
     creation_date: Option<String>,
+    #[serde(default)]
+    reviewers: Vec<RawReviewer>,
 }
 
 #[derive(Deserialize)]
 struct RawIdentity {
+    id: Option<String>,
     #[serde(rename = "displayName")]
     display_name: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct RawReviewer {
+    id: Option<String>,
+    #[serde(default)]
+    vote: i64,
+    #[serde(rename = "isRequired", default)]
+    is_required: bool,
+}
+
+#[derive(Deserialize)]
+struct RawConnectionData {
+    #[serde(rename = "authenticatedUser")]
+    authenticated_user: RawIdentity,
+}
+
 #[derive(Deserialize)]
 struct RawRepository {
     name: Option<String>,
@@ -108,19 +307,22 @@ fn is_az_logged_in() -> bool {
 }
 
 fn get_devops_defaults() -> (Option<String>, Option<String>) {
-    let output = match Command::new("az")
+    match Command::new("az")
         .args(["devops", "configure", "--list"])
         .output()
     {
-        Ok(o) if o.status.success() => o,
-        _ => return (None, None),
-    };
+        Ok(o) if o.status.success() => parse_devops_defaults(&String::from_utf8_lossy(&o.stdout)),
+        _ => (None, None),
+    }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+/// Organization and project from `az devops configure --list` output:
+/// `key = value` lines, with `None` or nothing for unset values
+fn parse_devops_defaults(output: &str) -> (Option<String>, Option<String>) {
     let mut org = None;
     let mut project = None;
 
-    for line in stdout.lines() {
+    for line in output.lines() {
         let parts: Vec<&str> = line.splitn(2, '=').collect();
         if parts.len() != 2 {
             continue;
@@ -198,54 +400,216 @@ fn work_item_web_url(org: &str, project: &str, id: u64) -> String {
 /// Build a web URL for a pull request
 fn pr_web_url(org: &str, project: &str, repo: &str, id: u64) -> String {
     let org_base = org.trim_end_matches('/');
-    format!(
-        "{}/{}/_git/{}/pullrequest/{}",
-        org_base, project, repo, id
+    format!("{}/{}/_git/{}/pullrequest/{}", org_base, project, repo, id)
+}
+
+fn require_defaults(status: &AzureAuthStatus) -> AuraResult<(String, String)> {
+    let org = status.organization.as_deref().ok_or_else(|| {
+        AuraError::not_configured("No Azure DevOps organization configured. Add a project in Settings or run `az devops configure --defaults organization=https://dev.azure.com/YOUR_ORG`")
+    })?;
+    let project = status.project.clone().ok_or_else(|| {
+        AuraError::not_configured("No Azure DevOps project configured. Add a project in Settings or run `az devops configure --defaults project=YOUR_PROJECT`")
+    })?;
+    Ok((org_url(org), project))
+}
+
+/// Organization URL from either a bare name or a full URL
+fn org_url(org: &str) -> String {
+    let org = org.trim().trim_end_matches('/');
+    if org.starts_with("https://") || org.starts_with("http://") {
+        org.to_string()
+    } else {
+        format!("https://dev.azure.com/{}", org)
+    }
+}
+
+/// "https://dev.azure.com/contoso" -> "contoso"; legacy
+/// "https://contoso.visualstudio.com" -> "contoso"
+fn org_name(org: &str) -> String {
+    let org = org.trim_end_matches('/');
+    let host = org.split("://").nth(1).unwrap_or(org);
+    match host.split_once('/') {
+        Some((_, path)) => path.rsplit('/').next().unwrap_or(path).to_string(),
+        None => host.split('.').next().unwrap_or(host).to_string(),
+    }
+}
+
+// -- Project list --
+
+async fn load_projects(
+    pool: &sqlx::SqlitePool,
+    connection_id: &str,
+) -> AuraResult<Vec<AzureProject>> {
+    let rows = sqlx::query_as::<_, (String, String, String, String)>(
+        "SELECT id, connection_id, organization, project FROM azure_projects WHERE connection_id = ? ORDER BY organization, project",
     )
+    .bind(connection_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, connection_id, organization, project)| AzureProject {
+            id,
+            connection_id,
+            organization,
+            project,
+        })
+        .collect())
 }
 
-// -- Tauri commands --
+/// Organization URL and project pairs to query: the ones saved on the
+/// connection, or the `az devops configure` defaults when none are saved
+async fn targets_for(
+    pool: &sqlx::SqlitePool,
+    connection_id: &str,
+) -> AuraResult<Vec<(String, String)>> {
+    let projects = load_projects(pool, connection_id).await?;
+    if !projects.is_empty() {
+        return Ok(projects
+            .into_iter()
+            .map(|p| (p.organization, p.project))
+            .collect());
+    }
 
-#[tauri::command]
-pub fn check_azure_auth() -> AzureAuthStatus {
-    resolve_auth()
+    let (organization, project) = tauri::async_runtime::spawn_blocking(get_devops_defaults)
+        .await
+        .map_err(AuraError::io)?;
+    let status = AzureAuthStatus {
+        cli_available: true,
+        logged_in: true,
+        organization,
+        project,
+    };
+    Ok(vec![require_defaults(&status)?])
 }
 
-#[tauri::command]
-pub fn azure_fetch_work_items() -> Result<Vec<AzureWorkItem>, String> {
-    let status = resolve_auth();
-    if !status.logged_in {
-        return Err("Azure CLI not authenticated. Run `az login` first.".to_string());
-    }
-    let org = status
-        .organization
-        .as_deref()
-        .ok_or("No Azure DevOps organization configured. Run `az devops configure --defaults organization=https://dev.azure.com/YOUR_ORG`")?;
-    let project = status
-        .project
-        .as_deref()
-        .ok_or("No Azure DevOps project configured. Run `az devops configure --defaults project=YOUR_PROJECT`")?;
-
-    let wiql = concat!(
-        "SELECT [System.Id], [System.Title], [System.State], [System.WorkItemType], ",
-        "[System.AssignedTo], [System.ChangedDate], [System.Tags] ",
-        "FROM workitems WHERE [System.AssignedTo] = @Me ",
-        "AND [System.State] <> 'Closed' AND [System.State] <> 'Removed' ",
-        "AND [System.State] <> 'Done' ORDER BY [System.ChangedDate] DESC"
-    );
+// -- Access token --
+
+fn parse_access_token(json: &[u8]) -> AuraResult<AccessToken> {
+    let raw: RawAccessToken = serde_json::from_slice(json)?;
+    let expires_at = match (raw.expires_on_unix, raw.expires_on.as_deref()) {
+        (Some(ts), _) => DateTime::from_timestamp(ts, 0),
+        (None, Some(local)) => NaiveDateTime::parse_from_str(local, "%Y-%m-%d %H:%M:%S%.f")
+            .ok()
+            .and_then(|t| t.and_local_timezone(Local).earliest())
+            .map(|t| t.with_timezone(&Utc)),
+        (None, None) => None,
+    }
+    .ok_or_else(|| AuraError::parse("az access token has no valid expiry"))?;
+
+    Ok(AccessToken {
+        token: raw.access_token,
+        expires_at,
+    })
+}
 
+/// Ask `az` for an Azure DevOps token. Needs only `az login`, not the
+/// azure-devops extension.
+fn request_access_token() -> AuraResult<AccessToken> {
     let output = Command::new("az")
-        .args(["boards", "query", "--wiql", wiql, "--output", "json"])
+        .args(["account", "get-access-token", "--resource", DEVOPS_RESOURCE])
+        .args(["--output", "json"])
         .output()
-        .map_err(|e| format!("Failed to run az: {}", e))?;
+        .map_err(|e| AuraError::from_spawn("az", e))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("az boards query failed: {}", stderr.trim()));
+        return Err(AuraError::NotAuthenticated {
+            provider: ProviderKind::AzureDevops,
+            message: format!("Run `az login` first. {}", stderr.trim()),
+        });
     }
+    parse_access_token(&output.stdout)
+}
+
+/// Bearer token for the REST API, reused until shortly before it expires
+pub async fn access_token() -> AuraResult<String> {
+    let now = Utc::now();
+    if let Some(cached) = TOKEN_CACHE.lock().unwrap().as_ref() {
+        if cached.expires_at - TOKEN_EXPIRY_MARGIN > now {
+            return Ok(cached.token.clone());
+        }
+    }
+
+    let fresh = tauri::async_runtime::spawn_blocking(request_access_token)
+        .await
+        .map_err(AuraError::io)??;
+    let token = fresh.token.clone();
+    *TOKEN_CACHE.lock().unwrap() = Some(fresh);
+    Ok(token)
+}
+
+fn build_headers(token: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+    );
+    headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+    headers
+}
+
+async fn read_json<T: serde::de::DeserializeOwned>(resp: reqwest::Response) -> AuraResult<T> {
+    if !resp.status().is_success() {
+        return Err(AuraError::from_response(ProviderKind::AzureDevops, resp).await);
+    }
+    resp.json::<T>().await.map_err(AuraError::from_reqwest)
+}
+
+// -- REST queries --
+
+/// Run a WIQL query in one project for the ids, then load the fields in
+/// batches. `org` is the organization URL.
+pub async fn query_work_items(
+    http: &HttpClient,
+    org: &str,
+    project: &str,
+    token: &str,
+    wiql: &str,
+) -> AuraResult<Vec<AzureWorkItem>> {
+    let resp = http
+        .send(
+            http.post(format!("{}/{}/_apis/wit/wiql", org, project))
+                .headers(build_headers(token))
+                .query(&[("api-version", API_VERSION)])
+                .json(&json!({ "query": wiql })),
+        )
+        .await?;
+    let result: RawWiqlResult = read_json(resp).await?;
+    let ids: Vec<u64> = result.work_items.into_iter().map(|w| w.id).collect();
+
+    let fields = [
+        "System.Title",
+        "System.State",
+        "System.WorkItemType",
+        "System.AssignedTo",
+        "System.ChangedDate",
+        "System.Tags",
+    ]
+    .join(",");
 
-    let raw: Vec<RawQueryItem> = serde_json::from_slice(&output.stdout)
-        .map_err(|e| format!("Failed to parse work items: {}", e))?;
+    let mut raw = Vec::with_capacity(ids.len());
+    for batch in ids.chunks(WORK_ITEM_BATCH) {
+        let ids = batch
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let resp = http
+            .send(
+                http.get(format!("{}/{}/_apis/wit/workitems", org, project))
+                    .headers(build_headers(token))
+                    .query(&[
+                        ("ids", ids.as_str()),
+                        ("fields", fields.as_str()),
+                        ("api-version", API_VERSION),
+                    ]),
+            )
+            .await?;
+        let page: RawList<RawQueryItem> = read_json(resp).await?;
+        raw.extend(page.value);
+    }
 
     let items = raw
         .into_iter()
@@ -268,9 +632,9 @@ pub fn azure_fetch_work_items() -> Result<Vec<AzureWorkItem>, String> {
                 assigned_to: r.fields.assigned_to.as_ref().and_then(extract_display_name),
                 changed_date: r.fields.changed_date.unwrap_or_default(),
                 tags,
-                url: r
-                    .url
-                    .unwrap_or_else(|| work_item_web_url(org, project, r.id)),
+                url: work_item_web_url(org, project, r.id),
+                organization: org_name(org),
+                project: project.to_string(),
             }
         })
         .collect();
@@ -278,44 +642,176 @@ pub fn azure_fetch_work_items() -> Result<Vec<AzureWorkItem>, String> {
     Ok(items)
 }
 
-#[tauri::command]
-pub fn azure_fetch_prs() -> Result<Vec<AzurePullRequest>, String> {
-    let status = resolve_auth();
-    if !status.logged_in {
-        return Err("Azure CLI not authenticated. Run `az login` first.".to_string());
-    }
-    let org = status.organization.as_deref().ok_or(
-        "No Azure DevOps organization configured. Run `az devops configure --defaults organization=https://dev.azure.com/YOUR_ORG`",
-    )?;
-    let project = status.project.as_deref().ok_or(
-        "No Azure DevOps project configured. Run `az devops configure --defaults project=YOUR_PROJECT`",
-    )?;
+/// Description and acceptance criteria of one work item. Types without
+/// acceptance criteria (bugs, tasks) simply omit the field.
+pub async fn fetch_work_item_details(
+    http: &HttpClient,
+    org: &str,
+    token: &str,
+    id: u64,
+) -> AuraResult<WorkItemDetails> {
+    let fields = "System.Description,Microsoft.VSTS.Common.AcceptanceCriteria";
+    let resp = http
+        .send(
+            http.get(format!("{}/_apis/wit/workitems/{}", org, id))
+                .headers(build_headers(token))
+                .query(&[("fields", fields), ("api-version", API_VERSION)]),
+        )
+        .await?;
+    let raw: RawWorkItemDetails = read_json(resp).await?;
+    let text = |html: Option<String>| html.map(|h| html_to_text(&h)).filter(|t| !t.is_empty());
+    Ok(WorkItemDetails {
+        description: text(raw.fields.description),
+        acceptance_criteria: text(raw.fields.acceptance_criteria),
+    })
+}
 
-    let output = Command::new("az")
-        .args([
-            "repos", "pr", "list", "--status", "active", "--output", "json",
-        ])
-        .output()
-        .map_err(|e| format!("Failed to run az: {}", e))?;
+/// Add hours to a work item's Completed Work and return the new revision.
+/// The patch is tested against the revision read, so a concurrent edit
+/// makes it fail instead of being overwritten.
+pub async fn add_completed_work(
+    http: &HttpClient,
+    org: &str,
+    token: &str,
+    id: u64,
+    hours: f64,
+) -> AuraResult<String> {
+    let url = format!("{}/_apis/wit/workitems/{}", org, id);
+    let resp = http
+        .send(
+            http.get(&url)
+                .headers(build_headers(token))
+                .query(&[("fields", COMPLETED_WORK), ("api-version", API_VERSION)]),
+        )
+        .await?;
+    let current: RawCompletedWork = read_json(resp).await?;
+    let patch = json!([
+        { "op": "test", "path": "/rev", "value": current.rev },
+        {
+            "op": "add",
+            "path": format!("/fields/{}", COMPLETED_WORK),
+            "value": current.fields.completed_work.unwrap_or(0.0) + hours,
+        },
+    ]);
+    let resp = http
+        .send_once(
+            http.patch(&url)
+                .headers(build_headers(token))
+                .header(CONTENT_TYPE, "application/json-patch+json")
+                .query(&[("api-version", API_VERSION)])
+                .body(patch.to_string()),
+        )
+        .await?;
+    let updated: RawCompletedWork = read_json(resp).await?;
+    Ok(updated.rev.to_string())
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("az repos pr list failed: {}", stderr.trim()));
+/// Identity id of the token's user within an organization
+pub async fn fetch_user_id(http: &HttpClient, org: &str, token: &str) -> AuraResult<String> {
+    let resp = http
+        .send(
+            http.get(format!("{}/_apis/connectionData", org))
+                .headers(build_headers(token)),
+        )
+        .await?;
+    let data: RawConnectionData = read_json(resp).await?;
+    data.authenticated_user
+        .id
+        .ok_or_else(|| AuraError::parse("Azure DevOps did not report the signed-in user"))
+}
+
+async fn search_pull_requests(
+    http: &HttpClient,
+    org: &str,
+    project: &str,
+    token: &str,
+    criterion: &str,
+    user_id: &str,
+) -> AuraResult<Vec<RawPullRequest>> {
+    let resp = http
+        .send(
+            http.get(format!("{}/{}/_apis/git/pullrequests", org, project))
+                .headers(build_headers(token))
+                .query(&[
+                    ("searchCriteria.status", "active"),
+                    (criterion, user_id),
+                    ("api-version", API_VERSION),
+                ]),
+        )
+        .await?;
+    let raw: RawList<RawPullRequest> = read_json(resp).await?;
+    Ok(raw.value)
+}
+
+/// The user's role on a PR and their vote. A PR the user neither created
+/// nor reviews yields `None`.
+fn involvement(
+    pr: &RawPullRequest,
+    user_id: &str,
+) -> Option<(PullRequestRole, Option<ReviewVote>)> {
+    let is_me = |id: &Option<String>| {
+        id.as_deref()
+            .is_some_and(|id| id.eq_ignore_ascii_case(user_id))
+    };
+    let review = pr.reviewers.iter().find(|r| is_me(&r.id));
+    let vote = review.map(|r| ReviewVote::from_code(r.vote));
+
+    if pr.created_by.as_ref().is_some_and(|c| is_me(&c.id)) {
+        Some((PullRequestRole::Author, vote))
+    } else {
+        review.map(|r| {
+            let role = if r.is_required {
+                PullRequestRole::RequiredReviewer
+            } else {
+                PullRequestRole::Reviewer
+            };
+            (role, vote)
+        })
     }
+}
 
-    let raw: Vec<RawPullRequest> = serde_json::from_slice(&output.stdout)
-        .map_err(|e| format!("Failed to parse pull requests: {}", e))?;
+/// Active pull requests in a project that the signed-in user, `user_id` in
+/// that organization, created or is asked to review
+pub async fn fetch_pull_requests(
+    http: &HttpClient,
+    org: &str,
+    project: &str,
+    token: &str,
+    user_id: &str,
+) -> AuraResult<Vec<AzurePullRequest>> {
+    let (created, reviewing) = futures::try_join!(
+        search_pull_requests(
+            http,
+            org,
+            project,
+            token,
+            "searchCriteria.creatorId",
+            user_id
+        ),
+        search_pull_requests(
+            http,
+            org,
+            project,
+            token,
+            "searchCriteria.reviewerId",
+            user_id
+        ),
+    )?;
 
-    let prs = raw
+    let mut seen = std::collections::HashSet::new();
+    let prs = created
         .into_iter()
-        .map(|r| {
+        .chain(reviewing)
+        .filter(|r| seen.insert(r.pull_request_id))
+        .filter_map(|r| {
+            let (role, vote) = involvement(&r, user_id)?;
             let repo_name = r
                 .repository
                 .as_ref()
                 .and_then(|repo| repo.name.clone())
                 .unwrap_or_default();
 
-            AzurePullRequest {
+            Some(AzurePullRequest {
                 id: r.pull_request_id,
                 title: r.title.unwrap_or_default(),
                 status: r.status.unwrap_or_default(),
@@ -335,16 +831,371 @@ pub fn azure_fetch_prs() -> Result<Vec<AzurePullRequest>, String> {
                     .unwrap_or_default(),
                 creation_date: r.creation_date.unwrap_or_default(),
                 url: pr_web_url(org, project, &repo_name, r.pull_request_id),
-            }
+                role,
+                vote,
+                organization: org_name(org),
+                project: project.to_string(),
+            })
         })
         .collect();
 
     Ok(prs)
 }
 
+impl<T> AcrossProjects<T> {
+    /// Pair each target with its result
+    fn gather(targets: &[(String, String)], results: Vec<AuraResult<Vec<T>>>) -> AuraResult<Self> {
+        let mut items = Vec::new();
+        let mut failures = Vec::new();
+        for ((org, project), result) in targets.iter().zip(results) {
+            match result {
+                Ok(found) => items.extend(found),
+                Err(error) => failures.push(ProjectFailure {
+                    organization: org_name(org),
+                    project: project.clone(),
+                    error,
+                }),
+            }
+        }
+        // Nothing reachable at all, e.g. signed out: that is the connection failing
+        if !failures.is_empty() && failures.len() == targets.len() {
+            return Err(failures.swap_remove(0).error);
+        }
+        Ok(AcrossProjects { items, failures })
+    }
+
+    fn into_fetched(self, to_item: impl Fn(T) -> WorkItem) -> Fetched {
+        Fetched {
+            items: self.items.into_iter().map(to_item).collect(),
+            failures: self
+                .failures
+                .into_iter()
+                .map(|f| (format!("{}/{}", f.organization, f.project), f.error))
+                .collect(),
+        }
+    }
+}
+
+/// Work items matching `wiql` in every organization/project pair, queried
+/// concurrently
+pub async fn fetch_work_items_across(
+    http: &HttpClient,
+    targets: &[(String, String)],
+    token: &str,
+    wiql: &str,
+) -> AuraResult<AcrossProjects<AzureWorkItem>> {
+    let results = futures::future::join_all(
+        targets
+            .iter()
+            .map(|(org, project)| query_work_items(http, org, project, token, wiql)),
+    )
+    .await;
+    AcrossProjects::gather(targets, results)
+}
+
+/// Pull requests from every organization/project pair, queried concurrently
+pub async fn fetch_pull_requests_across(
+    http: &HttpClient,
+    targets: &[(String, String)],
+    token: &str,
+) -> AuraResult<AcrossProjects<AzurePullRequest>> {
+    // The user's id only depends on the organization, so look it up once
+    // per organization rather than once per project
+    let mut orgs: Vec<&str> = targets.iter().map(|(org, _)| org.as_str()).collect();
+    orgs.sort_unstable();
+    orgs.dedup();
+    let ids =
+        futures::future::join_all(orgs.iter().map(|org| fetch_user_id(http, org, token))).await;
+    let user_ids: HashMap<&str, AuraResult<String>> = orgs.into_iter().zip(ids).collect();
+
+    let results = futures::future::join_all(targets.iter().map(|(org, project)| {
+        let user_id = &user_ids[org.as_str()];
+        async move {
+            match user_id {
+                Ok(user_id) => fetch_pull_requests(http, org, project, token, user_id).await,
+                Err(e) => Err(e.clone()),
+            }
+        }
+    }))
+    .await;
+    AcrossProjects::gather(targets, results)
+}
+
+// -- Provider --
+
+fn work_item_to_item(connection_id: &str, wi: AzureWorkItem) -> WorkItem {
+    let mut item = WorkItem::new(
+        connection_id,
+        ProviderKind::AzureDevops,
+        WorkItemKind::Issue,
+        // Ids are only unique within an organization
+        format!("{}/{}", wi.organization, wi.id),
+        wi.title,
+        wi.url,
+    )
+    .with_extra("work_item_id", wi.id)
+    .with_extra("organization", wi.organization);
+
+    item.status_category = StatusCategory::from_state_name(&wi.state);
+    item.status = wi.state;
+    item.item_type = Some(wi.work_item_type);
+    item.assignee = wi.assigned_to;
+    item.labels = wi.tags;
+    item.project = wi.project;
+    item.updated_at = normalize_timestamp(&wi.changed_date);
+    item
+}
+
+fn pull_request_to_item(connection_id: &str, pr: AzurePullRequest) -> WorkItem {
+    let mut item = WorkItem::new(
+        connection_id,
+        ProviderKind::AzureDevops,
+        WorkItemKind::PullRequest,
+        format!("{}/{}!{}", pr.organization, pr.repository, pr.id),
+        pr.title,
+        pr.url,
+    )
+    .with_extra("pull_request_id", pr.id)
+    .with_extra("repository", pr.repository.clone())
+    .with_extra("source_branch", pr.source_branch)
+    .with_extra("target_branch", pr.target_branch)
+    .with_extra("role", pr.role.as_str())
+    .with_extra("vote", pr.vote.map(|v| v.as_str()))
+    .with_extra("organization", pr.organization)
+    .with_extra("azure_project", pr.project);
+
+    item.status_category = match pr.status.as_str() {
+        "active" => StatusCategory::Open,
+        _ => StatusCategory::Closed,
+    };
+    item.status = pr.status;
+    item.author = Some(pr.created_by);
+    item.project = pr.repository;
+    item.created_at = Some(normalize_timestamp(&pr.creation_date));
+    // The PR list has no last-updated field; creation is the best we have
+    item.updated_at = normalize_timestamp(&pr.creation_date);
+    item
+}
+
+pub struct AzureProvider {
+    http: HttpClient,
+    pool: sqlx::SqlitePool,
+    connection: Connection,
+}
+
+impl AzureProvider {
+    pub fn new(http: HttpClient, pool: sqlx::SqlitePool, connection: Connection) -> Self {
+        AzureProvider {
+            http,
+            pool,
+            connection,
+        }
+    }
+
+    /// Organization URL and numeric id of one of this connection's items
+    async fn locate(&self, item: &WorkItem) -> AuraResult<(String, u64)> {
+        let id = item
+            .extra
+            .get("work_item_id")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| AuraError::invalid_input("Not an Azure DevOps work item."))?;
+        let org_of_item = item
+            .extra
+            .get("organization")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let targets = targets_for(&self.pool, &self.connection.id).await?;
+        let (org, _) = targets
+            .into_iter()
+            .find(|(org, _)| org_name(org) == org_of_item)
+            .ok_or_else(|| {
+                AuraError::not_configured(format!(
+                    "Organization {} is no longer on this connection.",
+                    org_of_item
+                ))
+            })?;
+        Ok((org, id))
+    }
+}
+
+#[async_trait]
+impl Provider for AzureProvider {
+    fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    async fn check_auth(&self) -> AuraResult<ProviderStatus> {
+        let authenticated = match access_token().await {
+            Ok(_) => true,
+            Err(AuraError::NotAuthenticated { .. }) => false,
+            Err(e) => return Err(e),
+        };
+        let mut orgs: Vec<String> = targets_for(&self.pool, &self.connection.id)
+            .await
+            .unwrap_or_default()
+            .iter()
+            .map(|(org, _)| org_name(org))
+            .collect();
+        orgs.dedup();
+        Ok(ProviderStatus {
+            connection_id: self.connection.id.clone(),
+            provider: ProviderKind::AzureDevops,
+            authenticated,
+            account: (!orgs.is_empty()).then(|| orgs.join(", ")),
+        })
+    }
+
+    async fn fetch_assigned(&self) -> AuraResult<Fetched> {
+        let targets = targets_for(&self.pool, &self.connection.id).await?;
+        let token = access_token().await?;
+        let items = fetch_work_items_across(&self.http, &targets, &token, ASSIGNED_WIQL).await?;
+        Ok(items.into_fetched(|wi| work_item_to_item(&self.connection.id, wi)))
+    }
+
+    async fn search(&self, query: &str) -> AuraResult<Fetched> {
+        let targets = targets_for(&self.pool, &self.connection.id).await?;
+        let token = access_token().await?;
+        let items = fetch_work_items_across(&self.http, &targets, &token, query).await?;
+        Ok(items.into_fetched(|wi| work_item_to_item(&self.connection.id, wi)))
+    }
+
+    async fn fetch_pull_requests(&self) -> AuraResult<Fetched> {
+        let targets = targets_for(&self.pool, &self.connection.id).await?;
+        let token = access_token().await?;
+        let prs = fetch_pull_requests_across(&self.http, &targets, &token).await?;
+        Ok(prs.into_fetched(|pr| pull_request_to_item(&self.connection.id, pr)))
+    }
+
+    async fn fetch_details(&self, item: &WorkItem) -> AuraResult<WorkItemDetails> {
+        let (org, id) = self.locate(item).await?;
+        let token = access_token().await?;
+        fetch_work_item_details(&self.http, &org, &token, id).await
+    }
+
+    /// Azure DevOps tracks time as a running total of hours, so the entry
+    /// is added to Completed Work; its start time and comment aren't kept
+    async fn log_work(&self, item: &WorkItem, entry: &WorkLogEntry) -> AuraResult<Option<String>> {
+        let (org, id) = self.locate(item).await?;
+        let token = access_token().await?;
+        let hours = entry.seconds as f64 / 3600.0;
+        let rev = add_completed_work(&self.http, &org, &token, id, hours).await?;
+        Ok(Some(rev))
+    }
+}
+
+// -- Tauri commands --
+
+#[tauri::command]
+pub async fn check_azure_auth() -> AuraResult<AzureAuthStatus> {
+    tauri::async_runtime::spawn_blocking(resolve_auth)
+        .await
+        .map_err(AuraError::io)
+}
+
+/// Work items from every project of a connection
+#[tauri::command]
+pub async fn azure_fetch_work_items(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<AcrossProjects<AzureWorkItem>> {
+    let connection = connection_for(&app, &connection_id, ProviderKind::AzureDevops).await?;
+    let pool = crate::db::pool(&app).await?;
+    let targets = targets_for(&pool, &connection.id).await?;
+    let token = access_token().await?;
+    fetch_work_items_across(&http, &targets, &token, ASSIGNED_WIQL).await
+}
+
+/// Pull requests from every project of a connection
+#[tauri::command]
+pub async fn azure_fetch_prs(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<AcrossProjects<AzurePullRequest>> {
+    let connection = connection_for(&app, &connection_id, ProviderKind::AzureDevops).await?;
+    let pool = crate::db::pool(&app).await?;
+    let targets = targets_for(&pool, &connection.id).await?;
+    let token = access_token().await?;
+    fetch_pull_requests_across(&http, &targets, &token).await
+}
+
+#[tauri::command]
+pub async fn list_azure_projects(
+    app: AppHandle,
+    connection_id: String,
+) -> AuraResult<Vec<AzureProject>> {
+    let pool = crate::db::pool(&app).await?;
+    load_projects(&pool, &connection_id).await
+}
+
+/// Add an organization/project pair to a connection. The organization may
+/// be a name or a URL.
+#[tauri::command]
+pub async fn add_azure_project(
+    app: AppHandle,
+    connection_id: String,
+    organization: String,
+    project: String,
+) -> AuraResult<AzureProject> {
+    let project = project.trim().to_string();
+    if organization.trim().is_empty() || project.is_empty() {
+        return Err(AuraError::invalid_input(
+            "Organization and project are required.",
+        ));
+    }
+    let connection = connection_for(&app, &connection_id, ProviderKind::AzureDevops).await?;
+    let pool = crate::db::pool(&app).await?;
+    let organization = org_url(&organization);
+
+    let exists = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM azure_projects WHERE connection_id = ? AND organization = ? AND project = ?",
+    )
+    .bind(&connection.id)
+    .bind(&organization)
+    .bind(&project)
+    .fetch_one(&pool)
+    .await?;
+    if exists > 0 {
+        return Err(AuraError::invalid_input(format!(
+            "{} is already added.",
+            project
+        )));
+    }
+
+    let id = crate::db::new_id();
+    sqlx::query(
+        "INSERT INTO azure_projects (id, connection_id, organization, project) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&connection.id)
+    .bind(&organization)
+    .bind(&project)
+    .execute(&pool)
+    .await?;
+
+    Ok(AzureProject {
+        id,
+        connection_id: connection.id,
+        organization,
+        project,
+    })
+}
+
+#[tauri::command]
+pub async fn remove_azure_project(app: AppHandle, id: String) -> AuraResult<()> {
+    let pool = crate::db::pool(&app).await?;
+    sqlx::query("DELETE FROM azure_projects WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{self, Requests};
 
     #[test]
     fn test_strip_ref_prefix() {
@@ -354,9 +1205,225 @@ mod tests {
     }
 
     #[test]
-    fn test_resolve_auth_returns_status() {
-        let status = resolve_auth();
-        // Should run without panicking regardless of az being installed
-        assert!(status.cli_available || !status.cli_available);
+    fn test_parse_devops_defaults() {
+        let output = "[defaults]\norganization = https://dev.azure.com/contoso\nproject = Web\n";
+        assert_eq!(
+            parse_devops_defaults(output),
+            (
+                Some("https://dev.azure.com/contoso".to_string()),
+                Some("Web".to_string())
+            )
+        );
+        let unset = "[defaults]\norganization = https://dev.azure.com/contoso\nproject = None\n";
+        assert_eq!(parse_devops_defaults(unset).1, None);
+        assert_eq!(parse_devops_defaults(""), (None, None));
+    }
+
+    #[test]
+    fn test_parse_access_token_expiry() {
+        let token = parse_access_token(
+            br#"{"accessToken": "abc", "expiresOn": "2024-03-01 11:00:00.000000", "expires_on": 1709290800, "tokenType": "Bearer"}"#,
+        )
+        .unwrap();
+        assert_eq!(token.token, "abc");
+        assert_eq!(token.expires_at.timestamp(), 1709290800);
+
+        // Older az versions only report local time
+        let token = parse_access_token(
+            br#"{"accessToken": "abc", "expiresOn": "2024-03-01 11:00:00.000000"}"#,
+        )
+        .unwrap();
+        let local = token.expires_at.with_timezone(&Local);
+        assert_eq!(
+            local.format("%Y-%m-%d %H:%M").to_string(),
+            "2024-03-01 11:00"
+        );
+    }
+
+    #[test]
+    fn test_org_name_from_url() {
+        assert_eq!(org_name("https://dev.azure.com/contoso"), "contoso");
+        assert_eq!(org_name("https://contoso.visualstudio.com"), "contoso");
+        assert_eq!(org_name("http://127.0.0.1:8080/contoso"), "contoso");
+    }
+
+    #[test]
+    fn test_org_url_accepts_name_or_url() {
+        assert_eq!(org_url("contoso"), "https://dev.azure.com/contoso");
+        assert_eq!(
+            org_url("https://dev.azure.com/contoso/"),
+            "https://dev.azure.com/contoso"
+        );
+    }
+
+    /// Serve canned JSON bodies on a local port, picked by path prefix
+    fn mock_server(routes: Vec<(&'static str, &'static str)>) -> String {
+        mock_server_logged(routes).0
+    }
+
+    /// Like `mock_server`, also returning the requests received so far
+    fn mock_server_logged(routes: Vec<(&'static str, &'static str)>) -> (String, Requests) {
+        let (url, requests) = test_support::mock_server(move |request| {
+            let authorized = request.header("authorization") == Some("Bearer test-token");
+            match routes.iter().find(|(p, _)| request.path.starts_with(p)) {
+                _ if !authorized => (401, "{}".to_string()),
+                Some((_, body)) => (200, body.to_string()),
+                None => (404, "{}".to_string()),
+            }
+        });
+        (format!("{}/contoso", url), requests)
+    }
+
+    #[test]
+    fn test_fetch_work_items_from_rest() {
+        let org = mock_server(vec![
+            (
+                "/contoso/Web/_apis/wit/wiql",
+                r#"{"workItems": [{"id": 7, "url": ""}]}"#,
+            ),
+            (
+                "/contoso/Web/_apis/wit/workitems",
+                r#"{"value": [{"id": 7, "fields": {
+                    "System.Title": "Fix login",
+                    "System.State": "Active",
+                    "System.WorkItemType": "Bug",
+                    "System.AssignedTo": {"displayName": "Ada"},
+                    "System.ChangedDate": "2024-03-01T10:00:00.123Z",
+                    "System.Tags": "auth; web"
+                }}]}"#,
+            ),
+        ]);
+        let http = HttpClient::new();
+
+        let items = tauri::async_runtime::block_on(query_work_items(
+            &http,
+            &org,
+            "Web",
+            "test-token",
+            ASSIGNED_WIQL,
+        ))
+        .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].title, "Fix login");
+        assert_eq!(items[0].assigned_to.as_deref(), Some("Ada"));
+        assert_eq!(items[0].tags, vec!["auth", "web"]);
+        assert!(items[0].url.ends_with("/Web/_workitems/edit/7"));
+        assert_eq!(items[0].organization, "contoso");
+        assert_eq!(items[0].project, "Web");
+
+        let err = tauri::async_runtime::block_on(query_work_items(
+            &http,
+            &org,
+            "Web",
+            "expired",
+            ASSIGNED_WIQL,
+        ))
+        .unwrap_err();
+        assert!(matches!(err, AuraError::NotAuthenticated { .. }));
+    }
+
+    #[test]
+    fn test_one_failing_project_does_not_hide_the_others() {
+        let org = mock_server(vec![
+            (
+                "/contoso/Web/_apis/wit/wiql",
+                r#"{"workItems": [{"id": 7, "url": ""}]}"#,
+            ),
+            (
+                "/contoso/Web/_apis/wit/workitems",
+                r#"{"value": [{"id": 7, "fields": {"System.Title": "Fix login"}}]}"#,
+            ),
+        ]);
+        let http = HttpClient::new();
+        let targets = vec![
+            (org.clone(), "Web".to_string()),
+            (org.clone(), "Locked".to_string()),
+        ];
+
+        let found = tauri::async_runtime::block_on(fetch_work_items_across(
+            &http,
+            &targets,
+            "test-token",
+            ASSIGNED_WIQL,
+        ))
+        .unwrap();
+        assert_eq!(found.items.len(), 1);
+        assert_eq!(found.items[0].title, "Fix login");
+        assert_eq!(found.failures.len(), 1);
+        assert_eq!(found.failures[0].project, "Locked");
+        assert_eq!(found.failures[0].organization, "contoso");
+
+        // Every project failing is the connection failing
+        let err = tauri::async_runtime::block_on(fetch_work_items_across(
+            &http,
+            &targets,
+            "expired",
+            ASSIGNED_WIQL,
+        ))
+        .unwrap_err();
+        assert!(matches!(err, AuraError::NotAuthenticated { .. }));
+    }
+
+    #[test]
+    fn test_fetch_pull_requests_from_rest() {
+        // The mock ignores search criteria, so both searches see both PRs
+        let (org, requested) = mock_server_logged(vec![
+            (
+                "/contoso/_apis/connectionData",
+                r#"{"authenticatedUser": {"id": "me-1", "providerDisplayName": "Ada"}}"#,
+            ),
+            (
+                "/contoso/Web/_apis/git/pullrequests",
+                r#"{"value": [{
+                    "pullRequestId": 42,
+                    "title": "Add cache",
+                    "status": "active",
+                    "createdBy": {"id": "me-1", "displayName": "Ada"},
+                    "repository": {"name": "app"},
+                    "sourceRefName": "refs/heads/cache",
+                    "targetRefName": "refs/heads/main",
+                    "creationDate": "2024-03-01T10:00:00Z",
+                    "reviewers": [{"id": "other", "vote": 10}]
+                }, {
+                    "pullRequestId": 43,
+                    "title": "Fix build",
+                    "status": "active",
+                    "createdBy": {"id": "other", "displayName": "Grace"},
+                    "repository": {"name": "app"},
+                    "creationDate": "2024-03-01T11:00:00Z",
+                    "reviewers": [{"id": "ME-1", "vote": -5, "isRequired": true}]
+                }], "count": 2}"#,
+            ),
+        ]);
+        let http = HttpClient::new();
+
+        let targets = vec![
+            (org.clone(), "Web".to_string()),
+            (org.clone(), "Api".to_string()),
+        ];
+        let found = tauri::async_runtime::block_on(fetch_pull_requests_across(
+            &http,
+            &targets,
+            "test-token",
+        ))
+        .unwrap();
+        // Api has no pull requests route, so only Web's come back
+        assert_eq!(found.failures.len(), 1);
+        // One user lookup for the organization, not one per project
+        let lookups = requested
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.path.contains("connectionData"))
+            .count();
+        assert_eq!(lookups, 1);
+        let prs = found.items;
+        assert_eq!(prs.len(), 2);
+        assert_eq!(prs[0].source_branch, "cache");
+        assert_eq!(prs[0].url, format!("{}/Web/_git/app/pullrequest/42", org));
+        assert_eq!(prs[0].role, PullRequestRole::Author);
+        assert_eq!(prs[0].vote, None);
+        assert_eq!(prs[1].role, PullRequestRole::RequiredReviewer);
+        assert_eq!(prs[1].vote, Some(ReviewVote::WaitingForAuthor));
     }
 }
@@ -0,0 +1,500 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::provider::{ProviderKind, ProviderStatus};
+
+// -- Types --
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    /// `gh` or `az` on the user's machine
+    Cli,
+    /// GitHub or Jira Server / Data Center personal access token
+    Pat,
+    /// Jira Cloud or FogBugz API token
+    ApiToken,
+    /// FogBugz password
+    Password,
+}
+
+impl AuthMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthMethod::Cli => "cli",
+            AuthMethod::Pat => "pat",
+            AuthMethod::ApiToken => "api_token",
+            AuthMethod::Password => "password",
+        }
+    }
+
+    /// Whether the connection keeps a secret in the keychain
+    pub fn needs_secret(&self) -> bool {
+        !matches!(self, AuthMethod::Cli)
+    }
+}
+
+impl std::str::FromStr for AuthMethod {
+    type Err = AuraError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cli" => Ok(AuthMethod::Cli),
+            "pat" => Ok(AuthMethod::Pat),
+            "api_token" => Ok(AuthMethod::ApiToken),
+            "password" => Ok(AuthMethod::Password),
+            _ => Err(AuraError::invalid_input(format!(
+                "Unknown auth method: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Non-secret, provider-specific settings, stored as JSON. Only the fields
+/// that apply to the connection's provider are set.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ConnectionConfig {
+    /// Jira or FogBugz site
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_url: Option<String>,
+    /// Jira or FogBugz login
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// GitHub Enterprise Server hostname; github.com if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// Most Jira issues to fetch; `jira::DEFAULT_MAX_RESULTS` if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_results: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Connection {
+    pub id: String,
+    pub provider: ProviderKind,
+    pub label: String,
+    pub auth_method: AuthMethod,
+    /// Account name, filled in when the connection is tested
+    pub username: Option<String>,
+    pub config: ConnectionConfig,
+    /// Whether a secret is stored in the keychain; the secret itself never
+    /// leaves the Rust side. Only filled in for connections returned to the
+    /// frontend.
+    pub has_secret: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Connection {
+    /// The token or password stored in the keychain for this connection
+    pub fn secret(&self) -> AuraResult<String> {
+        crate::credentials::load_secret(&self.id)?.ok_or_else(|| {
+            AuraError::not_configured(format!("No credentials saved for {}.", self.label))
+        })
+    }
+
+    /// A config field the provider cannot work without
+    pub fn required(&self, value: &Option<String>, name: &str) -> AuraResult<String> {
+        value
+            .clone()
+            .filter(|v| !v.trim().is_empty())
+            .ok_or_else(|| AuraError::not_configured(format!("{} has no {}.", self.label, name)))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewConnection {
+    pub provider: ProviderKind,
+    pub label: String,
+    pub auth_method: AuthMethod,
+    #[serde(default)]
+    pub config: ConnectionConfig,
+    /// Token or password; goes to the keychain, never to the database
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct ConnectionUpdate {
+    pub label: Option<String>,
+    pub config: Option<ConnectionConfig>,
+    /// Replaces the stored secret; `None` keeps it
+    pub secret: Option<String>,
+}
+
+// -- Validation --
+
+fn allowed_auth_methods(provider: ProviderKind) -> &'static [AuthMethod] {
+    match provider {
+        ProviderKind::Github => &[AuthMethod::Cli, AuthMethod::Pat],
+        ProviderKind::Jira => &[AuthMethod::ApiToken, AuthMethod::Pat],
+        ProviderKind::Fogbugz => &[AuthMethod::Password, AuthMethod::ApiToken],
+        ProviderKind::AzureDevops => &[AuthMethod::Cli],
+    }
+}
+
+fn validate(
+    provider: ProviderKind,
+    auth_method: AuthMethod,
+    label: &str,
+    config: &ConnectionConfig,
+) -> AuraResult<()> {
+    if label.trim().is_empty() {
+        return Err(AuraError::invalid_input("A connection needs a name."));
+    }
+    if !allowed_auth_methods(provider).contains(&auth_method) {
+        return Err(AuraError::invalid_input(format!(
+            "{} connections cannot use {} authentication.",
+            provider.as_str(),
+            auth_method.as_str()
+        )));
+    }
+    let blank = |v: &Option<String>| v.as_deref().is_none_or(|v| v.trim().is_empty());
+    if matches!(provider, ProviderKind::Jira | ProviderKind::Fogbugz) {
+        // Jira Server / Data Center PATs and FogBugz API tokens identify
+        // the user on their own
+        let needs_email = !matches!(
+            (provider, auth_method),
+            (ProviderKind::Jira, AuthMethod::Pat) | (ProviderKind::Fogbugz, AuthMethod::ApiToken)
+        );
+        if blank(&config.instance_url) || (needs_email && blank(&config.email)) {
+            return Err(AuraError::invalid_input(if needs_email {
+                "Instance URL and email are required."
+            } else {
+                "Instance URL is required."
+            }));
+        }
+    }
+    if config.host.is_some() && blank(&config.host) {
+        return Err(AuraError::invalid_input("The GitHub host is empty."));
+    }
+    if config.max_results == Some(0) {
+        return Err(AuraError::invalid_input(
+            "The issue limit must be at least 1.",
+        ));
+    }
+    Ok(())
+}
+
+/// A secret that isn't blank, stored exactly as entered: spaces can be part
+/// of a password
+fn non_empty(secret: Option<String>) -> Option<String> {
+    secret.filter(|s| !s.trim().is_empty())
+}
+
+// -- Storage --
+
+type ConnectionRow = (
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    String,
+    String,
+    String,
+);
+
+const SELECT_CONNECTIONS: &str = "SELECT id, provider, label, auth_method, username, config, created_at, updated_at FROM connections";
+
+fn from_row(row: ConnectionRow) -> AuraResult<Connection> {
+    let (id, provider, label, auth_method, username, config, created_at, updated_at) = row;
+    let auth_method: AuthMethod = auth_method.parse()?;
+    Ok(Connection {
+        provider: provider.parse()?,
+        config: serde_json::from_str(&config)?,
+        id,
+        label,
+        auth_method,
+        username,
+        has_secret: false,
+        created_at,
+        updated_at,
+    })
+}
+
+/// Move secrets that versions before the keychain kept in the settings
+/// table. Runs once at startup.
+pub async fn migrate_legacy_secrets(pool: &SqlitePool) -> AuraResult<()> {
+    crate::credentials::migrate_setting(pool, "jira_api_token", "jira").await?;
+    crate::credentials::migrate_setting(pool, "fogbugz_password", "fogbugz").await
+}
+
+/// Fill in `has_secret` for connections shown to the user. Reading the
+/// keychain can block on a system prompt, so it happens off the async
+/// runtime, and only here rather than on every load.
+async fn with_secret_flags(mut connections: Vec<Connection>) -> AuraResult<Vec<Connection>> {
+    tauri::async_runtime::spawn_blocking(move || {
+        for connection in &mut connections {
+            // A keychain that cannot be read shows up when the connection
+            // is used; listing should still work.
+            connection.has_secret = connection.auth_method.needs_secret()
+                && crate::credentials::load_secret(&connection.id)
+                    .ok()
+                    .flatten()
+                    .is_some();
+        }
+        connections
+    })
+    .await
+    .map_err(AuraError::io)
+}
+
+async fn with_secret_flag(connection: Connection) -> AuraResult<Connection> {
+    let mut flagged = with_secret_flags(vec![connection]).await?;
+    Ok(flagged.remove(0))
+}
+
+pub async fn load_connections(pool: &SqlitePool) -> AuraResult<Vec<Connection>> {
+    let rows = sqlx::query_as::<_, ConnectionRow>(&format!(
+        "{} ORDER BY provider, created_at",
+        SELECT_CONNECTIONS
+    ))
+    .fetch_all(pool)
+    .await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+pub async fn load_connection(pool: &SqlitePool, id: &str) -> AuraResult<Connection> {
+    let row = sqlx::query_as::<_, ConnectionRow>(&format!("{} WHERE id = ?", SELECT_CONNECTIONS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AuraError::invalid_input(format!("Unknown connection: {}", id)))?;
+    from_row(row)
+}
+
+/// Load a connection for a provider-specific command, rejecting ids that
+/// belong to another provider
+pub async fn connection_for(
+    app: &AppHandle,
+    id: &str,
+    provider: ProviderKind,
+) -> AuraResult<Connection> {
+    let pool = crate::db::pool(app).await?;
+    let connection = load_connection(&pool, id).await?;
+    if connection.provider != provider {
+        return Err(AuraError::invalid_input(format!(
+            "{} is not a {} connection",
+            connection.label,
+            provider.as_str()
+        )));
+    }
+    Ok(connection)
+}
+
+// -- Tauri commands --
+
+#[tauri::command]
+pub async fn list_connections(app: AppHandle) -> AuraResult<Vec<Connection>> {
+    let pool = crate::db::pool(&app).await?;
+    with_secret_flags(load_connections(&pool).await?).await
+}
+
+#[tauri::command]
+pub async fn create_connection(
+    app: AppHandle,
+    connection: NewConnection,
+) -> AuraResult<Connection> {
+    validate(
+        connection.provider,
+        connection.auth_method,
+        &connection.label,
+        &connection.config,
+    )?;
+    let secret = non_empty(connection.secret);
+    if connection.auth_method.needs_secret() && secret.is_none() {
+        return Err(AuraError::invalid_input("A token or password is required."));
+    }
+
+    let pool = crate::db::pool(&app).await?;
+    let id = crate::db::new_id();
+    if let Some(secret) = &secret {
+        crate::credentials::store_secret(&id, secret)?;
+    }
+    sqlx::query(
+        "INSERT INTO connections (id, provider, label, auth_method, config) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(connection.provider.as_str())
+    .bind(connection.label.trim())
+    .bind(connection.auth_method.as_str())
+    .bind(serde_json::to_string(&connection.config)?)
+    .execute(&pool)
+    .await?;
+
+    with_secret_flag(load_connection(&pool, &id).await?).await
+}
+
+#[tauri::command]
+pub async fn update_connection(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    id: String,
+    update: ConnectionUpdate,
+) -> AuraResult<Connection> {
+    let pool = crate::db::pool(&app).await?;
+    let existing = load_connection(&pool, &id).await?;
+    if existing.provider == ProviderKind::Fogbugz
+        && (update.config.is_some() || update.secret.is_some())
+    {
+        // The session belongs to the old site or login
+        crate::fogbugz::logoff(&http, &existing).await;
+    }
+    let label = update.label.unwrap_or(existing.label);
+    let config = update.config.unwrap_or(existing.config);
+    validate(existing.provider, existing.auth_method, &label, &config)?;
+
+    if let Some(secret) = non_empty(update.secret) {
+        if existing.auth_method.needs_secret() {
+            crate::credentials::store_secret(&id, &secret)?;
+        }
+    }
+    sqlx::query(
+        "UPDATE connections SET label = ?, config = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(label.trim())
+    .bind(serde_json::to_string(&config)?)
+    .bind(&id)
+    .execute(&pool)
+    .await?;
+
+    with_secret_flag(load_connection(&pool, &id).await?).await
+}
+
+/// Remove a connection with its secret, cached items and sync schedule
+#[tauri::command]
+pub async fn delete_connection(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    id: String,
+) -> AuraResult<()> {
+    let pool = crate::db::pool(&app).await?;
+    let existing = load_connection(&pool, &id).await?;
+    if existing.provider == ProviderKind::Fogbugz {
+        crate::fogbugz::logoff(&http, &existing).await;
+    }
+    let mut tx = pool.begin().await?;
+    for sql in [
+        "DELETE FROM work_items WHERE connection_id = ?",
+        "DELETE FROM connection_sync WHERE connection_id = ?",
+        "DELETE FROM azure_projects WHERE connection_id = ?",
+        "DELETE FROM saved_queries WHERE connection_id = ?",
+        "DELETE FROM links WHERE substr(work_item_id, 1, length(?1) + 1) = ?1 || ':'",
+        "DELETE FROM time_adjustments WHERE substr(work_item_id, 1, length(?1) + 1) = ?1 || ':'",
+        "DELETE FROM worklog_exports WHERE substr(work_item_id, 1, length(?1) + 1) = ?1 || ':'",
+        "DELETE FROM connections WHERE id = ?",
+    ] {
+        sqlx::query(sql).bind(&id).execute(&mut *tx).await?;
+    }
+    tx.commit().await?;
+    crate::credentials::delete_secret(&id)
+}
+
+/// Check a connection's credentials and remember the account name
+#[tauri::command]
+pub async fn test_connection(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    id: String,
+) -> AuraResult<ProviderStatus> {
+    let pool = crate::db::pool(&app).await?;
+    let connection = load_connection(&pool, &id).await?;
+    let status = crate::provider::provider_for(&http, &pool, connection)
+        .check_auth()
+        .await?;
+
+    if let Some(account) = status.account.as_deref().filter(|_| status.authenticated) {
+        sqlx::query("UPDATE connections SET username = ? WHERE id = ?")
+            .bind(account)
+            .bind(&id)
+            .execute(&pool)
+            .await?;
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_auth_method_per_provider() {
+        let config = ConnectionConfig::default();
+        assert!(validate(ProviderKind::Github, AuthMethod::Pat, "Work", &config).is_ok());
+        assert!(validate(ProviderKind::AzureDevops, AuthMethod::Pat, "Org", &config).is_err());
+        assert!(validate(ProviderKind::Github, AuthMethod::Cli, " ", &config).is_err());
+    }
+
+    #[test]
+    fn test_validate_requires_site_for_jira() {
+        let mut config = ConnectionConfig {
+            instance_url: Some("https://acme.atlassian.net".into()),
+            ..Default::default()
+        };
+        assert!(validate(ProviderKind::Jira, AuthMethod::ApiToken, "Client", &config).is_err());
+        config.email = Some("me@acme.com".into());
+        assert!(validate(ProviderKind::Jira, AuthMethod::ApiToken, "Client", &config).is_ok());
+        assert!(validate(ProviderKind::Jira, AuthMethod::Pat, "Server", &config).is_ok());
+        config.email = None;
+        assert!(validate(ProviderKind::Jira, AuthMethod::Pat, "Server", &config).is_ok());
+        config.max_results = Some(0);
+        assert!(validate(ProviderKind::Jira, AuthMethod::ApiToken, "Client", &config).is_err());
+    }
+
+    #[test]
+    fn test_validate_fogbugz_api_token_needs_no_email() {
+        let config = ConnectionConfig {
+            instance_url: Some("https://acme.fogbugz.com".into()),
+            ..Default::default()
+        };
+        assert!(validate(
+            ProviderKind::Fogbugz,
+            AuthMethod::ApiToken,
+            "Support",
+            &config
+        )
+        .is_ok());
+        assert!(validate(
+            ProviderKind::Fogbugz,
+            AuthMethod::Password,
+            "Support",
+            &config
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_config_round_trips_without_unset_fields() {
+        let config = ConnectionConfig {
+            host: Some("ghe.acme.com".into()),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(json, r#"{"host":"ghe.acme.com"}"#);
+        assert_eq!(
+            serde_json::from_str::<ConnectionConfig>(&json).unwrap(),
+            config
+        );
+        assert_eq!(
+            serde_json::from_str::<ConnectionConfig>("{}").unwrap(),
+            ConnectionConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_secrets_are_stored_as_entered() {
+        assert_eq!(non_empty(None), None);
+        assert_eq!(non_empty(Some(" \t".into())), None);
+        assert_eq!(
+            non_empty(Some(" pass word ".into())).as_deref(),
+            Some(" pass word ")
+        );
+    }
+}
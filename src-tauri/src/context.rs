@@ -0,0 +1,183 @@
+use sqlx::SqlitePool;
+
+use crate::error::AuraResult;
+use crate::http::HttpClient;
+use crate::links::{Link, LinkKind};
+use crate::provider::{provider_for, WorkItem, WorkItemDetails};
+
+/// A work item rendered for an AI coding tool
+pub struct WorkContext {
+    /// Native key, used to name the context file
+    pub key: String,
+    /// Markdown prompt
+    pub text: String,
+}
+
+/// Split an "Acceptance criteria" section off a Markdown description, for
+/// trackers that keep it in the body (GitHub). The section runs to the next
+/// heading.
+fn split_acceptance_criteria(description: &str) -> (String, Option<String>) {
+    let is_heading = |line: &str| {
+        let line = line.trim();
+        line.starts_with('#') || (line.starts_with("**") && line.ends_with("**"))
+    };
+    let lines: Vec<&str> = description.lines().collect();
+    let Some(start) = lines.iter().position(|l| {
+        let lower = l.to_lowercase();
+        lower.contains("acceptance criteria") && (is_heading(l) || lower.trim_end().ends_with(':'))
+    }) else {
+        return (description.trim().to_string(), None);
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| is_heading(l))
+        .map_or(lines.len(), |i| start + 1 + i);
+
+    let criteria = lines[start + 1..end].join("\n").trim().to_string();
+    let rest = [&lines[..start], &lines[end..]].concat().join("\n");
+    (
+        rest.trim().to_string(),
+        Some(criteria).filter(|c| !c.is_empty()),
+    )
+}
+
+fn render(item: &WorkItem, details: &WorkItemDetails, links: &[Link]) -> String {
+    let mut out = format!("# {}: {}\n\n", item.provider_id, item.title);
+
+    let mut facts = vec![];
+    if let Some(item_type) = &item.item_type {
+        facts.push(format!("Type: {}", item_type));
+    }
+    if !item.status.is_empty() {
+        facts.push(format!("Status: {}", item.status));
+    }
+    if let Some(priority) = &item.priority {
+        facts.push(format!("Priority: {}", priority));
+    }
+    if !item.project.is_empty() {
+        facts.push(format!("Project: {}", item.project));
+    }
+    if !item.labels.is_empty() {
+        facts.push(format!("Labels: {}", item.labels.join(", ")));
+    }
+    facts.push(format!("URL: {}", item.url));
+    for fact in facts {
+        out.push_str(&format!("- {}\n", fact));
+    }
+
+    let (description, criteria) = match &details.description {
+        Some(description) => split_acceptance_criteria(description),
+        None => (String::new(), None),
+    };
+    if !description.is_empty() {
+        out.push_str(&format!("\n## Description\n\n{}\n", description));
+    }
+    if let Some(criteria) = details.acceptance_criteria.as_ref().or(criteria.as_ref()) {
+        out.push_str(&format!("\n## Acceptance criteria\n\n{}\n", criteria));
+    }
+
+    let prs: Vec<&Link> = links
+        .iter()
+        .filter(|l| l.kind == LinkKind::PullRequest)
+        .collect();
+    if !prs.is_empty() {
+        out.push_str("\n## Linked pull requests\n\n");
+        for pr in prs {
+            let title = pr.target_title.as_deref().unwrap_or(&pr.target);
+            match &pr.target_url {
+                Some(url) => out.push_str(&format!("- {} ({})\n", title, url)),
+                None => out.push_str(&format!("- {}\n", title)),
+            }
+        }
+    }
+    let branches: Vec<&Link> = links
+        .iter()
+        .filter(|l| l.kind == LinkKind::Branch)
+        .collect();
+    if !branches.is_empty() {
+        out.push_str("\n## Branches\n\n");
+        for branch in branches {
+            out.push_str(&format!(
+                "- {} in {}\n",
+                branch.target,
+                branch.repo_path.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+
+    out.push_str(&format!(
+        "\nWork on {} in this repository. Start by reading the relevant code and propose a plan.\n",
+        item.provider_id
+    ));
+    out
+}
+
+/// Render a cached work item with its description, acceptance criteria and
+/// links. The description is fetched live; if the provider can't be
+/// reached the cached fields are used so the session still starts.
+pub async fn work_item_context(
+    http: &HttpClient,
+    pool: &SqlitePool,
+    work_item_id: &str,
+) -> AuraResult<WorkContext> {
+    let item = crate::sync::cached_work_item(pool, work_item_id).await?;
+    let fallback = WorkItemDetails {
+        description: item.description.clone(),
+        acceptance_criteria: None,
+    };
+    let details = match crate::connections::load_connection(pool, &item.connection_id).await {
+        Ok(connection) => provider_for(http, pool, connection)
+            .fetch_details(&item)
+            .await
+            .unwrap_or(fallback),
+        Err(_) => fallback,
+    };
+    let links = crate::links::query_links(pool, Some(&item.id), None).await?;
+
+    Ok(WorkContext {
+        key: item.provider_id.clone(),
+        text: render(&item, &details, &links),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{ProviderKind, WorkItemKind};
+
+    #[test]
+    fn test_split_acceptance_criteria() {
+        let body =
+            "Login fails.\n\n## Acceptance criteria\n- retries\n- shows error\n\n## Notes\nflaky";
+        let (rest, criteria) = split_acceptance_criteria(body);
+        assert_eq!(criteria.as_deref(), Some("- retries\n- shows error"));
+        assert_eq!(rest, "Login fails.\n\n## Notes\nflaky");
+
+        let (rest, criteria) = split_acceptance_criteria("No criteria here");
+        assert_eq!(rest, "No criteria here");
+        assert!(criteria.is_none());
+    }
+
+    #[test]
+    fn test_render_context() {
+        let mut item = WorkItem::new(
+            "c1",
+            ProviderKind::Jira,
+            WorkItemKind::Issue,
+            "PROJ-7".into(),
+            "Retry login".into(),
+            "https://acme.atlassian.net/browse/PROJ-7".into(),
+        );
+        item.labels = vec!["auth".into()];
+        let details = WorkItemDetails {
+            description: Some("Login fails on 502.".into()),
+            acceptance_criteria: Some("- retries twice".into()),
+        };
+        let text = render(&item, &details, &[]);
+        assert!(text.starts_with("# PROJ-7: Retry login\n"));
+        assert!(text.contains("- Labels: auth\n"));
+        assert!(text.contains("## Description\n\nLogin fails on 502.\n"));
+        assert!(text.contains("## Acceptance criteria\n\n- retries twice\n"));
+        assert!(!text.contains("## Linked pull requests"));
+    }
+}
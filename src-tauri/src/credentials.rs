@@ -0,0 +1,44 @@
+use sqlx::SqlitePool;
+
+use crate::error::AuraResult;
+
+const KEYRING_SERVICE: &str = "aura";
+
+// Secrets (API tokens, passwords, PATs) live in the OS keychain under the id
+// of the connection they belong to. They are read on the Rust side only and
+// never returned to the frontend.
+
+fn entry(connection_id: &str) -> AuraResult<keyring::Entry> {
+    Ok(keyring::Entry::new(KEYRING_SERVICE, connection_id)?)
+}
+
+pub fn load_secret(connection_id: &str) -> AuraResult<Option<String>> {
+    match entry(connection_id)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Save or replace the secret for a connection
+pub fn store_secret(connection_id: &str, secret: &str) -> AuraResult<()> {
+    Ok(entry(connection_id)?.set_password(secret)?)
+}
+
+/// Remove a connection's secret; a missing entry is not an error
+pub fn delete_secret(connection_id: &str) -> AuraResult<()> {
+    match entry(connection_id)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Move a secret that older versions kept in plain text in the `settings`
+/// table into the keychain
+pub async fn migrate_setting(pool: &SqlitePool, key: &str, connection_id: &str) -> AuraResult<()> {
+    if let Some(secret) = crate::db::get_setting(pool, key).await? {
+        store_secret(connection_id, &secret)?;
+        crate::db::delete_setting(pool, key).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_sql::{DbInstances, DbPool, Migration, MigrationKind};
+
+use crate::error::{AuraError, AuraResult};
+
+/// Connection string shared with the frontend's `Database.load`
+pub const DB_URL: &str = "sqlite:aura.db";
+
+/// Schema migrations, applied by tauri-plugin-sql when the database is loaded
+pub fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 1,
+            description: "create initial tables",
+            sql: include_str!("../migrations/001_init.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 2,
+            description: "create work item cache",
+            sql: include_str!("../migrations/002_work_item_cache.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 3,
+            description: "connections for all providers",
+            sql: include_str!("../migrations/003_connections.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "azure devops projects per connection",
+            sql: include_str!("../migrations/004_azure_projects.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "saved queries per connection",
+            sql: include_str!("../migrations/005_saved_queries.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "links between work items, branches, pull requests and repos",
+            sql: include_str!("../migrations/006_links.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "user-defined session tools",
+            sql: include_str!("../migrations/007_session_tools.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "launched sessions",
+            sql: include_str!("../migrations/008_sessions.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "time tracking and worklog exports",
+            sql: include_str!("../migrations/009_time_tracking.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "repo index",
+            sql: include_str!("../migrations/010_repos.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "scan root options",
+            sql: include_str!("../migrations/011_scan_root_options.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "session heartbeat and pending worklog exports",
+            sql: include_str!("../migrations/012_session_heartbeat.sql"),
+            kind: MigrationKind::Up,
+        },
+    ]
+}
+
+/// Borrow the pool opened by tauri-plugin-sql. The database is preloaded in
+/// tauri.conf.json, so migrations have already run by the time this is called.
+pub async fn pool(app: &AppHandle) -> AuraResult<SqlitePool> {
+    let instances = app
+        .try_state::<DbInstances>()
+        .ok_or_else(|| AuraError::Database {
+            message: "Database plugin not initialized".to_string(),
+        })?;
+    let instances = instances.0.read().await;
+    match instances.get(DB_URL) {
+        Some(DbPool::Sqlite(pool)) => Ok(pool.clone()),
+        None => Err(AuraError::Database {
+            message: format!("{} not loaded", DB_URL),
+        }),
+    }
+}
+
+/// Read a value from the key/value `settings` table
+pub async fn get_setting(pool: &SqlitePool, key: &str) -> AuraResult<Option<String>> {
+    sqlx::query_scalar::<_, String>("SELECT value FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(AuraError::from)
+}
+
+pub async fn set_setting(pool: &SqlitePool, key: &str, value: &str) -> AuraResult<()> {
+    sqlx::query(
+        "INSERT INTO settings (key, value) VALUES (?, ?) ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+    )
+    .bind(key)
+    .bind(value)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn delete_setting(pool: &SqlitePool, key: &str) -> AuraResult<()> {
+    sqlx::query("DELETE FROM settings WHERE key = ?")
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Random identifier in UUID v4 format, matching the ids the frontend
+/// creates with `crypto.randomUUID()`
+pub fn new_id() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    let random = || RandomState::new().build_hasher().finish();
+    let bits = ((random() as u128) << 64) | random() as u128;
+    // Set the version (4) and variant (10xx) bits
+    let bits = (bits & !(0xf << 76)) | (0x4 << 76);
+    let bits = (bits & !(0x3 << 62)) | (0x2 << 62);
+    let hex = format!("{:032x}", bits);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+/// Fresh in-memory database with every migration applied
+#[cfg(test)]
+pub async fn test_pool() -> SqlitePool {
+    // A single connection that is never recycled, since the in-memory
+    // database lives and dies with it
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .expect("open in-memory database");
+    for migration in migrations() {
+        sqlx::raw_sql(migration.sql)
+            .execute(&pool)
+            .await
+            .unwrap_or_else(|e| panic!("migration {}: {}", migration.version, e));
+    }
+    pool
+}
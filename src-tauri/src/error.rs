@@ -0,0 +1,377 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+use crate::provider::ProviderKind;
+
+/// Error returned by every Tauri command. Serialized as an object with a
+/// `kind` discriminator, a human-readable `message` and a `retryable` hint,
+/// plus the variant's own fields, so the UI can tell an expired login from a
+/// rate limit or an outage.
+#[derive(Debug, Clone)]
+pub enum AuraError {
+    /// Credentials are missing, expired or rejected (HTTP 401/403)
+    NotAuthenticated {
+        provider: ProviderKind,
+        message: String,
+    },
+    /// HTTP 429 or an exhausted GitHub quota
+    RateLimited {
+        provider: ProviderKind,
+        /// UTC, RFC 3339 — when the limit resets, if the API said so
+        reset_at: Option<String>,
+    },
+    /// Connection refused, DNS failure, timeout, ...
+    Network {
+        message: String,
+    },
+    /// Any other non-success HTTP response from a provider
+    ProviderApi {
+        provider: ProviderKind,
+        status: u16,
+        message: String,
+    },
+    /// A required command line tool is not installed
+    CliMissing {
+        tool: String,
+    },
+    /// A command line tool ran but failed
+    CliFailed {
+        tool: String,
+        message: String,
+    },
+    /// A response or stored value could not be decoded
+    Parse {
+        message: String,
+    },
+    /// Required setup is missing (organization, instance URL, ...)
+    NotConfigured {
+        message: String,
+    },
+    Database {
+        message: String,
+    },
+    /// The OS keychain could not be read or written
+    Keychain {
+        message: String,
+    },
+    /// Checking for, downloading or applying an app update failed
+    Update {
+        message: String,
+    },
+    Io {
+        message: String,
+    },
+    InvalidInput {
+        message: String,
+    },
+}
+
+impl AuraError {
+    fn kind(&self) -> &'static str {
+        match self {
+            AuraError::NotAuthenticated { .. } => "not_authenticated",
+            AuraError::RateLimited { .. } => "rate_limited",
+            AuraError::Network { .. } => "network",
+            AuraError::ProviderApi { .. } => "provider_api",
+            AuraError::CliMissing { .. } => "cli_missing",
+            AuraError::CliFailed { .. } => "cli_failed",
+            AuraError::Parse { .. } => "parse",
+            AuraError::NotConfigured { .. } => "not_configured",
+            AuraError::Database { .. } => "database",
+            AuraError::Keychain { .. } => "keychain",
+            AuraError::Update { .. } => "update",
+            AuraError::Io { .. } => "io",
+            AuraError::InvalidInput { .. } => "invalid_input",
+        }
+    }
+
+    /// Whether trying again later can succeed without user action
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AuraError::RateLimited { .. } | AuraError::Network { .. } => true,
+            AuraError::ProviderApi { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+
+    pub fn network(e: impl fmt::Display) -> Self {
+        AuraError::Network {
+            message: e.to_string(),
+        }
+    }
+
+    pub fn parse(e: impl fmt::Display) -> Self {
+        AuraError::Parse {
+            message: e.to_string(),
+        }
+    }
+
+    pub fn update(e: impl fmt::Display) -> Self {
+        AuraError::Update {
+            message: e.to_string(),
+        }
+    }
+
+    pub fn io(e: impl fmt::Display) -> Self {
+        AuraError::Io {
+            message: e.to_string(),
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        AuraError::InvalidInput {
+            message: message.into(),
+        }
+    }
+
+    pub fn not_configured(message: impl Into<String>) -> Self {
+        AuraError::NotConfigured {
+            message: message.into(),
+        }
+    }
+
+    pub fn cli_failed(tool: &str, message: impl Into<String>) -> Self {
+        AuraError::CliFailed {
+            tool: tool.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Map a failed `Command::output()` call: a missing binary is reported as
+    /// `CliMissing`, anything else as `CliFailed`.
+    pub fn from_spawn(tool: &str, e: std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            AuraError::CliMissing {
+                tool: tool.to_string(),
+            }
+        } else {
+            AuraError::cli_failed(tool, e.to_string())
+        }
+    }
+
+    /// Classify a transport-level reqwest error
+    pub fn from_reqwest(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            AuraError::parse(e)
+        } else {
+            AuraError::network(e)
+        }
+    }
+
+    /// Classify a non-success HTTP status from a provider
+    pub fn from_status(
+        provider: ProviderKind,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &str,
+    ) -> Self {
+        let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::FORBIDDEN
+                && headers
+                    .get("x-ratelimit-remaining")
+                    .and_then(|v| v.to_str().ok())
+                    == Some("0"));
+
+        if rate_limited {
+            AuraError::RateLimited {
+                provider,
+                reset_at: rate_limit_reset(headers),
+            }
+        } else if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            AuraError::NotAuthenticated {
+                provider,
+                message: body.to_string(),
+            }
+        } else {
+            AuraError::ProviderApi {
+                provider,
+                status: status.as_u16(),
+                message: body.to_string(),
+            }
+        }
+    }
+
+    /// Turn a non-success response into an error, consuming its body
+    pub async fn from_response(provider: ProviderKind, resp: reqwest::Response) -> Self {
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let body = resp.text().await.unwrap_or_default();
+        AuraError::from_status(provider, status, &headers, &body)
+    }
+}
+
+/// Reset time from a standard `Retry-After` (delay in seconds) or GitHub's
+/// `X-RateLimit-Reset` (epoch seconds), as UTC RFC 3339.
+fn rate_limit_reset(headers: &HeaderMap) -> Option<String> {
+    let header_secs = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<i64>().ok())
+    };
+
+    let reset = if let Some(delay) = header_secs(RETRY_AFTER.as_str()) {
+        chrono::Utc::now() + chrono::Duration::seconds(delay)
+    } else {
+        chrono::DateTime::from_timestamp(header_secs("x-ratelimit-reset")?, 0)?
+    };
+    Some(reset.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+}
+
+impl fmt::Display for AuraError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuraError::NotAuthenticated { provider, message } => {
+                write!(f, "{} authentication required", provider_name(*provider))?;
+                if !message.is_empty() {
+                    write!(f, ": {}", message)?;
+                }
+                Ok(())
+            }
+            AuraError::RateLimited { provider, reset_at } => {
+                write!(f, "{} rate limit exceeded", provider_name(*provider))?;
+                if let Some(reset_at) = reset_at {
+                    write!(f, " (resets at {})", reset_at)?;
+                }
+                Ok(())
+            }
+            AuraError::Network { message } => write!(f, "Request failed: {}", message),
+            AuraError::ProviderApi {
+                provider,
+                status,
+                message,
+            } => write!(
+                f,
+                "{} API error {}: {}",
+                provider_name(*provider),
+                status,
+                message
+            ),
+            AuraError::CliMissing { tool } => write!(f, "`{}` is not installed", tool),
+            AuraError::CliFailed { tool, message } => write!(f, "{} failed: {}", tool, message),
+            AuraError::Parse { message } => write!(f, "Parse error: {}", message),
+            AuraError::NotConfigured { message }
+            | AuraError::InvalidInput { message }
+            | AuraError::Io { message } => write!(f, "{}", message),
+            AuraError::Database { message } => write!(f, "Database error: {}", message),
+            AuraError::Keychain { message } => write!(f, "Keychain error: {}", message),
+            AuraError::Update { message } => write!(f, "Update failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AuraError {}
+
+fn provider_name(provider: ProviderKind) -> &'static str {
+    match provider {
+        ProviderKind::Github => "GitHub",
+        ProviderKind::Jira => "Jira",
+        ProviderKind::Fogbugz => "FogBugz",
+        ProviderKind::AzureDevops => "Azure DevOps",
+    }
+}
+
+impl Serialize for AuraError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        map.serialize_entry("retryable", &self.is_retryable())?;
+        match self {
+            AuraError::NotAuthenticated { provider, .. } => {
+                map.serialize_entry("provider", provider)?;
+            }
+            AuraError::RateLimited { provider, reset_at } => {
+                map.serialize_entry("provider", provider)?;
+                map.serialize_entry("reset_at", reset_at)?;
+            }
+            AuraError::ProviderApi {
+                provider, status, ..
+            } => {
+                map.serialize_entry("provider", provider)?;
+                map.serialize_entry("status", status)?;
+            }
+            AuraError::CliMissing { tool } | AuraError::CliFailed { tool, .. } => {
+                map.serialize_entry("tool", tool)?;
+            }
+            _ => {}
+        }
+        map.end()
+    }
+}
+
+impl From<sqlx::Error> for AuraError {
+    fn from(e: sqlx::Error) -> Self {
+        AuraError::Database {
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<keyring::Error> for AuraError {
+    fn from(e: keyring::Error) -> Self {
+        AuraError::Keychain {
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AuraError {
+    fn from(e: serde_json::Error) -> Self {
+        AuraError::parse(e)
+    }
+}
+
+pub type AuraResult<T> = Result<T, AuraError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_from_status_classifies_auth_and_api_errors() {
+        let headers = HeaderMap::new();
+        let err =
+            AuraError::from_status(ProviderKind::Jira, StatusCode::UNAUTHORIZED, &headers, "");
+        assert!(matches!(err, AuraError::NotAuthenticated { .. }));
+
+        let err = AuraError::from_status(
+            ProviderKind::Jira,
+            StatusCode::BAD_GATEWAY,
+            &headers,
+            "upstream",
+        );
+        assert!(matches!(err, AuraError::ProviderApi { status: 502, .. }));
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn test_github_exhausted_quota_is_rate_limit() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("1709287200"));
+        let err = AuraError::from_status(ProviderKind::Github, StatusCode::FORBIDDEN, &headers, "");
+        match err {
+            AuraError::RateLimited { reset_at, .. } => {
+                assert_eq!(reset_at.as_deref(), Some("2024-03-01T10:00:00Z"));
+            }
+            other => panic!("expected rate limit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_serialize_includes_kind_and_message() {
+        let err = AuraError::CliMissing {
+            tool: "gh".to_string(),
+        };
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["kind"], "cli_missing");
+        assert_eq!(json["tool"], "gh");
+        assert_eq!(json["message"], "`gh` is not installed");
+        assert_eq!(json["retryable"], false);
+    }
+}
@@ -1,6 +1,18 @@
+use async_trait::async_trait;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+use crate::connections::{connection_for, AuthMethod, Connection};
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::provider::{
+    normalize_timestamp, Fetched, Provider, ProviderKind, ProviderStatus, StatusCategory, WorkItem,
+    WorkItemDetails, WorkItemKind, WorkLogEntry,
+};
 
 // -- Public types returned to frontend --
 
@@ -25,6 +37,29 @@ pub struct FogBugzCase {
     pub is_open: bool,
 }
 
+/// How a connection authenticates
+#[derive(Clone)]
+pub enum FogBugzAuth {
+    /// Log on for a session token, which is cached and reused
+    Password { email: String, password: String },
+    /// A token generated in FogBugz, used as is
+    ApiToken { token: String },
+}
+
+/// Everything needed to call the API, secret included. Never serialized.
+#[derive(Clone)]
+pub struct FogBugzConfig {
+    /// Key of the cached session token
+    pub connection_id: String,
+    pub instance_url: String,
+    pub auth: FogBugzAuth,
+}
+
+/// Session tokens of password connections by connection id. A token stays
+/// valid until it is logged off, so it is reused instead of logging on for
+/// every request.
+static SESSIONS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
 // -- Raw JSON shapes from FogBugz JSON API --
 
 #[derive(Deserialize)]
@@ -50,13 +85,24 @@ struct RawApiResponse {
 struct RawData {
     cases: Option<Vec<RawCase>>,
     person: Option<RawPerson>,
+    interval: Option<RawInterval>,
+}
+
+#[derive(Deserialize)]
+struct RawInterval {
+    #[serde(rename = "ixInterval")]
+    id: i64,
 }
 
 #[derive(Deserialize)]
 struct RawError {
     message: Option<String>,
+    code: Option<serde_json::Value>,
 }
 
+/// FogBugz error code for a missing or expired session token
+const ERROR_NOT_LOGGED_ON: &str = "3";
+
 #[derive(Deserialize)]
 #[allow(non_snake_case)]
 struct RawCase {
@@ -70,6 +116,13 @@ struct RawCase {
     dtLastUpdated: Option<String>,
     tags: Option<Vec<String>>,
     fOpen: Option<bool>,
+    events: Option<Vec<RawEvent>>,
+}
+
+/// One entry of a case's history; `s` is the plain text body
+#[derive(Deserialize)]
+struct RawEvent {
+    s: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -89,160 +142,265 @@ fn build_headers() -> HeaderMap {
     headers
 }
 
+/// Site and credentials (secret from the keychain) of a FogBugz connection
+pub fn config_for(connection: &Connection) -> AuraResult<FogBugzConfig> {
+    let auth = match connection.auth_method {
+        AuthMethod::ApiToken => FogBugzAuth::ApiToken {
+            token: connection.secret()?,
+        },
+        _ => FogBugzAuth::Password {
+            email: connection.required(&connection.config.email, "email")?,
+            password: connection.secret()?,
+        },
+    };
+    Ok(FogBugzConfig {
+        connection_id: connection.id.clone(),
+        instance_url: connection.required(&connection.config.instance_url, "instance URL")?,
+        auth,
+    })
+}
+
+async fn require_config(app: &AppHandle, connection_id: &str) -> AuraResult<FogBugzConfig> {
+    config_for(&connection_for(app, connection_id, ProviderKind::Fogbugz).await?)
+}
+
 fn normalize_url(url: &str) -> String {
     url.trim().trim_end_matches('/').to_string()
 }
 
-fn check_api_errors(resp: &RawApiResponse) -> Result<(), String> {
+fn error_code_str(code: &serde_json::Value) -> String {
+    match code {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// FogBugz reports failures in the body of a 200 response
+fn api_error(message: String, codes: &[String]) -> AuraError {
+    if codes.iter().any(|c| c == ERROR_NOT_LOGGED_ON) {
+        AuraError::NotAuthenticated {
+            provider: ProviderKind::Fogbugz,
+            message,
+        }
+    } else {
+        AuraError::ProviderApi {
+            provider: ProviderKind::Fogbugz,
+            status: 200,
+            message,
+        }
+    }
+}
+
+fn check_api_errors(resp: &RawApiResponse) -> AuraResult<()> {
     if let Some(errors) = &resp.errors {
-        let msgs: Vec<String> = errors
-            .iter()
-            .filter_map(|e| e.message.clone())
-            .collect();
+        let msgs: Vec<String> = errors.iter().filter_map(|e| e.message.clone()).collect();
         if !msgs.is_empty() {
-            return Err(format!("FogBugz API error: {}", msgs.join("; ")));
+            let codes: Vec<String> = errors
+                .iter()
+                .filter_map(|e| e.code.as_ref().map(error_code_str))
+                .collect();
+            return Err(api_error(msgs.join("; "), &codes));
         }
     }
     if let Some(code) = &resp.error_code {
         if !code.is_null() {
-            return Err(format!("FogBugz error code: {}", code));
+            let code = error_code_str(code);
+            return Err(api_error(format!("error code {}", code), &[code]));
         }
     }
     Ok(())
 }
 
 /// Log in with email/password and return a session token
-async fn logon(
-    client: &reqwest::Client,
-    base: &str,
-    email: &str,
-    password: &str,
-) -> Result<String, String> {
-    let resp = client
-        .post(format!("{}/api/logon", base))
-        .headers(build_headers())
-        .json(&json!({
-            "email": email,
-            "password": password
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Logon request failed: {}", e))?;
+async fn logon(http: &HttpClient, base: &str, email: &str, password: &str) -> AuraResult<String> {
+    let resp = http
+        .send(
+            http.post(format!("{}/api/logon", base))
+                .headers(build_headers())
+                .json(&json!({
+                    "email": email,
+                    "password": password
+                })),
+        )
+        .await?;
 
     if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Logon failed ({}): {}", status, body));
+        return Err(AuraError::from_response(ProviderKind::Fogbugz, resp).await);
     }
 
-    let logon_resp: RawLogonResponse = resp
-        .json()
-        .await
-        .map_err(|e| format!("Logon parse error: {}", e))?;
+    let logon_resp: RawLogonResponse = resp.json().await.map_err(AuraError::from_reqwest)?;
 
     if let Some(errors) = &logon_resp.errors {
-        let msgs: Vec<String> = errors
-            .iter()
-            .filter_map(|e| e.message.clone())
-            .collect();
+        let msgs: Vec<String> = errors.iter().filter_map(|e| e.message.clone()).collect();
         if !msgs.is_empty() {
-            return Err(format!("Logon failed: {}", msgs.join("; ")));
+            return Err(AuraError::NotAuthenticated {
+                provider: ProviderKind::Fogbugz,
+                message: msgs.join("; "),
+            });
         }
     }
 
     logon_resp
         .data
         .and_then(|d| d.token)
-        .ok_or_else(|| "Logon succeeded but no token returned".to_string())
+        .ok_or_else(|| AuraError::parse("Logon succeeded but no token returned"))
 }
 
-// -- Tauri commands --
+// -- Session --
 
-/// Verify FogBugz credentials by logging in
-#[tauri::command]
-pub async fn check_fogbugz_auth(
-    instance_url: String,
-    email: String,
-    password: String,
-) -> Result<FogBugzAuthStatus, String> {
-    let base = normalize_url(&instance_url);
-    let client = reqwest::Client::new();
-
-    let token = match logon(&client, &base, &email, &password).await {
-        Ok(t) => t,
-        Err(_) => {
-            return Ok(FogBugzAuthStatus {
-                valid: false,
-                person_name: None,
-            });
-        }
+/// Token to send with API calls: the API token, or the cached session token
+/// of a password connection (logging on if there is none)
+async fn session_token(http: &HttpClient, config: &FogBugzConfig) -> AuraResult<String> {
+    let (email, password) = match &config.auth {
+        FogBugzAuth::ApiToken { token } => return Ok(token.clone()),
+        FogBugzAuth::Password { email, password } => (email, password),
     };
+    if let Some(token) = SESSIONS.lock().unwrap().get(&config.connection_id) {
+        return Ok(token.clone());
+    }
 
-    // Try to get current person info via viewPerson
-    let resp = client
-        .post(format!("{}/api/viewPerson", base))
-        .headers(build_headers())
-        .json(&json!({ "token": token }))
-        .send()
-        .await;
+    let base = normalize_url(&config.instance_url);
+    let token = logon(http, &base, email, password).await?;
+    SESSIONS
+        .lock()
+        .unwrap()
+        .insert(config.connection_id.clone(), token.clone());
+    Ok(token)
+}
 
-    let person_name = if let Ok(resp) = resp {
-        resp.json::<RawApiResponse>()
-            .await
-            .ok()
-            .and_then(|r| r.data)
-            .and_then(|d| d.person)
-            .and_then(|p| p.sFullName)
-    } else {
-        None
+/// POST to `/api/<command>` with the session token. A password connection
+/// whose cached token was rejected logs on again and retries once.
+async fn api_call(
+    http: &HttpClient,
+    config: &FogBugzConfig,
+    command: &str,
+    body: serde_json::Value,
+) -> AuraResult<RawApiResponse> {
+    call(http, config, command, body, false).await
+}
+
+/// Like `api_call`, for commands that change something. Network failures
+/// are not retried, since the first attempt may have gone through; a
+/// rejected token still is, as nothing was done with it.
+async fn api_write(
+    http: &HttpClient,
+    config: &FogBugzConfig,
+    command: &str,
+    body: serde_json::Value,
+) -> AuraResult<RawApiResponse> {
+    call(http, config, command, body, true).await
+}
+
+async fn call(
+    http: &HttpClient,
+    config: &FogBugzConfig,
+    command: &str,
+    mut body: serde_json::Value,
+    write: bool,
+) -> AuraResult<RawApiResponse> {
+    let base = normalize_url(&config.instance_url);
+    let mut retried = false;
+    loop {
+        body["token"] = json!(session_token(http, config).await?);
+        let request = http
+            .post(format!("{}/api/{}", base, command))
+            .headers(build_headers())
+            .json(&body);
+        let resp = if write {
+            http.send_once(request).await?
+        } else {
+            http.send(request).await?
+        };
+
+        if !resp.status().is_success() {
+            return Err(AuraError::from_response(ProviderKind::Fogbugz, resp).await);
+        }
+
+        let api_resp: RawApiResponse = resp.json().await.map_err(AuraError::from_reqwest)?;
+        match check_api_errors(&api_resp) {
+            Err(AuraError::NotAuthenticated { .. })
+                if !retried && matches!(config.auth, FogBugzAuth::Password { .. }) =>
+            {
+                SESSIONS.lock().unwrap().remove(&config.connection_id);
+                retried = true;
+            }
+            Err(e) => return Err(e),
+            Ok(()) => return Ok(api_resp),
+        }
+    }
+}
+
+/// End the cached session of a connection, if it has one. Best effort: the
+/// token is forgotten even when the server cannot be reached.
+pub async fn logoff(http: &HttpClient, connection: &Connection) {
+    let Some(token) = SESSIONS.lock().unwrap().remove(&connection.id) else {
+        return;
     };
+    let Some(instance_url) = connection.config.instance_url.as_deref() else {
+        return;
+    };
+    let _ = http
+        .send(
+            http.post(format!("{}/api/logoff", normalize_url(instance_url)))
+                .headers(build_headers())
+                .json(&json!({ "token": token })),
+        )
+        .await;
+}
 
-    Ok(FogBugzAuthStatus {
-        valid: true,
-        person_name,
-    })
+// -- API functions --
+
+/// Verify FogBugz credentials by looking up the signed-in person
+pub async fn verify_credentials(
+    http: &HttpClient,
+    config: &FogBugzConfig,
+) -> AuraResult<FogBugzAuthStatus> {
+    match api_call(http, config, "viewPerson", json!({})).await {
+        Ok(resp) => Ok(FogBugzAuthStatus {
+            valid: true,
+            person_name: resp.data.and_then(|d| d.person).and_then(|p| p.sFullName),
+        }),
+        Err(AuraError::NotAuthenticated { .. }) => Ok(FogBugzAuthStatus {
+            valid: false,
+            person_name: None,
+        }),
+        Err(e) => Err(e),
+    }
 }
 
+/// The default list: active cases assigned to the user
+const ASSIGNED_QUERY: &str = "assignedto:me status:active";
+
 /// Fetch open cases assigned to the current user
-#[tauri::command]
-pub async fn fogbugz_fetch_cases(
-    instance_url: String,
-    email: String,
-    password: String,
-) -> Result<Vec<FogBugzCase>, String> {
-    let base = normalize_url(&instance_url);
-    let client = reqwest::Client::new();
-
-    let token = logon(&client, &base, &email, &password).await?;
-
-    let resp = client
-        .post(format!("{}/api/search", base))
-        .headers(build_headers())
-        .json(&json!({
-            "token": token,
-            "q": "assignedto:me status:active",
+pub async fn fetch_assigned_cases(
+    http: &HttpClient,
+    config: &FogBugzConfig,
+) -> AuraResult<Vec<FogBugzCase>> {
+    search_cases(http, config, ASSIGNED_QUERY).await
+}
+
+/// Run a FogBugz search (same syntax as the search box)
+pub async fn search_cases(
+    http: &HttpClient,
+    config: &FogBugzConfig,
+    query: &str,
+) -> AuraResult<Vec<FogBugzCase>> {
+    let base = normalize_url(&config.instance_url);
+
+    let api_resp = api_call(
+        http,
+        config,
+        "search",
+        json!({
+            "q": query,
             "cols": [
                 "ixBug", "sTitle", "sStatus", "sCategory", "sPriority",
                 "sProject", "sArea", "dtLastUpdated", "tags", "fOpen"
             ],
             "max": 200
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
-
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!("FogBugz API error {}: {}", status, body));
-    }
-
-    let api_resp: RawApiResponse = resp
-        .json()
-        .await
-        .map_err(|e| format!("Parse error: {}", e))?;
-
-    check_api_errors(&api_resp)?;
+        }),
+    )
+    .await?;
 
     let cases = api_resp
         .data
@@ -269,3 +427,353 @@ pub async fn fogbugz_fetch_cases(
 
     Ok(cases)
 }
+
+/// Description of a case: the text of its first event, written when it was
+/// opened. FogBugz has no acceptance criteria field.
+pub async fn fetch_case_details(
+    http: &HttpClient,
+    config: &FogBugzConfig,
+    id: &str,
+) -> AuraResult<WorkItemDetails> {
+    let api_resp = api_call(
+        http,
+        config,
+        "search",
+        json!({ "q": id, "cols": ["events"], "max": 1 }),
+    )
+    .await?;
+    let description = api_resp
+        .data
+        .and_then(|d| d.cases)
+        .and_then(|cases| cases.into_iter().next())
+        .and_then(|case| case.events)
+        .and_then(|events| events.into_iter().find_map(|e| e.s))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Ok(WorkItemDetails {
+        description,
+        acceptance_criteria: None,
+    })
+}
+
+/// Record time worked on a case and return the interval's id. This is the
+/// interval `startWork` and `stopWork` would leave behind, but those can
+/// only clock in and out now, so past time is booked with `newInterval`.
+pub async fn add_interval(
+    http: &HttpClient,
+    config: &FogBugzConfig,
+    id: &str,
+    entry: &WorkLogEntry,
+) -> AuraResult<String> {
+    let end = entry.started + chrono::Duration::seconds(entry.seconds);
+    let api_resp = api_write(
+        http,
+        config,
+        "newInterval",
+        json!({
+            "ixBug": id,
+            "dtStart": entry.started.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "dtEnd": end.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        }),
+    )
+    .await?;
+    api_resp
+        .data
+        .and_then(|d| d.interval)
+        .map(|i| i.id.to_string())
+        .ok_or_else(|| AuraError::parse("FogBugz did not return the new interval"))
+}
+
+// -- Provider --
+
+fn to_work_item(connection_id: &str, case: FogBugzCase) -> WorkItem {
+    // Open cases are all "Active" in FogBugz; only closed ones carry a
+    // meaningful resolution ("Resolved (Fixed)", "Closed (Duplicate)", ...)
+    let status_category = if !case.is_open {
+        StatusCategory::Closed
+    } else if case.status.starts_with("Resolved") {
+        StatusCategory::Resolved
+    } else {
+        StatusCategory::Open
+    };
+
+    let mut item = WorkItem::new(
+        connection_id,
+        ProviderKind::Fogbugz,
+        WorkItemKind::Issue,
+        case.id.to_string(),
+        case.title,
+        case.url,
+    )
+    .with_extra("area", case.area);
+
+    item.status = case.status;
+    item.status_category = status_category;
+    item.priority = case.priority;
+    item.item_type = Some(case.category);
+    item.labels = case.tags;
+    item.project = case.project;
+    item.updated_at = normalize_timestamp(&case.updated);
+    item
+}
+
+pub struct FogBugzProvider {
+    http: HttpClient,
+    connection: Connection,
+}
+
+impl FogBugzProvider {
+    pub fn new(http: HttpClient, connection: Connection) -> Self {
+        FogBugzProvider { http, connection }
+    }
+}
+
+#[async_trait]
+impl Provider for FogBugzProvider {
+    fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    async fn check_auth(&self) -> AuraResult<ProviderStatus> {
+        let status = verify_credentials(&self.http, &config_for(&self.connection)?).await?;
+        Ok(ProviderStatus {
+            connection_id: self.connection.id.clone(),
+            provider: ProviderKind::Fogbugz,
+            authenticated: status.valid,
+            account: status.person_name,
+        })
+    }
+
+    async fn fetch_assigned(&self) -> AuraResult<Fetched> {
+        let cases = fetch_assigned_cases(&self.http, &config_for(&self.connection)?).await?;
+        Ok(cases
+            .into_iter()
+            .map(|c| to_work_item(&self.connection.id, c))
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn search(&self, query: &str) -> AuraResult<Fetched> {
+        let cases = search_cases(&self.http, &config_for(&self.connection)?, query).await?;
+        Ok(cases
+            .into_iter()
+            .map(|c| to_work_item(&self.connection.id, c))
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn fetch_details(&self, item: &WorkItem) -> AuraResult<WorkItemDetails> {
+        fetch_case_details(
+            &self.http,
+            &config_for(&self.connection)?,
+            &item.provider_id,
+        )
+        .await
+    }
+
+    async fn log_work(&self, item: &WorkItem, entry: &WorkLogEntry) -> AuraResult<Option<String>> {
+        let id = add_interval(
+            &self.http,
+            &config_for(&self.connection)?,
+            &item.provider_id,
+            entry,
+        )
+        .await?;
+        Ok(Some(id))
+    }
+}
+
+// -- Tauri commands --
+
+/// Verify a FogBugz connection's credentials by logging in
+#[tauri::command]
+pub async fn check_fogbugz_auth(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<FogBugzAuthStatus> {
+    let config = require_config(&app, &connection_id).await?;
+    verify_credentials(&http, &config).await
+}
+
+/// Fetch open cases assigned to the current user
+#[tauri::command]
+pub async fn fogbugz_fetch_cases(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<Vec<FogBugzCase>> {
+    let config = require_config(&app, &connection_id).await?;
+    fetch_assigned_cases(&http, &config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, Requests};
+    use serde_json::Value;
+
+    /// Local FogBugz: `respond` gets each request's path and JSON body
+    fn mock_server(respond: impl Fn(&str, &Value) -> Value + Send + 'static) -> (String, Requests) {
+        test_support::mock_server(move |request| {
+            (200, respond(&request.path, &request.json()).to_string())
+        })
+    }
+
+    /// Logs on as `fresh`, and only accepts `fresh` afterwards
+    fn fogbugz(path: &str, body: &Value) -> Value {
+        match path {
+            "/api/logon" => json!({ "data": { "token": "fresh" } }),
+            "/api/logoff" => json!({ "data": {} }),
+            _ if body["token"] == "fresh" => {
+                json!({ "data": { "person": { "sFullName": "Ada" } } })
+            }
+            _ => json!({ "errors": [{ "message": "Not logged on", "code": "3" }] }),
+        }
+    }
+
+    fn password_config(connection_id: &str, instance_url: &str) -> FogBugzConfig {
+        FogBugzConfig {
+            connection_id: connection_id.to_string(),
+            instance_url: instance_url.to_string(),
+            auth: FogBugzAuth::Password {
+                email: "ada@example.com".into(),
+                password: "secret".into(),
+            },
+        }
+    }
+
+    fn paths(requests: &Requests) -> Vec<String> {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.path.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_session_token_is_cached() {
+        let (url, requests) = mock_server(fogbugz);
+        let http = HttpClient::new();
+        let config = password_config("fb-cached", &url);
+
+        for _ in 0..2 {
+            let status =
+                tauri::async_runtime::block_on(verify_credentials(&http, &config)).unwrap();
+            assert!(status.valid);
+            assert_eq!(status.person_name.as_deref(), Some("Ada"));
+        }
+        // One logon, then the token is reused
+        assert_eq!(
+            paths(&requests),
+            vec!["/api/logon", "/api/viewPerson", "/api/viewPerson"]
+        );
+        assert_eq!(
+            SESSIONS
+                .lock()
+                .unwrap()
+                .get("fb-cached")
+                .map(String::as_str),
+            Some("fresh")
+        );
+    }
+
+    #[test]
+    fn test_expired_token_logs_on_again() {
+        let (url, requests) = mock_server(fogbugz);
+        let http = HttpClient::new();
+        let config = password_config("fb-expired", &url);
+        SESSIONS
+            .lock()
+            .unwrap()
+            .insert("fb-expired".into(), "stale".into());
+
+        let resp =
+            tauri::async_runtime::block_on(api_call(&http, &config, "viewPerson", json!({})))
+                .unwrap();
+        assert!(resp.data.and_then(|d| d.person).is_some());
+        assert_eq!(
+            paths(&requests),
+            vec!["/api/viewPerson", "/api/logon", "/api/viewPerson"]
+        );
+        assert_eq!(requests.lock().unwrap()[0].json()["token"], "stale");
+        assert_eq!(
+            SESSIONS
+                .lock()
+                .unwrap()
+                .get("fb-expired")
+                .map(String::as_str),
+            Some("fresh")
+        );
+    }
+
+    #[test]
+    fn test_rejected_token_is_retried_only_once() {
+        // Hands out a token it then rejects
+        let (url, requests) = mock_server(|path, _| match path {
+            "/api/logon" => json!({ "data": { "token": "useless" } }),
+            _ => json!({ "errors": [{ "message": "Not logged on", "code": 3 }] }),
+        });
+        let http = HttpClient::new();
+        let config = password_config("fb-rejected", &url);
+        SESSIONS
+            .lock()
+            .unwrap()
+            .insert("fb-rejected".into(), "stale".into());
+
+        let result =
+            tauri::async_runtime::block_on(api_call(&http, &config, "viewPerson", json!({})));
+        assert!(matches!(result, Err(AuraError::NotAuthenticated { .. })));
+        assert_eq!(
+            paths(&requests),
+            vec!["/api/viewPerson", "/api/logon", "/api/viewPerson"]
+        );
+
+        // An API token can't be renewed, so it isn't retried at all
+        requests.lock().unwrap().clear();
+        let config = FogBugzConfig {
+            auth: FogBugzAuth::ApiToken {
+                token: "revoked".into(),
+            },
+            ..config
+        };
+        let result =
+            tauri::async_runtime::block_on(api_call(&http, &config, "viewPerson", json!({})));
+        assert!(matches!(result, Err(AuraError::NotAuthenticated { .. })));
+        assert_eq!(paths(&requests), vec!["/api/viewPerson"]);
+    }
+
+    #[test]
+    fn test_logoff_ends_and_forgets_the_session() {
+        let (url, requests) = mock_server(fogbugz);
+        let http = HttpClient::new();
+        let connection = Connection {
+            id: "fb-logoff".into(),
+            provider: ProviderKind::Fogbugz,
+            label: "FogBugz".into(),
+            auth_method: AuthMethod::Password,
+            username: None,
+            config: crate::connections::ConnectionConfig {
+                instance_url: Some(format!("{}/", url)),
+                ..Default::default()
+            },
+            has_secret: true,
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+        SESSIONS
+            .lock()
+            .unwrap()
+            .insert("fb-logoff".into(), "fresh".into());
+
+        tauri::async_runtime::block_on(logoff(&http, &connection));
+        assert!(!SESSIONS.lock().unwrap().contains_key("fb-logoff"));
+        assert_eq!(paths(&requests), vec!["/api/logoff"]);
+        assert_eq!(requests.lock().unwrap()[0].json()["token"], "fresh");
+
+        // Nothing to end the second time
+        tauri::async_runtime::block_on(logoff(&http, &connection));
+        assert_eq!(paths(&requests).len(), 1);
+    }
+}
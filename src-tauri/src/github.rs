@@ -1,8 +1,52 @@
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, USER_AGENT, ACCEPT};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
 
+use crate::connections::{connection_for, AuthMethod, Connection};
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::provider::{
+    normalize_timestamp, Fetched, Provider, ProviderKind, ProviderStatus, StatusCategory, WorkItem,
+    WorkItemKind,
+};
+
+/// Host used when a connection does not name a GitHub Enterprise server
+pub const DEFAULT_HOST: &str = "github.com";
 const GITHUB_API: &str = "https://api.github.com";
 
+/// Bare hostname from what a user may paste: "https://ghe.corp/" -> "ghe.corp"
+pub fn normalize_host(host: &str) -> String {
+    let host = host.trim();
+    let host = host
+        .strip_prefix("https://")
+        .or_else(|| host.strip_prefix("http://"))
+        .unwrap_or(host);
+    host.trim_end_matches('/').to_lowercase()
+}
+
+/// REST root for a host. GitHub Enterprise Server serves the API under
+/// `/api/v3` on the instance itself.
+pub fn api_base(host: &str) -> String {
+    let host = normalize_host(host);
+    if host == DEFAULT_HOST || host == "api.github.com" {
+        GITHUB_API.to_string()
+    } else {
+        format!("https://{}/api/v3", host)
+    }
+}
+
+/// GitHub host of a connection
+pub fn host_for(connection: &Connection) -> String {
+    connection
+        .config
+        .host
+        .as_deref()
+        .map(normalize_host)
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| DEFAULT_HOST.to_string())
+}
+
 fn build_headers(token: &str) -> HeaderMap {
     let mut headers = HeaderMap::new();
     headers.insert(
@@ -64,53 +108,48 @@ pub struct SearchResult {
 // -- API functions --
 
 /// Fetch the authenticated user
-pub async fn fetch_user(token: &str) -> Result<GitHubUser, String> {
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(format!("{}/user", GITHUB_API))
-        .headers(build_headers(token))
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+pub async fn fetch_user(http: &HttpClient, api: &str, token: &str) -> AuraResult<GitHubUser> {
+    let resp = http
+        .send(
+            http.get(format!("{}/user", api))
+                .headers(build_headers(token)),
+        )
+        .await?;
 
     if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!("GitHub API error {}: {}", status, body));
+        return Err(AuraError::from_response(ProviderKind::Github, resp).await);
     }
 
     resp.json::<GitHubUser>()
         .await
-        .map_err(|e| format!("Parse error: {}", e))
+        .map_err(AuraError::from_reqwest)
 }
 
 /// Fetch issues assigned to the authenticated user
-pub async fn fetch_assigned_issues(token: &str) -> Result<Vec<GitHubIssue>, String> {
-    let client = reqwest::Client::new();
+pub async fn fetch_assigned_issues(
+    http: &HttpClient,
+    api: &str,
+    token: &str,
+) -> AuraResult<Vec<GitHubIssue>> {
     let mut all_issues = Vec::new();
     let mut page = 1u32;
 
     loop {
-        let resp = client
-            .get(format!(
-                "{}/issues?filter=assigned&state=open&per_page=100&page={}",
-                GITHUB_API, page
-            ))
-            .headers(build_headers(token))
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
+        let resp = http
+            .send(
+                http.get(format!(
+                    "{}/issues?filter=assigned&state=open&per_page=100&page={}",
+                    api, page
+                ))
+                .headers(build_headers(token)),
+            )
+            .await?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let body = resp.text().await.unwrap_or_default();
-            return Err(format!("GitHub API error {}: {}", status, body));
+            return Err(AuraError::from_response(ProviderKind::Github, resp).await);
         }
 
-        let issues: Vec<GitHubIssue> = resp
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))?;
+        let issues: Vec<GitHubIssue> = resp.json().await.map_err(AuraError::from_reqwest)?;
 
         let count = issues.len();
         // Filter out pull requests (they have a pull_request key)
@@ -130,54 +169,275 @@ pub async fn fetch_assigned_issues(token: &str) -> Result<Vec<GitHubIssue>, Stri
     Ok(all_issues)
 }
 
+/// Most results the search API returns for one query
+const SEARCH_LIMIT: usize = 1000;
+
+/// Run an issue search (GitHub search syntax); results may mix issues and
+/// pull requests
+pub async fn search_issues(
+    http: &HttpClient,
+    api: &str,
+    token: &str,
+    query: &str,
+) -> AuraResult<Vec<GitHubIssue>> {
+    let mut all_items = Vec::new();
+    let mut page = 1u32;
+
+    loop {
+        let page_param = page.to_string();
+        let resp = http
+            .send(
+                http.get(format!("{}/search/issues", api))
+                    .headers(build_headers(token))
+                    .query(&[
+                        ("q", query),
+                        ("sort", "updated"),
+                        ("per_page", "100"),
+                        ("page", page_param.as_str()),
+                    ]),
+            )
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(AuraError::from_response(ProviderKind::Github, resp).await);
+        }
+
+        let result: SearchResult = resp.json().await.map_err(AuraError::from_reqwest)?;
+        let count = result.items.len();
+        all_items.extend(result.items);
+
+        if count < 100
+            || all_items.len() as u64 >= result.total_count
+            || all_items.len() >= SEARCH_LIMIT
+        {
+            break;
+        }
+        page += 1;
+    }
+
+    Ok(all_items)
+}
+
 /// Fetch PRs where user is author or review-requested
-pub async fn fetch_assigned_prs(token: &str, username: &str) -> Result<Vec<GitHubIssue>, String> {
-    let client = reqwest::Client::new();
+pub async fn fetch_assigned_prs(
+    http: &HttpClient,
+    api: &str,
+    token: &str,
+    username: &str,
+) -> AuraResult<Vec<GitHubIssue>> {
     let query = format!("type:pr is:open involves:{}", username);
 
-    let resp = client
-        .get(format!("{}/search/issues", GITHUB_API))
-        .headers(build_headers(token))
-        .query(&[
-            ("q", query.as_str()),
-            ("sort", "updated"),
-            ("per_page", "100"),
-        ])
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+    let resp = http
+        .send(
+            http.get(format!("{}/search/issues", api))
+                .headers(build_headers(token))
+                .query(&[
+                    ("q", query.as_str()),
+                    ("sort", "updated"),
+                    ("per_page", "100"),
+                ]),
+        )
+        .await?;
 
     if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!("GitHub API error {}: {}", status, body));
+        return Err(AuraError::from_response(ProviderKind::Github, resp).await);
     }
 
-    let result: SearchResult = resp
-        .json()
-        .await
-        .map_err(|e| format!("Parse error: {}", e))?;
+    let result: SearchResult = resp.json().await.map_err(AuraError::from_reqwest)?;
 
     Ok(result.items)
 }
 
+// -- Provider --
+
+/// "https://api.github.com/repos/owner/repo" -> "owner/repo"
+fn repo_from_url(url: &str) -> String {
+    url.split("/repos/").nth(1).unwrap_or(url).to_string()
+}
+
+fn to_work_item(connection_id: &str, issue: GitHubIssue, kind: WorkItemKind) -> WorkItem {
+    let repo = repo_from_url(&issue.repository_url);
+    let status_category = if issue.state == "closed" {
+        StatusCategory::Closed
+    } else {
+        StatusCategory::Open
+    };
+
+    let mut item = WorkItem::new(
+        connection_id,
+        ProviderKind::Github,
+        kind,
+        format!("{}#{}", repo, issue.number),
+        issue.title,
+        issue.html_url,
+    )
+    .with_extra("number", issue.number)
+    .with_extra("github_id", issue.id)
+    .with_extra("repository", repo.clone());
+
+    item.description = issue.body;
+    item.status = issue.state;
+    item.status_category = status_category;
+    item.author = Some(issue.user.login);
+    item.labels = issue.labels.into_iter().map(|l| l.name).collect();
+    item.project = repo;
+    item.created_at = Some(normalize_timestamp(&issue.created_at));
+    item.updated_at = normalize_timestamp(&issue.updated_at);
+    item
+}
+
+/// API token for a connection: its own PAT, or `gh` for the connection's
+/// host (with the saved fallback PAT on github.com) for CLI connections
+pub fn token_for(connection: &Connection) -> AuraResult<String> {
+    match connection.auth_method {
+        AuthMethod::Pat => connection.secret(),
+        _ => crate::auth::github_token(&host_for(connection)),
+    }
+}
+
+pub struct GitHubProvider {
+    http: HttpClient,
+    connection: Connection,
+    api: String,
+}
+
+impl GitHubProvider {
+    pub fn new(http: HttpClient, connection: Connection) -> Self {
+        let api = api_base(&host_for(&connection));
+        GitHubProvider {
+            http,
+            connection,
+            api,
+        }
+    }
+
+    fn to_work_items(&self, issues: Vec<GitHubIssue>, kind: WorkItemKind) -> Vec<WorkItem> {
+        issues
+            .into_iter()
+            .map(|i| to_work_item(&self.connection.id, i, kind))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Provider for GitHubProvider {
+    fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    async fn check_auth(&self) -> AuraResult<ProviderStatus> {
+        let (authenticated, account) = match self.connection.auth_method {
+            AuthMethod::Pat => {
+                match fetch_user(&self.http, &self.api, &self.connection.secret()?).await {
+                    Ok(user) => (true, Some(user.login)),
+                    Err(AuraError::NotAuthenticated { .. }) => (false, None),
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => {
+                let host = host_for(&self.connection);
+                let status = crate::auth::resolve_github_auth(&self.http, &host).await;
+                (status.token.is_some(), status.username)
+            }
+        };
+        Ok(ProviderStatus {
+            connection_id: self.connection.id.clone(),
+            provider: ProviderKind::Github,
+            authenticated,
+            account,
+        })
+    }
+
+    async fn fetch_assigned(&self) -> AuraResult<Fetched> {
+        let token = token_for(&self.connection)?;
+        let issues = fetch_assigned_issues(&self.http, &self.api, &token).await?;
+        Ok(self.to_work_items(issues, WorkItemKind::Issue).into())
+    }
+
+    async fn fetch_pull_requests(&self) -> AuraResult<Fetched> {
+        let token = token_for(&self.connection)?;
+        let username = fetch_user(&self.http, &self.api, &token).await?.login;
+        let prs = fetch_assigned_prs(&self.http, &self.api, &token, &username).await?;
+        Ok(self.to_work_items(prs, WorkItemKind::PullRequest).into())
+    }
+
+    async fn search(&self, query: &str) -> AuraResult<Fetched> {
+        let token = token_for(&self.connection)?;
+        let found = search_issues(&self.http, &self.api, &token, query).await?;
+        Ok(found
+            .into_iter()
+            .map(|i| {
+                let kind = if i.pull_request.is_some() {
+                    WorkItemKind::PullRequest
+                } else {
+                    WorkItemKind::Issue
+                };
+                to_work_item(&self.connection.id, i, kind)
+            })
+            .collect::<Vec<_>>()
+            .into())
+    }
+}
+
 // -- Tauri commands --
 
 #[tauri::command]
-pub async fn github_fetch_issues() -> Result<Vec<GitHubIssue>, String> {
-    let token = crate::auth::extract_gh_token()?;
-    fetch_assigned_issues(&token).await
+pub async fn github_fetch_issues(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<Vec<GitHubIssue>> {
+    let connection = connection_for(&app, &connection_id, ProviderKind::Github).await?;
+    let api = api_base(&host_for(&connection));
+    fetch_assigned_issues(&http, &api, &token_for(&connection)?).await
 }
 
 #[tauri::command]
-pub async fn github_fetch_prs() -> Result<Vec<GitHubIssue>, String> {
-    let token = crate::auth::extract_gh_token()?;
-    let username = crate::auth::get_gh_username()?;
-    fetch_assigned_prs(&token, &username).await
+pub async fn github_fetch_prs(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<Vec<GitHubIssue>> {
+    let connection = connection_for(&app, &connection_id, ProviderKind::Github).await?;
+    let api = api_base(&host_for(&connection));
+    let token = token_for(&connection)?;
+    let username = fetch_user(&http, &api, &token).await?.login;
+    fetch_assigned_prs(&http, &api, &token, &username).await
 }
 
 #[tauri::command]
-pub async fn github_fetch_user() -> Result<GitHubUser, String> {
-    let token = crate::auth::extract_gh_token()?;
-    fetch_user(&token).await
+pub async fn github_fetch_user(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<GitHubUser> {
+    let connection = connection_for(&app, &connection_id, ProviderKind::Github).await?;
+    let api = api_base(&host_for(&connection));
+    fetch_user(&http, &api, &token_for(&connection)?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_base_per_host() {
+        assert_eq!(api_base("github.com"), "https://api.github.com");
+        assert_eq!(
+            api_base("ghe.corp.example"),
+            "https://ghe.corp.example/api/v3"
+        );
+        assert_eq!(
+            api_base("https://GHE.corp.example/"),
+            "https://ghe.corp.example/api/v3"
+        );
+    }
+
+    #[test]
+    fn test_repo_from_enterprise_url() {
+        assert_eq!(
+            repo_from_url("https://ghe.corp.example/api/v3/repos/team/app"),
+            "team/app"
+        );
+    }
 }
@@ -0,0 +1,204 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{IntoUrl, RequestBuilder, Response, StatusCode};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::error::{AuraError, AuraResult};
+
+const MAX_ATTEMPTS: u32 = 4;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+/// Server-requested waits longer than this are not slept through; the
+/// response is handed back so the caller can report a rate limit instead.
+const MAX_SERVER_WAIT: Duration = Duration::from_secs(60);
+
+/// Connection-pooled client shared by every provider through Tauri state.
+/// Cloning is cheap and shares the pool.
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpClient {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("Aura/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(30))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .expect("failed to build HTTP client");
+        HttpClient { client }
+    }
+
+    pub fn get(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.client.get(url)
+    }
+
+    pub fn post(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    pub fn patch(&self, url: impl IntoUrl) -> RequestBuilder {
+        self.client.patch(url)
+    }
+
+    /// Send a request, retrying transient failures (timeouts, connection
+    /// errors, 5xx, rate limits) with exponential backoff and jitter.
+    /// Non-success responses that are not worth retrying are returned as-is
+    /// for the caller to classify.
+    pub async fn send(&self, request: RequestBuilder) -> AuraResult<Response> {
+        let mut attempt = 0;
+        loop {
+            // Streaming bodies cannot be cloned; those get a single attempt
+            let Some(this_try) = request.try_clone() else {
+                return request.send().await.map_err(AuraError::from_reqwest);
+            };
+            let last_attempt = attempt + 1 >= MAX_ATTEMPTS;
+
+            match this_try.send().await {
+                Ok(resp) => match retry_delay(resp.status(), resp.headers(), attempt) {
+                    Some(delay) if !last_attempt => tokio::time::sleep(delay).await,
+                    _ => return Ok(resp),
+                },
+                Err(e) if is_transient(&e) && !last_attempt => {
+                    tokio::time::sleep(backoff(attempt)).await
+                }
+                Err(e) => return Err(AuraError::from_reqwest(e)),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Send a request exactly once. For writes that are not idempotent, like
+    /// booking time: a retry after a timeout could book it twice.
+    pub async fn send_once(&self, request: RequestBuilder) -> AuraResult<Response> {
+        request.send().await.map_err(AuraError::from_reqwest)
+    }
+}
+
+fn is_transient(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.is_request()
+}
+
+/// Exponential backoff with jitter: a random delay between half and all of
+/// `BASE_BACKOFF * 2^attempt`, capped at `MAX_BACKOFF`.
+fn backoff(attempt: u32) -> Duration {
+    let ceiling = BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF);
+    let half = ceiling / 2;
+    let jitter_ms = random_u64() % (half.as_millis() as u64 + 1);
+    half + Duration::from_millis(jitter_ms)
+}
+
+/// Randomness for jitter without pulling in a RNG crate; `RandomState` is
+/// seeded randomly per instance.
+fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+fn header_secs(headers: &HeaderMap, name: &str) -> Option<i64> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<i64>().ok())
+}
+
+/// How long the server asked us to wait, from `Retry-After` or, when the
+/// quota is exhausted, GitHub's `X-RateLimit-Reset`.
+fn server_wait(headers: &HeaderMap) -> Option<Duration> {
+    if let Some(secs) = header_secs(headers, RETRY_AFTER.as_str()) {
+        return Some(Duration::from_secs(secs.max(0) as u64));
+    }
+    if header_secs(headers, "x-ratelimit-remaining") == Some(0) {
+        let reset = header_secs(headers, "x-ratelimit-reset")?;
+        let now = chrono::Utc::now().timestamp();
+        return Some(Duration::from_secs((reset - now).max(0) as u64));
+    }
+    None
+}
+
+/// Delay before retrying a response, or `None` if it should be returned
+fn retry_delay(status: StatusCode, headers: &HeaderMap, attempt: u32) -> Option<Duration> {
+    let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && server_wait(headers).is_some());
+
+    if rate_limited {
+        let wait = server_wait(headers).unwrap_or_else(|| backoff(attempt));
+        return (wait <= MAX_SERVER_WAIT).then_some(wait);
+    }
+
+    match status {
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => Some(
+            server_wait(headers)
+                .filter(|w| *w <= MAX_SERVER_WAIT)
+                .unwrap_or_else(|| backoff(attempt)),
+        ),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        for attempt in 0..3 {
+            let ceiling = BASE_BACKOFF * 2u32.pow(attempt);
+            let delay = backoff(attempt);
+            assert!(delay >= ceiling / 2 && delay <= ceiling);
+        }
+        assert!(backoff(20) <= MAX_BACKOFF);
+    }
+
+    #[test]
+    fn test_retry_delay_honors_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(
+            retry_delay(StatusCode::TOO_MANY_REQUESTS, &headers, 0),
+            Some(Duration::from_secs(7))
+        );
+
+        // Too long to wait in the background: hand the 429 back
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(
+            retry_delay(StatusCode::TOO_MANY_REQUESTS, &headers, 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_ignores_plain_client_errors() {
+        let headers = HeaderMap::new();
+        assert_eq!(retry_delay(StatusCode::FORBIDDEN, &headers, 0), None);
+        assert_eq!(retry_delay(StatusCode::NOT_FOUND, &headers, 0), None);
+        assert!(retry_delay(StatusCode::BAD_GATEWAY, &headers, 0).is_some());
+    }
+
+    #[test]
+    fn test_github_secondary_limit_is_retried() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        let reset = chrono::Utc::now().timestamp() + 5;
+        headers.insert(
+            "x-ratelimit-reset",
+            HeaderValue::from_str(&reset.to_string()).unwrap(),
+        );
+        let delay = retry_delay(StatusCode::FORBIDDEN, &headers, 0).unwrap();
+        assert!(delay <= Duration::from_secs(5));
+    }
+}
@@ -1,5 +1,18 @@
+use async_trait::async_trait;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+use crate::connections::{connection_for, AuthMethod, Connection};
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::provider::{
+    html_to_text, normalize_timestamp, Fetched, Provider, ProviderKind, ProviderStatus,
+    StatusCategory, WorkItem, WorkItemDetails, WorkItemKind, WorkLogEntry,
+};
 
 // -- Public types returned to frontend --
 
@@ -8,6 +21,51 @@ pub struct JiraAuthStatus {
     pub valid: bool,
     pub display_name: Option<String>,
     pub email: Option<String>,
+    pub deployment: Option<JiraDeployment>,
+}
+
+/// Which flavour of Jira an instance runs. Cloud speaks REST v3 with
+/// email + API token; Server and Data Center speak v2 with a bearer PAT.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JiraDeployment {
+    Cloud,
+    Server,
+    DataCenter,
+}
+
+impl JiraDeployment {
+    /// From `deploymentType` of /rest/api/2/serverInfo
+    fn from_type(deployment_type: &str) -> Self {
+        match deployment_type {
+            "Cloud" => JiraDeployment::Cloud,
+            "DataCenter" => JiraDeployment::DataCenter,
+            _ => JiraDeployment::Server,
+        }
+    }
+
+    /// Guess from the site when serverInfo cannot be read
+    fn from_url(instance_url: &str) -> Self {
+        let host = instance_url
+            .split("://")
+            .nth(1)
+            .unwrap_or(instance_url)
+            .split(['/', ':'])
+            .next()
+            .unwrap_or_default();
+        if host.ends_with(".atlassian.net") || host.ends_with(".jira.com") {
+            JiraDeployment::Cloud
+        } else {
+            JiraDeployment::Server
+        }
+    }
+
+    fn api_path(&self) -> &'static str {
+        match self {
+            JiraDeployment::Cloud => "/rest/api/3",
+            JiraDeployment::Server | JiraDeployment::DataCenter => "/rest/api/2",
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -24,11 +82,50 @@ pub struct JiraIssue {
     pub project: String,
 }
 
+/// Credentials sent with every request
+#[derive(Clone)]
+pub enum JiraAuth {
+    /// Cloud: email + API token as Basic auth
+    Basic { email: String, api_token: String },
+    /// Server / Data Center: personal access token as a bearer token
+    Bearer { token: String },
+}
+
+/// Everything needed to call the API, secret included. Never serialized.
+#[derive(Clone)]
+pub struct JiraConfig {
+    pub instance_url: String,
+    pub auth: JiraAuth,
+    /// Stop paging once this many issues have been fetched
+    pub max_results: usize,
+}
+
+/// Issues fetched per connection when the connection sets no limit
+pub const DEFAULT_MAX_RESULTS: usize = 1000;
+
+/// Page size requested from the search endpoint
+const PAGE_SIZE: usize = 100;
+
+/// The default list: unresolved issues assigned to the user
+const ASSIGNED_JQL: &str =
+    "assignee = currentUser() AND resolution = Unresolved ORDER BY updated DESC";
+
 // -- Raw JSON shapes from Jira REST API --
 
+/// One page of /rest/api/3/search/jql. `nextPageToken` is absent on the
+/// last page.
+///
+/// Server / Data Center only has /rest/api/2/search, paged by `startAt`
+/// up to `total`.
 #[derive(Deserialize)]
 struct RawSearchResult {
+    #[serde(default)]
     issues: Vec<RawIssue>,
+    total: Option<usize>,
+    #[serde(rename = "nextPageToken")]
+    next_page_token: Option<String>,
+    #[serde(rename = "isLast", default)]
+    is_last: bool,
 }
 
 #[derive(Deserialize)]
@@ -76,6 +173,27 @@ struct RawProject {
     name: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct RawServerInfo {
+    #[serde(rename = "deploymentType")]
+    deployment_type: Option<String>,
+}
+
+/// One issue with `expand=renderedFields,names`: every field rendered as
+/// HTML, plus display names to find custom fields by
+#[derive(Deserialize)]
+struct RawIssueDetails {
+    #[serde(rename = "renderedFields", default)]
+    rendered_fields: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    names: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct RawWorklog {
+    id: String,
+}
+
 #[derive(Deserialize)]
 struct RawMyself {
     #[serde(rename = "displayName")]
@@ -93,98 +211,199 @@ fn basic_auth_value(email: &str, token: &str) -> String {
     format!("Basic {}", encoded)
 }
 
-fn build_headers(email: &str, token: &str) -> HeaderMap {
+fn build_headers(auth: &JiraAuth) -> HeaderMap {
+    let value = match auth {
+        JiraAuth::Basic { email, api_token } => basic_auth_value(email, api_token),
+        JiraAuth::Bearer { token } => format!("Bearer {}", token),
+    };
     let mut headers = HeaderMap::new();
-    headers.insert(
-        AUTHORIZATION,
-        HeaderValue::from_str(&basic_auth_value(email, token)).unwrap(),
-    );
+    headers.insert(AUTHORIZATION, HeaderValue::from_str(&value).unwrap());
     headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
     headers
 }
 
+/// Site and credentials (secret from the keychain) of a Jira connection.
+/// PAT connections are Server / Data Center; API token ones are Cloud.
+pub fn config_for(connection: &Connection) -> AuraResult<JiraConfig> {
+    let auth = match connection.auth_method {
+        AuthMethod::Pat => JiraAuth::Bearer {
+            token: connection.secret()?,
+        },
+        _ => JiraAuth::Basic {
+            email: connection.required(&connection.config.email, "email")?,
+            api_token: connection.secret()?,
+        },
+    };
+    Ok(JiraConfig {
+        instance_url: connection.required(&connection.config.instance_url, "instance URL")?,
+        auth,
+        max_results: connection
+            .config
+            .max_results
+            .map_or(DEFAULT_MAX_RESULTS, |n| n as usize),
+    })
+}
+
+async fn require_config(app: &AppHandle, connection_id: &str) -> AuraResult<JiraConfig> {
+    config_for(&connection_for(app, connection_id, ProviderKind::Jira).await?)
+}
+
 fn normalize_url(url: &str) -> String {
     url.trim().trim_end_matches('/').to_string()
 }
 
-// -- Tauri commands --
+fn config_email(config: &JiraConfig) -> Option<String> {
+    match &config.auth {
+        JiraAuth::Basic { email, .. } => Some(email.clone()),
+        JiraAuth::Bearer { .. } => None,
+    }
+}
 
-/// Verify Jira credentials by calling /rest/api/3/myself
-#[tauri::command]
-pub async fn check_jira_auth(
-    instance_url: String,
-    email: String,
-    api_token: String,
-) -> Result<JiraAuthStatus, String> {
-    let base = normalize_url(&instance_url);
-
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(format!("{}/rest/api/3/myself", base))
-        .headers(build_headers(&email, &api_token))
-        .send()
+// -- Deployment detection --
+
+/// Detected deployments by site, so serverInfo is read once per run
+static DEPLOYMENTS: Mutex<BTreeMap<String, JiraDeployment>> = Mutex::new(BTreeMap::new());
+
+/// Read `deploymentType` from /rest/api/2/serverInfo, which both Cloud and
+/// Server answer anonymously. Falls back to guessing from the host.
+pub async fn detect_deployment(http: &HttpClient, instance_url: &str) -> JiraDeployment {
+    let base = normalize_url(instance_url);
+    if let Some(deployment) = DEPLOYMENTS.lock().unwrap().get(&base) {
+        return *deployment;
+    }
+
+    let detected = match http
+        .send(
+            http.get(format!("{}/rest/api/2/serverInfo", base))
+                .header(ACCEPT, "application/json"),
+        )
         .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+    {
+        Ok(resp) if resp.status().is_success() => resp
+            .json::<RawServerInfo>()
+            .await
+            .ok()
+            .and_then(|info| info.deployment_type)
+            .map(|t| JiraDeployment::from_type(&t)),
+        _ => None,
+    };
+
+    match detected {
+        Some(deployment) => {
+            DEPLOYMENTS.lock().unwrap().insert(base, deployment);
+            deployment
+        }
+        None => JiraDeployment::from_url(&base),
+    }
+}
+
+// -- API functions --
+
+/// Verify Jira credentials by calling `myself` on the instance's API version
+pub async fn verify_credentials(
+    http: &HttpClient,
+    config: &JiraConfig,
+) -> AuraResult<JiraAuthStatus> {
+    let base = normalize_url(&config.instance_url);
+    let deployment = detect_deployment(http, &base).await;
+
+    let resp = http
+        .send(
+            http.get(format!("{}{}/myself", base, deployment.api_path()))
+                .headers(build_headers(&config.auth)),
+        )
+        .await?;
 
     if !resp.status().is_success() {
         return Ok(JiraAuthStatus {
             valid: false,
             display_name: None,
-            email: Some(email),
+            email: config_email(config),
+            deployment: Some(deployment),
         });
     }
 
-    let myself: RawMyself = resp
-        .json()
-        .await
-        .map_err(|e| format!("Parse error: {}", e))?;
+    let myself: RawMyself = resp.json().await.map_err(AuraError::from_reqwest)?;
 
     Ok(JiraAuthStatus {
         valid: true,
         display_name: myself.display_name,
-        email: myself.email_address.or(Some(email)),
+        email: myself.email_address.or_else(|| config_email(config)),
+        deployment: Some(deployment),
     })
 }
 
 /// Fetch open issues assigned to the current user
-#[tauri::command]
-pub async fn jira_fetch_issues(
-    instance_url: String,
-    email: String,
-    api_token: String,
-) -> Result<Vec<JiraIssue>, String> {
-    let base = normalize_url(&instance_url);
-    let jql = "assignee = currentUser() AND resolution = Unresolved ORDER BY updated DESC";
-
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(format!("{}/rest/api/3/search", base))
-        .headers(build_headers(&email, &api_token))
-        .query(&[
-            ("jql", jql),
-            ("maxResults", "100"),
+pub async fn fetch_assigned_issues(
+    http: &HttpClient,
+    config: &JiraConfig,
+) -> AuraResult<Vec<JiraIssue>> {
+    search_issues(http, config, ASSIGNED_JQL).await
+}
+
+/// Run a JQL search, following `nextPageToken` (Cloud) or `startAt`
+/// (Server / Data Center) until every issue or `config.max_results` is
+/// fetched
+pub async fn search_issues(
+    http: &HttpClient,
+    config: &JiraConfig,
+    jql: &str,
+) -> AuraResult<Vec<JiraIssue>> {
+    let base = normalize_url(&config.instance_url);
+    let deployment = detect_deployment(http, &base).await;
+    let search_url = match deployment {
+        JiraDeployment::Cloud => format!("{}/rest/api/3/search/jql", base),
+        _ => format!("{}/rest/api/2/search", base),
+    };
+    let mut raw_issues: Vec<RawIssue> = Vec::new();
+    let mut page_token: Option<String> = None;
+    while raw_issues.len() < config.max_results {
+        let page_size = PAGE_SIZE.min(config.max_results - raw_issues.len());
+        let mut query = vec![
+            ("jql", jql.to_string()),
+            ("maxResults", page_size.to_string()),
             (
                 "fields",
-                "summary,status,issuetype,priority,updated,labels,project",
+                "summary,status,issuetype,priority,updated,labels,project".to_string(),
             ),
-        ])
-        .send()
-        .await
-        .map_err(|e| format!("Request failed: {}", e))?;
+        ];
+        if deployment != JiraDeployment::Cloud {
+            query.push(("startAt", raw_issues.len().to_string()));
+        } else if let Some(token) = page_token.take() {
+            query.push(("nextPageToken", token));
+        }
 
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Jira API error {}: {}", status, body));
-    }
+        let resp = http
+            .send(
+                http.get(&search_url)
+                    .headers(build_headers(&config.auth))
+                    .query(&query),
+            )
+            .await?;
 
-    let result: RawSearchResult = resp
-        .json()
-        .await
-        .map_err(|e| format!("Parse error: {}", e))?;
+        if !resp.status().is_success() {
+            return Err(AuraError::from_response(ProviderKind::Jira, resp).await);
+        }
 
-    let issues = result
-        .issues
+        let page: RawSearchResult = resp.json().await.map_err(AuraError::from_reqwest)?;
+        if page.issues.is_empty() {
+            break;
+        }
+        raw_issues.extend(page.issues);
+        if deployment != JiraDeployment::Cloud {
+            if page.total.is_none_or(|total| raw_issues.len() >= total) {
+                break;
+            }
+        } else {
+            match page.next_page_token {
+                Some(token) if !page.is_last => page_token = Some(token),
+                _ => break,
+            }
+        }
+    }
+    raw_issues.truncate(config.max_results);
+
+    let issues = raw_issues
         .into_iter()
         .map(|raw| {
             let status_color = raw
@@ -227,3 +446,349 @@ pub async fn jira_fetch_issues(
 
     Ok(issues)
 }
+
+/// Description and acceptance criteria of one issue. Acceptance criteria
+/// are a custom field, found by display name.
+pub async fn fetch_issue_details(
+    http: &HttpClient,
+    config: &JiraConfig,
+    key: &str,
+) -> AuraResult<WorkItemDetails> {
+    let base = normalize_url(&config.instance_url);
+    let deployment = detect_deployment(http, &base).await;
+    let resp = http
+        .send(
+            http.get(format!("{}{}/issue/{}", base, deployment.api_path(), key))
+                .headers(build_headers(&config.auth))
+                .query(&[("expand", "renderedFields,names")]),
+        )
+        .await?;
+    if !resp.status().is_success() {
+        return Err(AuraError::from_response(ProviderKind::Jira, resp).await);
+    }
+    let raw: RawIssueDetails = resp.json().await.map_err(AuraError::from_reqwest)?;
+
+    let rendered = |field: &str| {
+        raw.rendered_fields
+            .get(field)
+            .and_then(|v| v.as_str())
+            .map(html_to_text)
+            .filter(|t| !t.is_empty())
+    };
+    let acceptance_field = raw
+        .names
+        .iter()
+        .find(|(_, name)| name.to_lowercase().contains("acceptance criteria"))
+        .map(|(id, _)| id.as_str());
+    Ok(WorkItemDetails {
+        description: rendered("description"),
+        acceptance_criteria: acceptance_field.and_then(rendered),
+    })
+}
+
+/// Add a worklog to an issue and return its id. REST v3 wants the comment
+/// as an Atlassian document; v2 takes plain text.
+pub async fn add_worklog(
+    http: &HttpClient,
+    config: &JiraConfig,
+    key: &str,
+    entry: &WorkLogEntry,
+) -> AuraResult<String> {
+    let base = normalize_url(&config.instance_url);
+    let deployment = detect_deployment(http, &base).await;
+    let comment = match deployment {
+        JiraDeployment::Cloud => json!({
+            "type": "doc",
+            "version": 1,
+            "content": [{
+                "type": "paragraph",
+                "content": [{ "type": "text", "text": entry.comment }],
+            }],
+        }),
+        JiraDeployment::Server | JiraDeployment::DataCenter => json!(entry.comment),
+    };
+    let resp = http
+        .send_once(
+            http.post(format!(
+                "{}{}/issue/{}/worklog",
+                base,
+                deployment.api_path(),
+                key
+            ))
+            .headers(build_headers(&config.auth))
+            .json(&json!({
+                "started": entry.started.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string(),
+                "timeSpentSeconds": entry.seconds,
+                "comment": comment,
+            })),
+        )
+        .await?;
+    if !resp.status().is_success() {
+        return Err(AuraError::from_response(ProviderKind::Jira, resp).await);
+    }
+    let worklog: RawWorklog = resp.json().await.map_err(AuraError::from_reqwest)?;
+    Ok(worklog.id)
+}
+
+// -- Provider --
+
+/// Jira groups workflow statuses into categories whose colours are stable:
+/// blue-gray (To Do), yellow (In Progress) and green (Done).
+fn status_category(issue: &JiraIssue) -> StatusCategory {
+    match issue.status_color.as_str() {
+        "yellow" => StatusCategory::InProgress,
+        "green" => match StatusCategory::from_state_name(&issue.status) {
+            StatusCategory::Closed => StatusCategory::Closed,
+            _ => StatusCategory::Resolved,
+        },
+        _ => StatusCategory::Open,
+    }
+}
+
+fn to_work_item(connection_id: &str, issue: JiraIssue) -> WorkItem {
+    let status_category = status_category(&issue);
+    let mut item = WorkItem::new(
+        connection_id,
+        ProviderKind::Jira,
+        WorkItemKind::Issue,
+        issue.key.clone(),
+        issue.summary,
+        issue.url,
+    )
+    .with_extra("key", issue.key)
+    .with_extra("status_color", issue.status_color);
+
+    item.status = issue.status;
+    item.status_category = status_category;
+    item.priority = issue.priority;
+    item.item_type = Some(issue.issue_type);
+    item.labels = issue.labels;
+    item.project = issue.project;
+    item.updated_at = normalize_timestamp(&issue.updated);
+    item
+}
+
+pub struct JiraProvider {
+    http: HttpClient,
+    connection: Connection,
+}
+
+impl JiraProvider {
+    pub fn new(http: HttpClient, connection: Connection) -> Self {
+        JiraProvider { http, connection }
+    }
+}
+
+#[async_trait]
+impl Provider for JiraProvider {
+    fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    async fn check_auth(&self) -> AuraResult<ProviderStatus> {
+        let status = verify_credentials(&self.http, &config_for(&self.connection)?).await?;
+        Ok(ProviderStatus {
+            connection_id: self.connection.id.clone(),
+            provider: ProviderKind::Jira,
+            authenticated: status.valid,
+            account: status.display_name.or(status.email),
+        })
+    }
+
+    async fn fetch_assigned(&self) -> AuraResult<Fetched> {
+        let issues = fetch_assigned_issues(&self.http, &config_for(&self.connection)?).await?;
+        Ok(issues
+            .into_iter()
+            .map(|i| to_work_item(&self.connection.id, i))
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn search(&self, query: &str) -> AuraResult<Fetched> {
+        let issues = search_issues(&self.http, &config_for(&self.connection)?, query).await?;
+        Ok(issues
+            .into_iter()
+            .map(|i| to_work_item(&self.connection.id, i))
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn fetch_details(&self, item: &WorkItem) -> AuraResult<WorkItemDetails> {
+        fetch_issue_details(
+            &self.http,
+            &config_for(&self.connection)?,
+            &item.provider_id,
+        )
+        .await
+    }
+
+    async fn log_work(&self, item: &WorkItem, entry: &WorkLogEntry) -> AuraResult<Option<String>> {
+        let id = add_worklog(
+            &self.http,
+            &config_for(&self.connection)?,
+            &item.provider_id,
+            entry,
+        )
+        .await?;
+        Ok(Some(id))
+    }
+}
+
+// -- Tauri commands --
+
+/// Verify a Jira connection's credentials by calling `myself`
+#[tauri::command]
+pub async fn check_jira_auth(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<JiraAuthStatus> {
+    let config = require_config(&app, &connection_id).await?;
+    verify_credentials(&http, &config).await
+}
+
+/// Fetch open issues assigned to the current user
+#[tauri::command]
+pub async fn jira_fetch_issues(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    connection_id: String,
+) -> AuraResult<Vec<JiraIssue>> {
+    let config = require_config(&app, &connection_id).await?;
+    fetch_assigned_issues(&http, &config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, Requests};
+
+    #[test]
+    fn test_deployment_from_server_info_type() {
+        assert_eq!(JiraDeployment::from_type("Cloud"), JiraDeployment::Cloud);
+        assert_eq!(
+            JiraDeployment::from_type("DataCenter"),
+            JiraDeployment::DataCenter
+        );
+        assert_eq!(JiraDeployment::from_type("Server"), JiraDeployment::Server);
+        assert_eq!(JiraDeployment::DataCenter.api_path(), "/rest/api/2");
+    }
+
+    #[test]
+    fn test_deployment_guessed_from_host() {
+        assert_eq!(
+            JiraDeployment::from_url("https://acme.atlassian.net"),
+            JiraDeployment::Cloud
+        );
+        assert_eq!(
+            JiraDeployment::from_url("https://jira.acme.com:8443/jira"),
+            JiraDeployment::Server
+        );
+    }
+
+    /// Local Jira of the given deployment type whose searches answer with
+    /// `page`, given the request's `startAt` or `nextPageToken`
+    fn mock_jira(
+        deployment_type: &'static str,
+        page: impl Fn(Option<&str>) -> serde_json::Value + Send + 'static,
+    ) -> (JiraConfig, Requests) {
+        let (url, requests) = test_support::mock_server(move |request| {
+            if request.path.starts_with("/rest/api/2/serverInfo") {
+                return (
+                    200,
+                    json!({ "deploymentType": deployment_type }).to_string(),
+                );
+            }
+            let cursor = request
+                .query("nextPageToken")
+                .or_else(|| request.query("startAt"));
+            (200, page(cursor).to_string())
+        });
+        let config = JiraConfig {
+            instance_url: url,
+            auth: JiraAuth::Bearer {
+                token: "test-token".into(),
+            },
+            max_results: DEFAULT_MAX_RESULTS,
+        };
+        (config, requests)
+    }
+
+    fn issues(keys: std::ops::Range<usize>) -> Vec<serde_json::Value> {
+        keys.map(|n| json!({ "key": format!("PROJ-{}", n), "fields": {} }))
+            .collect()
+    }
+
+    fn keys(issues: &[JiraIssue]) -> Vec<&str> {
+        issues.iter().map(|i| i.key.as_str()).collect()
+    }
+
+    /// `param` of every search request, in order
+    fn searched(requests: &Requests, param: &str) -> Vec<Option<String>> {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.path.contains("/search"))
+            .map(|r| r.query(param).map(String::from))
+            .collect()
+    }
+
+    #[test]
+    fn test_cloud_search_follows_page_tokens() {
+        let (config, requests) = mock_jira("Cloud", |token| match token {
+            None => json!({ "issues": issues(1..3), "nextPageToken": "p2" }),
+            Some("p2") => json!({ "issues": issues(3..5), "nextPageToken": "p3" }),
+            _ => json!({ "issues": issues(5..7), "isLast": true }),
+        });
+        let http = HttpClient::new();
+
+        let found = tauri::async_runtime::block_on(search_issues(&http, &config, "x")).unwrap();
+        assert_eq!(
+            keys(&found),
+            ["PROJ-1", "PROJ-2", "PROJ-3", "PROJ-4", "PROJ-5", "PROJ-6"]
+        );
+        assert_eq!(
+            searched(&requests, "nextPageToken"),
+            [None, Some("p2".into()), Some("p3".into())]
+        );
+        assert!(requests.lock().unwrap()[1]
+            .path
+            .starts_with("/rest/api/3/search/jql"));
+    }
+
+    #[test]
+    fn test_server_search_pages_by_start_at_until_total() {
+        let (config, requests) = mock_jira("Server", |start_at| {
+            let start: usize = start_at.and_then(|s| s.parse().ok()).unwrap_or(0);
+            json!({ "issues": issues(start..(start + 2).min(5)), "total": 5 })
+        });
+        let http = HttpClient::new();
+
+        let found = tauri::async_runtime::block_on(search_issues(&http, &config, "x")).unwrap();
+        assert_eq!(found.len(), 5);
+        assert_eq!(
+            searched(&requests, "startAt"),
+            [Some("0".into()), Some("2".into()), Some("4".into())]
+        );
+    }
+
+    #[test]
+    fn test_search_stops_at_max_results() {
+        // Pages of two, whatever size is asked for
+        let (mut config, requests) = mock_jira("Cloud", |token| match token {
+            None => json!({ "issues": issues(1..3), "nextPageToken": "p2" }),
+            _ => json!({ "issues": issues(3..5), "nextPageToken": "p3" }),
+        });
+        config.max_results = 3;
+        let http = HttpClient::new();
+
+        let found = tauri::async_runtime::block_on(search_issues(&http, &config, "x")).unwrap();
+        assert_eq!(keys(&found), ["PROJ-1", "PROJ-2", "PROJ-3"]);
+        // Only what is still missing is asked for, and paging stops there
+        assert_eq!(
+            searched(&requests, "maxResults"),
+            [Some("3".into()), Some("1".into())]
+        );
+    }
+}
@@ -1,48 +1,114 @@
 mod auth;
 mod azure;
+mod connections;
+mod context;
+mod credentials;
+mod db;
+mod error;
 mod fogbugz;
 mod github;
+mod http;
 mod jira;
+mod links;
+mod provider;
+mod queries;
 mod repos;
+mod scan_roots;
+mod session_registry;
 mod sessions;
+mod sync;
+#[cfg(test)]
+mod test_support;
+mod time_tracking;
 mod updater;
-
-use tauri_plugin_sql::{Migration, MigrationKind};
+mod workflow;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let migrations = vec![Migration {
-        version: 1,
-        description: "create initial tables",
-        sql: include_str!("../migrations/001_init.sql"),
-        kind: MigrationKind::Up,
-    }];
-
     tauri::Builder::default()
         .manage(updater::UpdaterState(std::sync::Mutex::new(None)))
+        .manage(sync::SyncState::default())
+        .manage(session_registry::SessionRegistry::default())
+        .manage(http::HttpClient::new())
         .plugin(
             tauri_plugin_sql::Builder::default()
-                .add_migrations("sqlite:aura.db", migrations)
+                .add_migrations(db::DB_URL, db::migrations())
                 .build(),
         )
         .plugin(tauri_plugin_shell::init())
+        .setup(|app| {
+            // Best effort: whatever is left in the settings table is moved
+            // on the next launch
+            let handle = app.handle().clone();
+            let _ = tauri::async_runtime::block_on(async move {
+                let pool = db::pool(&handle).await?;
+                connections::migrate_legacy_secrets(&pool).await
+            });
+            sync::start_scheduler(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             auth::check_github_auth,
-            auth::get_github_token,
+            auth::save_github_pat,
+            auth::validate_github_pat,
+            auth::delete_github_pat,
             github::github_fetch_issues,
             github::github_fetch_prs,
             github::github_fetch_user,
             repos::scan_repos,
+            repos::list_repos,
             repos::list_directories,
+            scan_roots::list_scan_roots,
+            scan_roots::create_scan_root,
+            scan_roots::update_scan_root,
+            scan_roots::delete_scan_root,
             sessions::detect_session_tools,
             sessions::launch_session,
+            sessions::launch_session_for_item,
+            sessions::create_session_tool,
+            sessions::update_session_tool,
+            sessions::delete_session_tool,
+            session_registry::list_sessions,
+            session_registry::focus_session,
+            session_registry::kill_session,
             azure::check_azure_auth,
             azure::azure_fetch_work_items,
             azure::azure_fetch_prs,
+            azure::list_azure_projects,
+            azure::add_azure_project,
+            azure::remove_azure_project,
+            connections::list_connections,
+            connections::create_connection,
+            connections::update_connection,
+            connections::delete_connection,
+            connections::test_connection,
             jira::check_jira_auth,
             jira::jira_fetch_issues,
             fogbugz::check_fogbugz_auth,
             fogbugz::fogbugz_fetch_cases,
+            links::list_links,
+            links::create_link,
+            links::delete_link,
+            links::auto_link,
+            provider::check_all_providers,
+            provider::fetch_all_work_items,
+            provider::fetch_all_pull_requests,
+            queries::list_saved_queries,
+            queries::create_saved_query,
+            queries::update_saved_query,
+            queries::delete_saved_query,
+            queries::run_saved_query,
+            sync::get_cached_work_items,
+            sync::sync_work_items,
+            sync::get_sync_status,
+            sync::set_sync_interval,
+            time_tracking::get_tracked_time,
+            time_tracking::adjust_tracked_time,
+            time_tracking::export_worklog,
+            time_tracking::settle_worklog_export,
+            workflow::get_branch_template,
+            workflow::set_branch_template,
+            workflow::start_work_on_item,
             updater::check_for_updates,
             updater::download_update,
             updater::install_update,
@@ -0,0 +1,685 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::{AuraError, AuraResult};
+use crate::provider::{ProviderKind, WorkItem, WorkItemKind};
+
+// -- Types --
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkKind {
+    Branch,
+    PullRequest,
+    Repo,
+}
+
+impl LinkKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LinkKind::Branch => "branch",
+            LinkKind::PullRequest => "pull_request",
+            LinkKind::Repo => "repo",
+        }
+    }
+}
+
+impl std::str::FromStr for LinkKind {
+    type Err = AuraError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "branch" => Ok(LinkKind::Branch),
+            "pull_request" => Ok(LinkKind::PullRequest),
+            "repo" => Ok(LinkKind::Repo),
+            _ => Err(AuraError::invalid_input(format!(
+                "Unknown link kind: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// A work item linked to a branch, pull request or repo. Titles and URLs
+/// come from the work item cache and are missing for items not synced yet.
+#[derive(Debug, Serialize, Clone)]
+pub struct Link {
+    pub id: String,
+    pub work_item_id: String,
+    pub kind: LinkKind,
+    pub repo_path: Option<String>,
+    /// Branch name or pull request id; empty for repo links
+    pub target: String,
+    /// Added by hand rather than detected from a ticket key
+    pub manual: bool,
+    pub work_item_title: Option<String>,
+    pub work_item_url: Option<String>,
+    /// Native key of the work item (`PROJ-123`, `owner/repo#12`, ...)
+    pub work_item_key: Option<String>,
+    pub target_title: Option<String>,
+    pub target_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewLink {
+    pub work_item_id: String,
+    pub kind: LinkKind,
+    pub repo_path: Option<String>,
+    pub target: Option<String>,
+}
+
+/// A link found by the auto-linker, before it is stored
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DetectedLink {
+    work_item_id: String,
+    kind: LinkKind,
+    repo_path: String,
+    target: String,
+}
+
+/// Local branches of one repo, read for the auto-linker
+struct RepoBranches {
+    path: String,
+    /// "owner/repo" of `origin`, to resolve bare `#123` references
+    github_repo: Option<String>,
+    branches: Vec<String>,
+}
+
+// -- Ticket references --
+
+/// A ticket key found in a branch name or pull request title
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum TicketRef {
+    /// `PROJ-123`
+    Jira(String),
+    /// `#456`, resolved against a GitHub repo
+    GitHub(u64),
+    /// `AB#789`
+    Azure(u64),
+    /// `case 12`, `case-12`, `fb12`, `BugzID: 12`
+    FogBugz(u64),
+}
+
+const FOGBUGZ_PREFIXES: [&str; 3] = ["bugzid", "case", "fb"];
+
+/// Digits starting at `start` that end on a word boundary, and the index
+/// after them
+fn number_at(bytes: &[u8], start: usize) -> Option<(u64, usize)> {
+    let end = start
+        + bytes[start.min(bytes.len())..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+    if end == start || end - start > 12 {
+        return None;
+    }
+    if bytes.get(end).is_some_and(|b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+    let n = std::str::from_utf8(&bytes[start..end]).ok()?.parse().ok()?;
+    Some((n, end))
+}
+
+/// Every ticket reference in a branch name or title. Matches are loose on
+/// purpose: a reference only becomes a link if a cached work item has it.
+fn ticket_refs(text: &str) -> Vec<TicketRef> {
+    let bytes = text.as_bytes();
+    let mut refs = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let at_boundary = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+
+        if bytes[i] == b'#' {
+            let azure = i >= 2
+                && bytes[i - 2..i].eq_ignore_ascii_case(b"AB")
+                && (i == 2 || !bytes[i - 3].is_ascii_alphanumeric());
+            if azure || at_boundary {
+                if let Some((n, end)) = number_at(bytes, i + 1) {
+                    refs.push(if azure {
+                        TicketRef::Azure(n)
+                    } else {
+                        TicketRef::GitHub(n)
+                    });
+                    i = end;
+                    continue;
+                }
+            }
+        } else if at_boundary && bytes[i].is_ascii_alphabetic() {
+            let end = i + bytes[i..]
+                .iter()
+                .take_while(|b| b.is_ascii_alphanumeric())
+                .count();
+            let word = text[i..end].to_ascii_lowercase();
+
+            for prefix in FOGBUGZ_PREFIXES {
+                let Some(rest) = word.strip_prefix(prefix) else {
+                    continue;
+                };
+                if !rest.is_empty() {
+                    if let Ok(n) = rest.parse() {
+                        refs.push(TicketRef::FogBugz(n));
+                    }
+                } else {
+                    // "case 12", "case-12", "BugzID: 12"
+                    let digits = end
+                        + bytes[end..]
+                            .iter()
+                            .take_while(|b| matches!(b, b' ' | b'-' | b'_' | b':'))
+                            .count();
+                    if digits > end {
+                        if let Some((n, _)) = number_at(bytes, digits) {
+                            refs.push(TicketRef::FogBugz(n));
+                        }
+                    }
+                }
+                break;
+            }
+
+            if word.len() >= 2 && bytes.get(end) == Some(&b'-') {
+                if let Some((n, after)) = number_at(bytes, end + 1) {
+                    refs.push(TicketRef::Jira(format!(
+                        "{}-{}",
+                        text[i..end].to_ascii_uppercase(),
+                        n
+                    )));
+                    i = after;
+                    continue;
+                }
+            }
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+    refs
+}
+
+/// Whether a cached issue is the one a reference points to. `repo` is the
+/// GitHub repo a bare `#123` belongs to, if known.
+fn refers_to(item: &WorkItem, reference: &TicketRef, repo: Option<&str>) -> bool {
+    if item.kind != WorkItemKind::Issue {
+        return false;
+    }
+    match reference {
+        TicketRef::Jira(key) => {
+            item.provider == ProviderKind::Jira && item.provider_id.eq_ignore_ascii_case(key)
+        }
+        TicketRef::GitHub(n) => {
+            item.provider == ProviderKind::Github
+                && repo.is_some_and(|repo| {
+                    item.provider_id
+                        .eq_ignore_ascii_case(&format!("{}#{}", repo, n))
+                })
+        }
+        TicketRef::Azure(n) => {
+            item.provider == ProviderKind::AzureDevops
+                && item.extra.get("work_item_id").and_then(|v| v.as_u64()) == Some(*n)
+        }
+        TicketRef::FogBugz(n) => {
+            item.provider == ProviderKind::Fogbugz && item.provider_id == n.to_string()
+        }
+    }
+}
+
+fn issues_for<'a>(issues: &'a [WorkItem], text: &str, repo: Option<&str>) -> Vec<&'a WorkItem> {
+    let refs = ticket_refs(text);
+    issues
+        .iter()
+        .filter(|item| refs.iter().any(|r| refers_to(item, r, repo)))
+        .collect()
+}
+
+/// Links implied by ticket keys in branch names and pull request titles
+/// (and Azure source branches)
+fn detect_links(
+    issues: &[WorkItem],
+    pull_requests: &[WorkItem],
+    repos: &[RepoBranches],
+) -> Vec<DetectedLink> {
+    let mut links = Vec::new();
+    let mut seen = HashSet::new();
+    let mut add = |link: DetectedLink| {
+        if seen.insert(link.clone()) {
+            links.push(link);
+        }
+    };
+
+    for repo in repos {
+        for branch in &repo.branches {
+            for item in issues_for(issues, branch, repo.github_repo.as_deref()) {
+                add(DetectedLink {
+                    work_item_id: item.id.clone(),
+                    kind: LinkKind::Branch,
+                    repo_path: repo.path.clone(),
+                    target: branch.clone(),
+                });
+                add(DetectedLink {
+                    work_item_id: item.id.clone(),
+                    kind: LinkKind::Repo,
+                    repo_path: repo.path.clone(),
+                    target: String::new(),
+                });
+            }
+        }
+    }
+
+    for pr in pull_requests {
+        let extra = |key: &str| pr.extra.get(key).and_then(|v| v.as_str());
+        let text = format!(
+            "{} {}",
+            pr.title,
+            extra("source_branch").unwrap_or_default()
+        );
+        let repo = match pr.provider {
+            ProviderKind::Github => extra("repository"),
+            _ => None,
+        };
+        for item in issues_for(issues, &text, repo) {
+            add(DetectedLink {
+                work_item_id: item.id.clone(),
+                kind: LinkKind::PullRequest,
+                repo_path: String::new(),
+                target: pr.id.clone(),
+            });
+        }
+    }
+
+    links
+}
+
+fn read_repo_branches(path: &Path) -> RepoBranches {
+    let mut branches = crate::repos::list_branches(path);
+    let current = crate::repos::get_current_branch(path);
+    if current != "HEAD" && current != "unknown" && !branches.contains(&current) {
+        branches.push(current);
+    }
+    RepoBranches {
+        path: path.to_string_lossy().to_string(),
+        github_repo: crate::repos::origin_repo(path),
+        branches,
+    }
+}
+
+// -- Storage --
+
+type LinkRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+const SELECT_LINKS: &str = "SELECT l.id, l.work_item_id, l.kind, l.repo_path, l.target, l.source,
+        json_extract(w.data, '$.title'), json_extract(w.data, '$.url'), json_extract(w.data, '$.provider_id'),
+        json_extract(p.data, '$.title'), json_extract(p.data, '$.url')
+    FROM links l
+    LEFT JOIN work_items w ON w.id = l.work_item_id
+    LEFT JOIN work_items p ON p.id = l.target AND l.kind = 'pull_request'";
+
+fn from_row(row: LinkRow) -> AuraResult<Link> {
+    let (
+        id,
+        work_item_id,
+        kind,
+        repo_path,
+        target,
+        source,
+        work_item_title,
+        work_item_url,
+        work_item_key,
+        target_title,
+        target_url,
+    ) = row;
+    Ok(Link {
+        id,
+        work_item_id,
+        kind: kind.parse()?,
+        repo_path: Some(repo_path).filter(|p| !p.is_empty()),
+        target,
+        manual: source == "manual",
+        work_item_title,
+        work_item_url,
+        work_item_key,
+        target_title,
+        target_url,
+    })
+}
+
+async fn load_link(pool: &SqlitePool, id: &str) -> AuraResult<Link> {
+    let row = sqlx::query_as::<_, LinkRow>(&format!("{} WHERE l.id = ?", SELECT_LINKS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AuraError::invalid_input(format!("Unknown link: {}", id)))?;
+    from_row(row)
+}
+
+async fn cached_items(pool: &SqlitePool, kind: WorkItemKind) -> AuraResult<Vec<WorkItem>> {
+    let rows = sqlx::query_scalar::<_, String>("SELECT data FROM work_items WHERE kind = ?")
+        .bind(kind.as_str())
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .iter()
+        .filter_map(|data| serde_json::from_str(data).ok())
+        .collect())
+}
+
+/// Replace the auto-detected links of the scanned repos (`None` when every
+/// repo under the scan roots was scanned) and all auto-detected pull request
+/// links, which are re-derived from the whole cache. Manual links and the
+/// branch links of other repos are left alone.
+async fn store_detected(
+    pool: &SqlitePool,
+    scanned: Option<&[String]>,
+    links: &[DetectedLink],
+) -> AuraResult<()> {
+    let mut tx = pool.begin().await?;
+    match scanned {
+        None => {
+            sqlx::query("DELETE FROM links WHERE source = 'auto'")
+                .execute(&mut *tx)
+                .await?;
+        }
+        Some(repo_paths) => {
+            sqlx::query("DELETE FROM links WHERE source = 'auto' AND kind = 'pull_request'")
+                .execute(&mut *tx)
+                .await?;
+            for repo_path in repo_paths {
+                sqlx::query(
+                    "DELETE FROM links WHERE source = 'auto' AND kind != 'pull_request' AND repo_path = ?",
+                )
+                .bind(repo_path)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+    }
+    for link in links {
+        sqlx::query(
+            "INSERT OR IGNORE INTO links (id, work_item_id, kind, repo_path, target, source) VALUES (?, ?, ?, ?, ?, 'auto')",
+        )
+        .bind(crate::db::new_id())
+        .bind(&link.work_item_id)
+        .bind(link.kind.as_str())
+        .bind(&link.repo_path)
+        .bind(&link.target)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await.map_err(AuraError::from)
+}
+
+pub(crate) async fn query_links(
+    pool: &SqlitePool,
+    work_item_id: Option<&str>,
+    repo_path: Option<&str>,
+) -> AuraResult<Vec<Link>> {
+    let rows = sqlx::query_as::<_, LinkRow>(&format!(
+        "{} WHERE (?1 IS NULL OR l.work_item_id = ?1) AND (?2 IS NULL OR l.repo_path = ?2)
+         ORDER BY l.work_item_id, l.kind, l.target",
+        SELECT_LINKS
+    ))
+    .bind(work_item_id)
+    .bind(repo_path)
+    .fetch_all(pool)
+    .await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+/// Store a manual link and return its id. Linking by hand what the
+/// auto-linker found makes it permanent.
+pub(crate) async fn insert_manual(
+    pool: &SqlitePool,
+    work_item_id: &str,
+    kind: LinkKind,
+    repo_path: &str,
+    target: &str,
+) -> AuraResult<String> {
+    sqlx::query(
+        "INSERT INTO links (id, work_item_id, kind, repo_path, target, source) VALUES (?, ?, ?, ?, ?, 'manual')
+         ON CONFLICT (work_item_id, kind, repo_path, target) DO UPDATE SET source = 'manual'",
+    )
+    .bind(crate::db::new_id())
+    .bind(work_item_id)
+    .bind(kind.as_str())
+    .bind(repo_path)
+    .bind(target)
+    .execute(pool)
+    .await?;
+
+    sqlx::query_scalar::<_, String>(
+        "SELECT id FROM links WHERE work_item_id = ? AND kind = ? AND repo_path = ? AND target = ?",
+    )
+    .bind(work_item_id)
+    .bind(kind.as_str())
+    .bind(repo_path)
+    .bind(target)
+    .fetch_one(pool)
+    .await
+    .map_err(AuraError::from)
+}
+
+// -- Tauri commands --
+
+/// Links of one work item, of one repo, or all of them
+#[tauri::command]
+pub async fn list_links(
+    app: AppHandle,
+    work_item_id: Option<String>,
+    repo_path: Option<String>,
+) -> AuraResult<Vec<Link>> {
+    let pool = crate::db::pool(&app).await?;
+    query_links(&pool, work_item_id.as_deref(), repo_path.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn create_link(app: AppHandle, link: NewLink) -> AuraResult<Link> {
+    if link.work_item_id.trim().is_empty() {
+        return Err(AuraError::invalid_input("A link needs a work item."));
+    }
+    let repo_path = link.repo_path.unwrap_or_default().trim().to_string();
+    let target = link.target.unwrap_or_default().trim().to_string();
+    match link.kind {
+        LinkKind::Branch if repo_path.is_empty() || target.is_empty() => {
+            return Err(AuraError::invalid_input(
+                "A branch link needs a repo and a branch.",
+            ));
+        }
+        LinkKind::PullRequest if target.is_empty() => {
+            return Err(AuraError::invalid_input(
+                "A pull request link needs a pull request.",
+            ));
+        }
+        LinkKind::Repo if repo_path.is_empty() => {
+            return Err(AuraError::invalid_input("A repo link needs a repo."));
+        }
+        _ => {}
+    }
+
+    let pool = crate::db::pool(&app).await?;
+    let id = insert_manual(
+        &pool,
+        link.work_item_id.trim(),
+        link.kind,
+        &repo_path,
+        &target,
+    )
+    .await?;
+    load_link(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn delete_link(app: AppHandle, id: String) -> AuraResult<()> {
+    let pool = crate::db::pool(&app).await?;
+    sqlx::query("DELETE FROM links WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await?;
+    Ok(())
+}
+
+/// Detect links from ticket keys in local branch names and cached pull
+/// request titles. Scans the given repos, or every repo under the scan
+/// roots. Returns all links afterwards.
+#[tauri::command]
+pub async fn auto_link(app: AppHandle, repo_paths: Option<Vec<String>>) -> AuraResult<Vec<Link>> {
+    let pool = crate::db::pool(&app).await?;
+    let paths: Vec<PathBuf> = match &repo_paths {
+        Some(paths) => paths.iter().map(PathBuf::from).collect(),
+        None => {
+            let roots = crate::scan_roots::load_scan_roots(&pool).await?;
+            tauri::async_runtime::spawn_blocking(move || crate::repos::repo_paths(&roots))
+                .await
+                .map_err(AuraError::io)?
+        }
+    };
+    let repos = tauri::async_runtime::spawn_blocking(move || {
+        paths
+            .iter()
+            .map(|p| read_repo_branches(p))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(AuraError::io)?;
+
+    let issues = cached_items(&pool, WorkItemKind::Issue).await?;
+    let pull_requests = cached_items(&pool, WorkItemKind::PullRequest).await?;
+    let links = detect_links(&issues, &pull_requests, &repos);
+    store_detected(&pool, repo_paths.as_deref(), &links).await?;
+
+    list_links(app, None, None).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(provider: ProviderKind, provider_id: &str) -> WorkItem {
+        WorkItem::new(
+            provider.as_str(),
+            provider,
+            WorkItemKind::Issue,
+            provider_id.to_string(),
+            String::new(),
+            String::new(),
+        )
+    }
+
+    #[test]
+    fn test_ticket_refs_in_branch_names() {
+        assert_eq!(
+            ticket_refs("feature/PROJ-123-login"),
+            vec![TicketRef::Jira("PROJ-123".into())]
+        );
+        assert_eq!(ticket_refs("fix #456"), vec![TicketRef::GitHub(456)]);
+        assert_eq!(ticket_refs("AB#789 retry"), vec![TicketRef::Azure(789)]);
+        assert_eq!(ticket_refs("BugzID: 12"), vec![TicketRef::FogBugz(12)]);
+        assert!(ticket_refs("case-34").contains(&TicketRef::FogBugz(34)));
+        assert!(ticket_refs("main").is_empty());
+        assert!(ticket_refs("C#12").is_empty());
+    }
+
+    #[test]
+    fn test_detect_links_from_branches_and_prs() {
+        let jira = issue(ProviderKind::Jira, "PROJ-123");
+        let github = issue(ProviderKind::Github, "acme/web#456");
+        let pr = WorkItem::new(
+            "github",
+            ProviderKind::Github,
+            WorkItemKind::PullRequest,
+            "acme/web#460".into(),
+            "Fix login (#456)".into(),
+            String::new(),
+        )
+        .with_extra("repository", "acme/web");
+
+        let repos = vec![RepoBranches {
+            path: "/src/web".into(),
+            github_repo: Some("acme/web".into()),
+            branches: vec!["proj-123-login".into(), "main".into()],
+        }];
+        let links = detect_links(
+            &[jira.clone(), github.clone()],
+            std::slice::from_ref(&pr),
+            &repos,
+        );
+
+        assert!(links.contains(&DetectedLink {
+            work_item_id: jira.id.clone(),
+            kind: LinkKind::Branch,
+            repo_path: "/src/web".into(),
+            target: "proj-123-login".into(),
+        }));
+        assert!(links.contains(&DetectedLink {
+            work_item_id: jira.id,
+            kind: LinkKind::Repo,
+            repo_path: "/src/web".into(),
+            target: String::new(),
+        }));
+        assert!(links.contains(&DetectedLink {
+            work_item_id: github.id,
+            kind: LinkKind::PullRequest,
+            repo_path: String::new(),
+            target: pr.id,
+        }));
+        assert_eq!(links.len(), 3);
+    }
+
+    #[test]
+    fn test_rescan_keeps_auto_links_of_other_repos() {
+        let link = |kind: LinkKind, repo_path: &str, target: &str| DetectedLink {
+            work_item_id: "jira:PROJ-1".into(),
+            kind,
+            repo_path: repo_path.into(),
+            target: target.into(),
+        };
+        async fn targets(pool: &SqlitePool) -> Vec<String> {
+            sqlx::query_scalar::<_, String>("SELECT target FROM links ORDER BY target")
+                .fetch_all(pool)
+                .await
+                .unwrap()
+        }
+
+        tauri::async_runtime::block_on(async {
+            let pool = crate::db::test_pool().await;
+            let links = [
+                link(LinkKind::Branch, "/src/web", "proj-1-web"),
+                link(LinkKind::Branch, "/src/api", "proj-1-api"),
+                link(LinkKind::PullRequest, "", "gh:acme/web#1"),
+            ];
+            store_detected(&pool, None, &links).await.unwrap();
+            assert_eq!(
+                targets(&pool).await,
+                ["gh:acme/web#1", "proj-1-api", "proj-1-web"]
+            );
+
+            // Rescanning one repo replaces its links and the pull request links only
+            let links = [
+                link(LinkKind::Branch, "/src/web", "proj-1-renamed"),
+                link(LinkKind::PullRequest, "", "gh:acme/web#2"),
+            ];
+            store_detected(&pool, Some(&["/src/web".to_string()]), &links)
+                .await
+                .unwrap();
+            assert_eq!(
+                targets(&pool).await,
+                ["gh:acme/web#2", "proj-1-api", "proj-1-renamed"]
+            );
+
+            // A full scan drops the links of repos that are gone
+            store_detected(&pool, None, &[]).await.unwrap();
+            assert!(targets(&pool).await.is_empty());
+        });
+    }
+}
@@ -0,0 +1,553 @@
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::azure::AzureProvider;
+use crate::connections::Connection;
+use crate::error::{AuraError, AuraResult};
+use crate::fogbugz::FogBugzProvider;
+use crate::github::GitHubProvider;
+use crate::http::HttpClient;
+use crate::jira::JiraProvider;
+
+// -- Unified data model (FR-004) --
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    Github,
+    Jira,
+    Fogbugz,
+    AzureDevops,
+}
+
+impl ProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::Github => "github",
+            ProviderKind::Jira => "jira",
+            ProviderKind::Fogbugz => "fogbugz",
+            ProviderKind::AzureDevops => "azure_devops",
+        }
+    }
+}
+
+impl std::str::FromStr for ProviderKind {
+    type Err = AuraError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "github" => Ok(ProviderKind::Github),
+            "jira" => Ok(ProviderKind::Jira),
+            "fogbugz" => Ok(ProviderKind::Fogbugz),
+            "azure_devops" => Ok(ProviderKind::AzureDevops),
+            _ => Err(AuraError::invalid_input(format!("Unknown provider: {}", s))),
+        }
+    }
+}
+
+/// Provider-independent status bucket; the original status string is kept
+/// alongside it on the work item.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusCategory {
+    Open,
+    InProgress,
+    Resolved,
+    Closed,
+}
+
+impl StatusCategory {
+    /// Best-effort mapping for trackers with free-form workflow state names
+    pub fn from_state_name(state: &str) -> Self {
+        let s = state.trim().to_lowercase();
+        if s.starts_with("closed")
+            || s.starts_with("done")
+            || s.starts_with("removed")
+            || s.contains("cancel")
+        {
+            StatusCategory::Closed
+        } else if s.starts_with("resolved") || s.starts_with("fixed") || s.starts_with("complete") {
+            StatusCategory::Resolved
+        } else if s.starts_with("active")
+            || s.contains("progress")
+            || s.starts_with("doing")
+            || s.starts_with("committed")
+            || s.contains("review")
+        {
+            StatusCategory::InProgress
+        } else {
+            StatusCategory::Open
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkItemKind {
+    Issue,
+    PullRequest,
+}
+
+impl WorkItemKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkItemKind::Issue => "issue",
+            WorkItemKind::PullRequest => "pull_request",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkItem {
+    /// Stable identifier across connections: `{connection_id}:{provider_id}`
+    pub id: String,
+    pub connection_id: String,
+    pub provider: ProviderKind,
+    /// Native identifier in the source system (Jira key, `owner/repo#12`, ...)
+    pub provider_id: String,
+    pub kind: WorkItemKind,
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    pub status_category: StatusCategory,
+    pub priority: Option<String>,
+    pub item_type: Option<String>,
+    pub assignee: Option<String>,
+    pub author: Option<String>,
+    pub labels: Vec<String>,
+    pub project: String,
+    pub created_at: Option<String>,
+    /// UTC, RFC 3339 — safe to compare lexicographically across providers
+    pub updated_at: String,
+    /// Provider-specific fields that have no place in the shared model
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl WorkItem {
+    pub fn new(
+        connection_id: &str,
+        provider: ProviderKind,
+        kind: WorkItemKind,
+        provider_id: String,
+        title: String,
+        url: String,
+    ) -> Self {
+        WorkItem {
+            id: format!("{}:{}", connection_id, provider_id),
+            connection_id: connection_id.to_string(),
+            provider,
+            provider_id,
+            kind,
+            url,
+            title,
+            description: None,
+            status: String::new(),
+            status_category: StatusCategory::Open,
+            priority: None,
+            item_type: None,
+            assignee: None,
+            author: None,
+            labels: vec![],
+            project: String::new(),
+            created_at: None,
+            updated_at: String::new(),
+            extra: serde_json::Map::new(),
+        }
+    }
+
+    /// Record a provider-specific field in the extension map
+    pub fn with_extra(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.extra.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProviderStatus {
+    pub connection_id: String,
+    pub provider: ProviderKind,
+    pub authenticated: bool,
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProviderFailure {
+    pub connection_id: String,
+    pub provider: ProviderKind,
+    /// The part of the connection that failed, such as an Azure DevOps
+    /// project; `None` when the whole connection did
+    pub source: Option<String>,
+    pub error: AuraError,
+}
+
+/// Long-form text of one work item, fetched on demand rather than synced
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct WorkItemDetails {
+    /// Plain text; HTML and rich text are flattened
+    pub description: Option<String>,
+    pub acceptance_criteria: Option<String>,
+}
+
+/// Time to book against a work item
+#[derive(Debug, Clone)]
+pub struct WorkLogEntry {
+    pub started: DateTime<Utc>,
+    /// Whole minutes, in seconds
+    pub seconds: i64,
+    pub comment: String,
+}
+
+/// Items from one connection. A connection that reads from several places,
+/// like the projects of an Azure DevOps connection, returns what it could
+/// reach and reports the rest in `failures`, keyed by where it failed.
+#[derive(Debug, Default)]
+pub struct Fetched {
+    pub items: Vec<WorkItem>,
+    pub failures: Vec<(String, AuraError)>,
+}
+
+impl From<Vec<WorkItem>> for Fetched {
+    fn from(items: Vec<WorkItem>) -> Self {
+        Fetched {
+            items,
+            failures: vec![],
+        }
+    }
+}
+
+impl Fetched {
+    /// The failures as reported for a connection
+    pub fn failures_of(&self, connection: &Connection) -> Vec<ProviderFailure> {
+        self.failures
+            .iter()
+            .map(|(source, error)| ProviderFailure {
+                connection_id: connection.id.clone(),
+                provider: connection.provider,
+                source: Some(source.clone()),
+                error: error.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Merged result across connections. A failing connection does not hide the
+/// items of the healthy ones; it is reported in `errors` instead.
+#[derive(Debug, Serialize, Clone)]
+pub struct WorkItemList {
+    pub items: Vec<WorkItem>,
+    pub errors: Vec<ProviderFailure>,
+}
+
+// -- Provider trait --
+
+/// One configured account on a provider
+#[async_trait]
+pub trait Provider: Send + Sync {
+    fn connection(&self) -> &Connection;
+
+    fn kind(&self) -> ProviderKind {
+        self.connection().provider
+    }
+
+    /// Status of a connection whose credentials were rejected
+    fn unauthenticated(&self) -> ProviderStatus {
+        ProviderStatus {
+            connection_id: self.connection().id.clone(),
+            provider: self.kind(),
+            authenticated: false,
+            account: None,
+        }
+    }
+
+    async fn check_auth(&self) -> AuraResult<ProviderStatus>;
+
+    /// Open items assigned to the authenticated user
+    async fn fetch_assigned(&self) -> AuraResult<Fetched>;
+
+    /// Pull requests involving the user; trackers without PRs return nothing
+    async fn fetch_pull_requests(&self) -> AuraResult<Fetched> {
+        Ok(Fetched::default())
+    }
+
+    /// Items matching a query in the provider's own syntax: JQL, WIQL,
+    /// GitHub search or FogBugz search
+    async fn search(&self, query: &str) -> AuraResult<Fetched>;
+
+    /// Description and acceptance criteria of an item from this connection.
+    /// Providers whose listing already carries the description return it.
+    async fn fetch_details(&self, item: &WorkItem) -> AuraResult<WorkItemDetails> {
+        Ok(WorkItemDetails {
+            description: item.description.clone(),
+            acceptance_criteria: None,
+        })
+    }
+
+    /// Book time against an item with the tracker's own time tracking.
+    /// Returns the tracker's id for the booking, if it has one.
+    async fn log_work(
+        &self,
+        _item: &WorkItem,
+        _entry: &WorkLogEntry,
+    ) -> AuraResult<Option<String>> {
+        Err(AuraError::invalid_input(format!(
+            "{} doesn't track time.",
+            self.connection().label
+        )))
+    }
+}
+
+// -- Helpers --
+
+/// Convert a provider timestamp to UTC RFC 3339. Jira's `+0000` offsets are
+/// not RFC 3339, so they get a second parse attempt. Unparseable values are
+/// passed through unchanged.
+pub fn normalize_timestamp(raw: &str) -> String {
+    DateTime::parse_from_rfc3339(raw)
+        .or_else(|_| DateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f%z"))
+        .map(|dt| {
+            dt.with_timezone(&Utc)
+                .to_rfc3339_opts(SecondsFormat::Secs, true)
+        })
+        .unwrap_or_else(|_| raw.to_string())
+}
+
+/// Flatten the HTML Jira and Azure DevOps render rich text fields to:
+/// block elements become line breaks, list items get a bullet, tags are
+/// dropped and common entities decoded.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("");
+        match name {
+            "li" if !tag.starts_with('/') => text.push_str("\n- "),
+            "br" | "p" | "div" | "ul" | "ol" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                text.push('\n')
+            }
+            _ => {}
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    // Collapse the blank lines nested blocks leave behind
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() && lines.last().is_none_or(|l| l.trim().is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/// Sort newest first, falling back to id for a stable order
+pub fn sort_work_items(items: &mut [WorkItem]) {
+    items.sort_by(|a, b| {
+        b.updated_at
+            .cmp(&a.updated_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+}
+
+pub fn provider_for(
+    http: &HttpClient,
+    pool: &sqlx::SqlitePool,
+    connection: Connection,
+) -> Box<dyn Provider> {
+    match connection.provider {
+        ProviderKind::Github => Box::new(GitHubProvider::new(http.clone(), connection)),
+        ProviderKind::Jira => Box::new(JiraProvider::new(http.clone(), connection)),
+        ProviderKind::Fogbugz => Box::new(FogBugzProvider::new(http.clone(), connection)),
+        ProviderKind::AzureDevops => {
+            Box::new(AzureProvider::new(http.clone(), pool.clone(), connection))
+        }
+    }
+}
+
+/// A provider for every saved connection
+pub async fn load_providers(
+    http: &HttpClient,
+    pool: &sqlx::SqlitePool,
+) -> AuraResult<Vec<Box<dyn Provider>>> {
+    let connections = crate::connections::load_connections(pool).await?;
+    Ok(connections
+        .into_iter()
+        .map(|c| provider_for(http, pool, c))
+        .collect())
+}
+
+async fn collect_from_providers(
+    providers: &[Box<dyn Provider>],
+    pull_requests: bool,
+) -> WorkItemList {
+    let results = futures::future::join_all(providers.iter().map(|p| async move {
+        let result = if pull_requests {
+            p.fetch_pull_requests().await
+        } else {
+            p.fetch_assigned().await
+        };
+        (p.connection(), result)
+    }))
+    .await;
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    for (connection, result) in results {
+        match result {
+            Ok(found) => {
+                errors.extend(found.failures_of(connection));
+                items.extend(found.items);
+            }
+            Err(error) => errors.push(ProviderFailure {
+                connection_id: connection.id.clone(),
+                provider: connection.provider,
+                source: None,
+                error,
+            }),
+        }
+    }
+
+    sort_work_items(&mut items);
+    WorkItemList { items, errors }
+}
+
+// -- Tauri commands --
+
+#[tauri::command]
+pub async fn check_all_providers(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+) -> AuraResult<Vec<ProviderStatus>> {
+    let pool = crate::db::pool(&app).await?;
+    let providers = load_providers(&http, &pool).await?;
+    let results = futures::future::join_all(
+        providers
+            .iter()
+            .map(|p| async move { p.check_auth().await.unwrap_or_else(|_| p.unauthenticated()) }),
+    )
+    .await;
+    Ok(results)
+}
+
+/// Fetch assigned items from every connection as one sorted list
+#[tauri::command]
+pub async fn fetch_all_work_items(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+) -> AuraResult<WorkItemList> {
+    let pool = crate::db::pool(&app).await?;
+    let providers = load_providers(&http, &pool).await?;
+    Ok(collect_from_providers(&providers, false).await)
+}
+
+/// Fetch pull requests from every connection whose provider has them
+#[tauri::command]
+pub async fn fetch_all_pull_requests(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+) -> AuraResult<WorkItemList> {
+    let pool = crate::db::pool(&app).await?;
+    let providers = load_providers(&http, &pool).await?;
+    Ok(collect_from_providers(&providers, true).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_timestamp() {
+        assert_eq!(
+            normalize_timestamp("2024-03-01T10:00:00Z"),
+            "2024-03-01T10:00:00Z"
+        );
+        // Jira style offset
+        assert_eq!(
+            normalize_timestamp("2024-03-01T12:00:00.000+0200"),
+            "2024-03-01T10:00:00Z"
+        );
+        // Azure style fractional seconds
+        assert_eq!(
+            normalize_timestamp("2024-03-01T10:00:00.123Z"),
+            "2024-03-01T10:00:00Z"
+        );
+        assert_eq!(normalize_timestamp("garbage"), "garbage");
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        assert_eq!(
+            html_to_text(
+                "<p>Fix the <b>login</b> &amp; retry</p><ul><li>one</li><li>two</li></ul>"
+            ),
+            "Fix the login & retry\n\n- one\n- two"
+        );
+        assert_eq!(html_to_text("plain"), "plain");
+        assert_eq!(html_to_text("a<br/>b"), "a\nb");
+    }
+
+    #[test]
+    fn test_status_from_state_name() {
+        assert_eq!(StatusCategory::from_state_name("New"), StatusCategory::Open);
+        assert_eq!(
+            StatusCategory::from_state_name("In Progress"),
+            StatusCategory::InProgress
+        );
+        assert_eq!(
+            StatusCategory::from_state_name("Resolved (Fixed)"),
+            StatusCategory::Resolved
+        );
+        assert_eq!(
+            StatusCategory::from_state_name("Closed (Duplicate)"),
+            StatusCategory::Closed
+        );
+    }
+
+    #[test]
+    fn test_sort_work_items_newest_first() {
+        let mut a = WorkItem::new(
+            "jira",
+            ProviderKind::Jira,
+            WorkItemKind::Issue,
+            "A-1".into(),
+            "a".into(),
+            String::new(),
+        );
+        a.updated_at = normalize_timestamp("2024-03-01T12:00:00.000+0200");
+        let mut b = WorkItem::new(
+            "github",
+            ProviderKind::Github,
+            WorkItemKind::Issue,
+            "o/r#1".into(),
+            "b".into(),
+            String::new(),
+        );
+        b.updated_at = normalize_timestamp("2024-03-01T11:00:00Z");
+
+        let mut items = vec![a, b];
+        sort_work_items(&mut items);
+        assert_eq!(items[0].id, "github:o/r#1");
+        assert_eq!(items[1].id, "jira:A-1");
+    }
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, State};
+
+use crate::connections::load_connection;
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::provider::{provider_for, sort_work_items, ProviderKind, WorkItemList};
+
+// -- Types --
+
+/// A named query run against one connection, in that provider's syntax
+#[derive(Debug, Serialize, Clone)]
+pub struct SavedQuery {
+    pub id: String,
+    pub connection_id: String,
+    pub provider: ProviderKind,
+    pub name: String,
+    pub query: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewSavedQuery {
+    pub connection_id: String,
+    pub name: String,
+    pub query: String,
+}
+
+/// Fields left out are kept as they are
+#[derive(Debug, Deserialize, Default)]
+pub struct SavedQueryUpdate {
+    pub name: Option<String>,
+    pub query: Option<String>,
+}
+
+fn validate(name: &str, query: &str) -> AuraResult<()> {
+    if name.trim().is_empty() {
+        return Err(AuraError::invalid_input("A query needs a name."));
+    }
+    if query.trim().is_empty() {
+        return Err(AuraError::invalid_input("The query is empty."));
+    }
+    Ok(())
+}
+
+// -- Storage --
+
+type SavedQueryRow = (String, String, String, String, String, String, String);
+
+const SELECT_QUERIES: &str = "SELECT q.id, q.connection_id, c.provider, q.name, q.query, q.created_at, q.updated_at FROM saved_queries q JOIN connections c ON c.id = q.connection_id";
+
+fn from_row(row: SavedQueryRow) -> AuraResult<SavedQuery> {
+    let (id, connection_id, provider, name, query, created_at, updated_at) = row;
+    Ok(SavedQuery {
+        id,
+        connection_id,
+        provider: provider.parse()?,
+        name,
+        query,
+        created_at,
+        updated_at,
+    })
+}
+
+async fn load_query(pool: &SqlitePool, id: &str) -> AuraResult<SavedQuery> {
+    let row = sqlx::query_as::<_, SavedQueryRow>(&format!("{} WHERE q.id = ?", SELECT_QUERIES))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AuraError::invalid_input(format!("Unknown query: {}", id)))?;
+    from_row(row)
+}
+
+async fn name_taken(
+    pool: &SqlitePool,
+    connection_id: &str,
+    name: &str,
+    except_id: Option<&str>,
+) -> AuraResult<bool> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM saved_queries WHERE connection_id = ? AND name = ? AND id IS NOT ?",
+    )
+    .bind(connection_id)
+    .bind(name)
+    .bind(except_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(count > 0)
+}
+
+// -- Tauri commands --
+
+/// Saved queries, optionally only those of one connection
+#[tauri::command]
+pub async fn list_saved_queries(
+    app: AppHandle,
+    connection_id: Option<String>,
+) -> AuraResult<Vec<SavedQuery>> {
+    let pool = crate::db::pool(&app).await?;
+    let rows = sqlx::query_as::<_, SavedQueryRow>(&format!(
+        "{} WHERE ?1 IS NULL OR q.connection_id = ?1 ORDER BY c.provider, q.name",
+        SELECT_QUERIES
+    ))
+    .bind(connection_id)
+    .fetch_all(&pool)
+    .await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+#[tauri::command]
+pub async fn create_saved_query(app: AppHandle, query: NewSavedQuery) -> AuraResult<SavedQuery> {
+    validate(&query.name, &query.query)?;
+    let pool = crate::db::pool(&app).await?;
+    let connection = load_connection(&pool, &query.connection_id).await?;
+    let name = query.name.trim();
+    if name_taken(&pool, &connection.id, name, None).await? {
+        return Err(AuraError::invalid_input(format!(
+            "{} already has a query named {}.",
+            connection.label, name
+        )));
+    }
+
+    let id = crate::db::new_id();
+    sqlx::query("INSERT INTO saved_queries (id, connection_id, name, query) VALUES (?, ?, ?, ?)")
+        .bind(&id)
+        .bind(&connection.id)
+        .bind(name)
+        .bind(query.query.trim())
+        .execute(&pool)
+        .await?;
+
+    load_query(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn update_saved_query(
+    app: AppHandle,
+    id: String,
+    update: SavedQueryUpdate,
+) -> AuraResult<SavedQuery> {
+    let pool = crate::db::pool(&app).await?;
+    let existing = load_query(&pool, &id).await?;
+    let name = update.name.unwrap_or(existing.name);
+    let query = update.query.unwrap_or(existing.query);
+    validate(&name, &query)?;
+    if name_taken(&pool, &existing.connection_id, name.trim(), Some(&id)).await? {
+        return Err(AuraError::invalid_input(format!(
+            "There is already a query named {}.",
+            name.trim()
+        )));
+    }
+
+    sqlx::query(
+        "UPDATE saved_queries SET name = ?, query = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(name.trim())
+    .bind(query.trim())
+    .bind(&id)
+    .execute(&pool)
+    .await?;
+
+    load_query(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn delete_saved_query(app: AppHandle, id: String) -> AuraResult<()> {
+    let pool = crate::db::pool(&app).await?;
+    sqlx::query("DELETE FROM saved_queries WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await?;
+    Ok(())
+}
+
+/// Run a saved query against its connection. Items come back in the same
+/// unified shape and order as the default lists; parts of the connection
+/// that failed, such as one Azure DevOps project, are in `errors`.
+#[tauri::command]
+pub async fn run_saved_query(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    id: String,
+) -> AuraResult<WorkItemList> {
+    let pool = crate::db::pool(&app).await?;
+    let saved = load_query(&pool, &id).await?;
+    let connection = load_connection(&pool, &saved.connection_id).await?;
+    let provider = provider_for(&http, &pool, connection);
+    let fetched = provider.search(&saved.query).await?;
+    let errors = fetched.failures_of(provider.connection());
+    let mut items = fetched.items;
+    sort_work_items(&mut items);
+    Ok(WorkItemList { items, errors })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_requires_name_and_query() {
+        assert!(validate("Waiting on QA", "status = \"In QA\"").is_ok());
+        assert!(validate(" ", "status = \"In QA\"").is_err());
+        assert!(validate("Waiting on QA", "").is_err());
+    }
+}
@@ -1,39 +1,116 @@
-use serde::Serialize;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Emitter};
 
-#[derive(Debug, Serialize, Clone)]
+use crate::error::{AuraError, AuraResult};
+use crate::scan_roots::{load_scan_roots, ScanRoot, DEFAULT_MAX_DEPTH};
+
+/// Event emitted for each repo as a scan gets to it; payload is a
+/// `RepoScanProgress`
+pub const REPO_SCAN_EVENT: &str = "repo-scan-progress";
+/// Repos read at the same time during a scan
+const SCAN_WORKERS: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LocalRepo {
     pub name: String,
     pub path: String,
+    /// Short commit id when HEAD is detached
     pub current_branch: String,
     pub is_dirty: bool,
+    #[serde(flatten)]
+    pub status: RepoStatus,
+}
+
+/// Where a repo stands beyond its branch: what it needs before switching
+/// context
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RepoStatus {
+    /// Tracking branch, e.g. `origin/main`
+    pub upstream: Option<String>,
+    /// Commits not on the upstream yet
+    pub ahead: u32,
+    /// Upstream commits not pulled yet
+    pub behind: u32,
+    pub stashes: u32,
+    /// Files with changes in the index
+    pub staged: u32,
+    /// Tracked files with changes not staged
+    pub modified: u32,
+    pub untracked: u32,
+    /// Files with unresolved merge conflicts
+    pub conflicted: u32,
+    pub detached: bool,
+    pub operation: Option<RepoOperation>,
 }
 
-/// Recursively scan for Git repos up to max_depth
-fn find_repos(root: &Path, max_depth: u32, current_depth: u32) -> Vec<PathBuf> {
-    if current_depth > max_depth {
+/// A multi-step git command stopped halfway, waiting for the user
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoOperation {
+    Rebase,
+    Merge,
+    CherryPick,
+    Revert,
+    Bisect,
+}
+
+/// Recursively scan a root for Git repos up to its max depth. `relative` is
+/// `dir` relative to the root, for matching the root's patterns. When
+/// symlinks are followed, `visited` holds the canonical paths walked so far
+/// so a link back up the tree can't loop.
+fn find_repos(
+    root: &ScanRoot,
+    dir: &Path,
+    relative: &str,
+    current_depth: u32,
+    visited: &mut HashSet<PathBuf>,
+) -> Vec<PathBuf> {
+    if current_depth > root.max_depth {
         return vec![];
     }
 
     let mut repos = Vec::new();
 
-    let entries = match std::fs::read_dir(root) {
+    let entries = match std::fs::read_dir(dir) {
         Ok(e) => e,
         Err(_) => return repos,
     };
 
     for entry in entries.flatten() {
         let path = entry.path();
-        if !path.is_dir() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let is_dir = if file_type.is_symlink() {
+            root.follow_symlinks && path.is_dir()
+        } else {
+            file_type.is_dir()
+        };
+        if !is_dir {
             continue;
         }
 
         let name = entry.file_name();
         let name_str = name.to_string_lossy();
-
-        // Skip hidden dirs (except .git) and common non-repo dirs
-        if name_str.starts_with('.') || name_str == "node_modules" || name_str == "target" {
+        let child = if relative.is_empty() {
+            name_str.to_string()
+        } else {
+            format!("{}/{}", relative, name_str)
+        };
+        if root.skips(&child) {
+            continue;
+        }
+        if root.follow_symlinks
+            && !path
+                .canonicalize()
+                .is_ok_and(|canonical| visited.insert(canonical))
+        {
             continue;
         }
 
@@ -45,14 +122,30 @@ fn find_repos(root: &Path, max_depth: u32, current_depth: u32) -> Vec<PathBuf> {
         }
 
         // Recurse into subdirectories
-        repos.extend(find_repos(&path, max_depth, current_depth + 1));
+        repos.extend(find_repos(root, &path, &child, current_depth + 1, visited));
     }
 
     repos
 }
 
+/// Run git in a repo and return its trimmed stdout. Prompts are disabled so
+/// a remote that wants credentials fails instead of hanging.
+pub(crate) fn git(repo_path: &Path, args: &[&str]) -> AuraResult<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .map_err(|e| AuraError::from_spawn("git", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AuraError::cli_failed("git", stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 /// Get current branch name for a repo
-fn get_current_branch(repo_path: &Path) -> String {
+pub(crate) fn get_current_branch(repo_path: &Path) -> String {
     Command::new("git")
         .args(["rev-parse", "--abbrev-ref", "HEAD"])
         .current_dir(repo_path)
@@ -68,8 +161,50 @@ fn get_current_branch(repo_path: &Path) -> String {
         .unwrap_or_else(|| "unknown".to_string())
 }
 
+/// Names of the repo's local branches
+pub(crate) fn list_branches(repo_path: &Path) -> Vec<String> {
+    Command::new("git")
+        .args(["for-each-ref", "--format=%(refname:short)", "refs/heads"])
+        .current_dir(repo_path)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// "owner/repo" of the `origin` remote, for SSH and HTTPS URLs alike
+pub(crate) fn origin_repo(repo_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .current_dir(repo_path)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    repo_from_remote(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+/// "git@github.com:owner/repo.git" or "https://host/owner/repo" -> "owner/repo"
+fn repo_from_remote(url: &str) -> Option<String> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let mut segments = path.rsplit('/');
+    let repo = segments.next().filter(|s| !s.is_empty())?;
+    let owner = segments.next().filter(|s| !s.is_empty())?;
+    Some(format!("{}/{}", owner, repo))
+}
+
 /// Check if repo has uncommitted changes
-fn is_repo_dirty(repo_path: &Path) -> bool {
+pub(crate) fn is_repo_dirty(repo_path: &Path) -> bool {
     Command::new("git")
         .args(["status", "--porcelain"])
         .current_dir(repo_path)
@@ -78,41 +213,368 @@ fn is_repo_dirty(repo_path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-/// Discover repos in given root directories
-pub fn discover_repos(roots: &[String], max_depth: u32) -> Vec<LocalRepo> {
-    let mut repos = Vec::new();
-
-    for root in roots {
-        let root_path = Path::new(root);
-        if !root_path.exists() || !root_path.is_dir() {
+/// Branch name (or short commit when detached) and counts from
+/// `git status --porcelain=v2 --branch`
+fn parse_status(porcelain: &str) -> (String, RepoStatus) {
+    let mut status = RepoStatus::default();
+    let mut branch = String::new();
+    let mut oid = String::new();
+    for line in porcelain.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.oid" => oid = value.to_string(),
+                "branch.head" => branch = value.to_string(),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for count in value.split_whitespace() {
+                        if let Some(n) = count.strip_prefix('+') {
+                            status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = count.strip_prefix('-') {
+                            status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
             continue;
         }
+        let mut fields = line.split(' ');
+        match fields.next() {
+            Some("1") | Some("2") => {
+                let xy = fields.next().unwrap_or("..").as_bytes();
+                if xy.first().is_some_and(|&x| x != b'.') {
+                    status.staged += 1;
+                }
+                if xy.get(1).is_some_and(|&y| y != b'.') {
+                    status.modified += 1;
+                }
+            }
+            Some("u") => status.conflicted += 1,
+            Some("?") => status.untracked += 1,
+            _ => {}
+        }
+    }
+
+    if branch == "(detached)" {
+        status.detached = true;
+        branch = oid.chars().take(7).collect();
+    }
+    (branch, status)
+}
+
+/// The repo's git directory; in a worktree `.git` is a file pointing to it
+fn git_dir(repo_path: &Path) -> Option<PathBuf> {
+    let dot_git = repo_path.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let pointer = std::fs::read_to_string(&dot_git).ok()?;
+    let dir = pointer.trim().strip_prefix("gitdir:")?.trim();
+    Some(repo_path.join(dir))
+}
+
+/// The operation left in progress, read from the marker files git keeps
+/// in its directory
+fn operation_in(git_dir: &Path) -> Option<RepoOperation> {
+    [
+        ("rebase-merge", RepoOperation::Rebase),
+        ("rebase-apply", RepoOperation::Rebase),
+        ("MERGE_HEAD", RepoOperation::Merge),
+        ("CHERRY_PICK_HEAD", RepoOperation::CherryPick),
+        ("REVERT_HEAD", RepoOperation::Revert),
+        ("BISECT_LOG", RepoOperation::Bisect),
+    ]
+    .into_iter()
+    .find(|(marker, _)| git_dir.join(marker).exists())
+    .map(|(_, operation)| operation)
+}
+
+fn stash_count(repo_path: &Path) -> u32 {
+    git(
+        repo_path,
+        &["rev-list", "--walk-reflogs", "--count", "refs/stash"],
+    )
+    .ok()
+    .and_then(|n| n.parse().ok())
+    .unwrap_or(0)
+}
+
+/// Branch and status of one repo. A repo git can't read is listed with an
+/// `unknown` branch rather than dropped.
+pub(crate) fn read_repo(repo_path: &Path) -> LocalRepo {
+    let name = repo_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let (current_branch, mut status) =
+        // Without optional locks git doesn't rewrite the index, which would
+        // change its mtime and defeat the next incremental scan
+        match git(
+            repo_path,
+            &["--no-optional-locks", "status", "--porcelain=v2", "--branch"],
+        ) {
+            Ok(porcelain) => parse_status(&porcelain),
+            Err(_) => ("unknown".to_string(), RepoStatus::default()),
+        };
+    status.stashes = stash_count(repo_path);
+    status.operation = git_dir(repo_path).and_then(|dir| operation_in(&dir));
+
+    LocalRepo {
+        name,
+        path: repo_path.to_string_lossy().to_string(),
+        current_branch,
+        is_dirty: status.staged + status.modified + status.untracked + status.conflicted > 0,
+        status,
+    }
+}
+
+/// Paths of the Git repos under the given roots
+pub(crate) fn repo_paths(roots: &[ScanRoot]) -> Vec<PathBuf> {
+    walk_roots(roots)
+        .into_iter()
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// Git repos under each root, paired with the root they were found under.
+/// A repo under overlapping roots belongs to the first.
+fn walk_roots(roots: &[ScanRoot]) -> Vec<(String, PathBuf)> {
+    let mut seen = HashSet::new();
+    roots
+        .iter()
+        .filter(|root| Path::new(&root.path).is_dir())
+        .flat_map(|root| {
+            let path = Path::new(&root.path);
+            let mut visited: HashSet<PathBuf> = path.canonicalize().into_iter().collect();
+            find_repos(root, path, "", 0, &mut visited)
+                .into_iter()
+                .map(move |repo| (root.path.clone(), repo))
+        })
+        .filter(|(_, repo)| seen.insert(repo.clone()))
+        .collect()
+}
+
+// -- Repo index --
 
-        let found = find_repos(root_path, max_depth, 0);
-        for repo_path in found {
-            let name = repo_path
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown".to_string());
-
-            repos.push(LocalRepo {
-                name,
-                path: repo_path.to_string_lossy().to_string(),
-                current_branch: get_current_branch(&repo_path),
-                is_dirty: is_repo_dirty(&repo_path),
-            });
+#[derive(Debug, Serialize, Clone)]
+pub struct RepoScanProgress {
+    pub repo: LocalRepo,
+    /// Repos handled so far, out of all found
+    pub done: usize,
+    pub total: usize,
+    /// Whether git was asked again rather than the index being current
+    pub refreshed: bool,
+}
+
+struct ScannedRepo {
+    root: String,
+    repo: LocalRepo,
+    fingerprint: String,
+    refreshed: bool,
+}
+
+/// Where the refs shared by all worktrees live. A worktree's git directory
+/// names it in `commondir`; for the main checkout it is the git directory.
+fn common_dir(git_dir: &Path) -> PathBuf {
+    std::fs::read_to_string(git_dir.join("commondir"))
+        .map(|relative| git_dir.join(relative.trim()))
+        .unwrap_or_else(|_| git_dir.to_path_buf())
+}
+
+/// Modification times of the files git touches when the branch, commits,
+/// staged changes, upstream or stashes change: commits and resets append
+/// to `logs/HEAD`, `git gc` rewrites `packed-refs`, and fetches and pushes
+/// append to the upstream's reflog. Unstaged edits touch nothing under
+/// `.git`, which is why a forced rescan exists.
+fn fingerprint(repo_path: &Path, upstream: Option<&str>) -> String {
+    let dir = git_dir(repo_path);
+    let common = dir.as_deref().map(common_dir);
+    let mtime = |dir: Option<&PathBuf>, name: &str| {
+        dir.and_then(|dir| std::fs::metadata(dir.join(name)).ok())
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_millis())
+    };
+    let upstream_log = upstream.map(|u| format!("logs/refs/remotes/{}", u));
+    format!(
+        "{}:{}:{}:{}:{}:{}:{}",
+        mtime(dir.as_ref(), "HEAD"),
+        mtime(dir.as_ref(), "logs/HEAD"),
+        mtime(dir.as_ref(), "index"),
+        mtime(dir.as_ref(), "FETCH_HEAD"),
+        mtime(common.as_ref(), "packed-refs"),
+        mtime(common.as_ref(), "logs/refs/stash"),
+        upstream_log.map_or(0, |log| mtime(common.as_ref(), &log)),
+    )
+}
+
+/// Read a repo, reusing its indexed status if its fingerprint still
+/// matches. In-progress operations are checked either way since that only
+/// looks for files.
+fn scan_repo(root: String, path: PathBuf, indexed: Option<(String, String)>) -> ScannedRepo {
+    let cached = indexed
+        .and_then(|(fingerprint, data)| {
+            Some((fingerprint, serde_json::from_str::<LocalRepo>(&data).ok()?))
+        })
+        .filter(|(stored, repo)| *stored == fingerprint(&path, repo.status.upstream.as_deref()));
+    match cached {
+        Some((fingerprint, mut repo)) => {
+            repo.status.operation = git_dir(&path).and_then(|dir| operation_in(&dir));
+            ScannedRepo {
+                root,
+                repo,
+                fingerprint,
+                refreshed: false,
+            }
+        }
+        None => {
+            let repo = read_repo(&path);
+            ScannedRepo {
+                root,
+                fingerprint: fingerprint(&path, repo.status.upstream.as_deref()),
+                repo,
+                refreshed: true,
+            }
         }
     }
+}
 
-    repos.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    repos
+/// Update the index with the repos of this scan. Indexed repos that were
+/// not found are dropped if they belong to one of the `roots` scanned, or
+/// to any root when `roots` is `None` because every scan root was scanned.
+async fn store_index(
+    pool: &SqlitePool,
+    roots: Option<&[String]>,
+    scanned: &[ScannedRepo],
+) -> AuraResult<()> {
+    let found: HashSet<&str> = scanned.iter().map(|s| s.repo.path.as_str()).collect();
+    let indexed = sqlx::query_as::<_, (String, String)>("SELECT path, root FROM repos")
+        .fetch_all(pool)
+        .await?;
+    let gone = indexed.iter().filter(|(path, root)| {
+        !found.contains(path.as_str()) && roots.is_none_or(|roots| roots.contains(root))
+    });
+
+    let mut tx = pool.begin().await?;
+    for (path, _) in gone {
+        sqlx::query("DELETE FROM repos WHERE path = ?")
+            .bind(path)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for s in scanned {
+        sqlx::query(
+            "INSERT INTO repos (path, root, name, fingerprint, data) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (path) DO UPDATE SET root = excluded.root, name = excluded.name,
+                 fingerprint = excluded.fingerprint, data = excluded.data, scanned_at = datetime('now')",
+        )
+        .bind(&s.repo.path)
+        .bind(&s.root)
+        .bind(&s.repo.name)
+        .bind(&s.fingerprint)
+        .bind(serde_json::to_string(&s.repo)?)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// The indexed repos, sorted. Rows written by an older model version are
+/// skipped rather than failing the whole list; the next scan rewrites them.
+async fn read_index(pool: &SqlitePool) -> AuraResult<Vec<LocalRepo>> {
+    let rows = sqlx::query_scalar::<_, String>("SELECT data FROM repos")
+        .fetch_all(pool)
+        .await?;
+    let mut repos: Vec<LocalRepo> = rows
+        .iter()
+        .filter_map(|data| serde_json::from_str(data).ok())
+        .collect();
+    sort_repos(&mut repos);
+    Ok(repos)
+}
+
+fn sort_repos(repos: &mut [LocalRepo]) {
+    repos.sort_by_key(|r| r.name.to_lowercase());
 }
 
 // -- Tauri commands --
 
+/// Find the repos under the roots and read their status, a few at a time
+/// off the async runtime. Repos unchanged since the last scan come from the
+/// index unless `force` is set. Each repo is also emitted as
+/// `REPO_SCAN_EVENT` as soon as it is read.
+///
+/// Scans the configured scan roots with their own options, or just `roots`
+/// if given, with the default options and `max_depth`.
+#[tauri::command]
+pub async fn scan_repos(
+    app: AppHandle,
+    roots: Option<Vec<String>>,
+    max_depth: Option<u32>,
+    force: Option<bool>,
+) -> AuraResult<Vec<LocalRepo>> {
+    let pool = crate::db::pool(&app).await?;
+    let scanned_roots = roots.clone();
+    let roots = match roots {
+        Some(paths) => {
+            let max_depth = max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+            paths
+                .iter()
+                .map(|path| ScanRoot::with_defaults(path, max_depth))
+                .collect()
+        }
+        None => load_scan_roots(&pool).await?,
+    };
+    let found = tauri::async_runtime::spawn_blocking(move || walk_roots(&roots))
+        .await
+        .map_err(AuraError::io)?;
+
+    let mut indexed: HashMap<String, (String, String)> = if force.unwrap_or(false) {
+        HashMap::new()
+    } else {
+        sqlx::query_as::<_, (String, String, String)>("SELECT path, fingerprint, data FROM repos")
+            .fetch_all(&pool)
+            .await?
+            .into_iter()
+            .map(|(path, fingerprint, data)| (path, (fingerprint, data)))
+            .collect()
+    };
+
+    let total = found.len();
+    let mut reads = futures::stream::iter(found.into_iter().map(|(root, path)| {
+        let previous = indexed.remove(path.to_string_lossy().as_ref());
+        tauri::async_runtime::spawn_blocking(move || scan_repo(root, path, previous))
+    }))
+    .buffer_unordered(SCAN_WORKERS);
+
+    let mut scanned = Vec::with_capacity(total);
+    while let Some(result) = reads.next().await {
+        let repo = result.map_err(AuraError::io)?;
+        let _ = app.emit(
+            REPO_SCAN_EVENT,
+            RepoScanProgress {
+                repo: repo.repo.clone(),
+                done: scanned.len() + 1,
+                total,
+                refreshed: repo.refreshed,
+            },
+        );
+        scanned.push(repo);
+    }
+
+    store_index(&pool, scanned_roots.as_deref(), &scanned).await?;
+    let mut repos: Vec<LocalRepo> = scanned.into_iter().map(|s| s.repo).collect();
+    sort_repos(&mut repos);
+    Ok(repos)
+}
+
+/// Repos as of the last scan, without asking git
 #[tauri::command]
-pub fn scan_repos(roots: Vec<String>, max_depth: Option<u32>) -> Vec<LocalRepo> {
-    discover_repos(&roots, max_depth.unwrap_or(4))
+pub async fn list_repos(app: AppHandle) -> AuraResult<Vec<LocalRepo>> {
+    let pool = crate::db::pool(&app).await?;
+    read_index(&pool).await
 }
 
 /// List subdirectories for path autocomplete
@@ -176,16 +638,75 @@ mod tests {
 
     #[test]
     fn test_discover_repos_with_empty_roots() {
-        let repos = discover_repos(&[], 4);
+        let repos = repo_paths(&[]);
         assert!(repos.is_empty());
     }
 
     #[test]
     fn test_discover_repos_nonexistent_path() {
-        let repos = discover_repos(&["/nonexistent/path/12345".to_string()], 4);
+        let repos = repo_paths(&[ScanRoot::with_defaults("/nonexistent/path/12345", 4)]);
         assert!(repos.is_empty());
     }
 
+    #[test]
+    fn test_repo_from_remote() {
+        assert_eq!(
+            repo_from_remote("git@github.com:acme/web.git").as_deref(),
+            Some("acme/web")
+        );
+        assert_eq!(
+            repo_from_remote("https://ghe.acme.com/acme/web").as_deref(),
+            Some("acme/web")
+        );
+        assert_eq!(repo_from_remote("nonsense"), None);
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let porcelain = "# branch.oid 4f2a9c1d0e\n\
+            # branch.head feature/PROJ-7-retry\n\
+            # branch.upstream origin/feature/PROJ-7-retry\n\
+            # branch.ab +2 -5\n\
+            1 M. N... 100644 100644 100644 a b src/lib.rs\n\
+            1 MM N... 100644 100644 100644 a b src/main.rs\n\
+            1 .M N... 100644 100644 100644 a b README.md\n\
+            2 R. N... 100644 100644 100644 a b R100 new.rs\told.rs\n\
+            u UU N... 100644 100644 100644 100644 a b c Cargo.lock\n\
+            ? notes.txt\n";
+        let (branch, status) = parse_status(porcelain);
+        assert_eq!(branch, "feature/PROJ-7-retry");
+        assert_eq!(
+            status,
+            RepoStatus {
+                upstream: Some("origin/feature/PROJ-7-retry".into()),
+                ahead: 2,
+                behind: 5,
+                staged: 3,
+                modified: 2,
+                untracked: 1,
+                conflicted: 1,
+                ..Default::default()
+            }
+        );
+
+        let (branch, status) = parse_status("# branch.oid 4f2a9c1d0e\n# branch.head (detached)\n");
+        assert_eq!(branch, "4f2a9c1");
+        assert!(status.detached);
+        assert_eq!(status.upstream, None);
+    }
+
+    #[test]
+    fn test_operation_in_progress() {
+        let dir = env::temp_dir().join(format!("aura-git-dir-{}", crate::db::new_id()));
+        std::fs::create_dir_all(dir.join("rebase-merge")).unwrap();
+        assert_eq!(operation_in(&dir), Some(RepoOperation::Rebase));
+        std::fs::remove_dir_all(dir.join("rebase-merge")).unwrap();
+        assert_eq!(operation_in(&dir), None);
+        std::fs::write(dir.join("CHERRY_PICK_HEAD"), "").unwrap();
+        assert_eq!(operation_in(&dir), Some(RepoOperation::CherryPick));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_discover_repos_finds_self() {
         // cargo test runs from src-tauri/, so grandparent is the dev directory
@@ -197,7 +718,195 @@ mod tests {
             .unwrap()
             .to_string_lossy()
             .to_string();
-        let repos = discover_repos(&[grandparent], 1);
+        let repos = walk_roots(&[ScanRoot::with_defaults(&grandparent, 1)]);
         assert!(!repos.is_empty());
+        assert!(repos.iter().all(|(root, _)| *root == grandparent));
+    }
+
+    #[test]
+    fn test_root_options() {
+        let dir = env::temp_dir().join(format!("aura-scan-root-{}", crate::db::new_id()));
+        for repo in [
+            "web",
+            ".config/nvim",
+            "work/.archive/old",
+            "node_modules/dep",
+        ] {
+            std::fs::create_dir_all(dir.join(repo).join(".git")).unwrap();
+        }
+        let names = |root: &ScanRoot| {
+            let mut names: Vec<String> = repo_paths(std::slice::from_ref(root))
+                .iter()
+                .map(|p| {
+                    p.strip_prefix(&dir)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect();
+            names.sort();
+            names
+        };
+
+        let mut root = ScanRoot::with_defaults(&dir.to_string_lossy(), 4);
+        assert_eq!(names(&root), vec!["web"]);
+        root.include = vec![".config".into(), "work/.archive".into()];
+        assert_eq!(
+            names(&root),
+            vec![".config/nvim", "web", "work/.archive/old"]
+        );
+        root.max_depth = 1;
+        assert_eq!(names(&root), vec![".config/nvim", "web"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_symlinks_without_looping() {
+        let dir = env::temp_dir().join(format!("aura-scan-links-{}", crate::db::new_id()));
+        let outside = env::temp_dir().join(format!("aura-scan-outside-{}", crate::db::new_id()));
+        std::fs::create_dir_all(dir.join("a/web/.git")).unwrap();
+        std::fs::create_dir_all(outside.join("api/.git")).unwrap();
+        std::os::unix::fs::symlink(&dir, dir.join("a/loop")).unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("linked")).unwrap();
+
+        let mut root = ScanRoot::with_defaults(&dir.to_string_lossy(), 16);
+        assert_eq!(
+            repo_paths(std::slice::from_ref(&root)),
+            vec![dir.join("a/web")]
+        );
+        root.follow_symlinks = true;
+        let mut found = repo_paths(std::slice::from_ref(&root));
+        found.sort();
+        assert_eq!(found, vec![dir.join("a/web"), dir.join("linked/api")]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn test_scan_reuses_unchanged_index() {
+        let root = env::current_dir().unwrap();
+        let path = root.parent().unwrap().to_path_buf();
+        let root = root.to_string_lossy().to_string();
+        let first = scan_repo(root.clone(), path.clone(), None);
+        assert!(first.refreshed);
+        let data = serde_json::to_string(&first.repo).unwrap();
+        let second = scan_repo(root, path, Some((first.fingerprint.clone(), data)));
+        assert!(!second.refreshed);
+        assert_eq!(second.repo.current_branch, first.repo.current_branch);
+    }
+
+    #[test]
+    fn test_fingerprint_follows_shared_refs_of_worktrees() {
+        let dir = env::temp_dir().join(format!("aura-fingerprint-{}", crate::db::new_id()));
+        let common = dir.join("web/.git");
+        let worktree_dir = common.join("worktrees/web-feature");
+        std::fs::create_dir_all(&worktree_dir).unwrap();
+        std::fs::write(worktree_dir.join("HEAD"), "ref: refs/heads/feature\n").unwrap();
+        std::fs::write(worktree_dir.join("commondir"), "../..\n").unwrap();
+        let worktree = dir.join("web-feature");
+        std::fs::create_dir_all(&worktree).unwrap();
+        std::fs::write(
+            worktree.join(".git"),
+            format!("gitdir: {}\n", worktree_dir.display()),
+        )
+        .unwrap();
+
+        let upstream = Some("origin/feature");
+        let before = fingerprint(&worktree, upstream);
+        std::fs::write(common.join("packed-refs"), "").unwrap();
+        let packed = fingerprint(&worktree, upstream);
+        assert_ne!(packed, before);
+
+        std::fs::create_dir_all(common.join("logs/refs/remotes/origin")).unwrap();
+        std::fs::write(common.join("logs/refs/remotes/origin/feature"), "").unwrap();
+        assert_ne!(fingerprint(&worktree, upstream), packed);
+        // Other branches' upstreams don't matter
+        assert_eq!(fingerprint(&worktree, Some("origin/main")), packed);
+
+        std::fs::create_dir_all(worktree_dir.join("logs")).unwrap();
+        std::fs::write(worktree_dir.join("logs/HEAD"), "").unwrap();
+        assert_ne!(fingerprint(&worktree, Some("origin/main")), packed);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_store_index_drops_missing_repos_of_scanned_roots_only() {
+        let scanned = |root: &str, path: &str| ScannedRepo {
+            root: root.into(),
+            repo: LocalRepo {
+                name: path.rsplit('/').next().unwrap().into(),
+                path: path.into(),
+                current_branch: "main".into(),
+                is_dirty: false,
+                status: RepoStatus::default(),
+            },
+            fingerprint: String::new(),
+            refreshed: true,
+        };
+        async fn indexed(pool: &SqlitePool) -> Vec<String> {
+            sqlx::query_scalar::<_, String>("SELECT path FROM repos ORDER BY path")
+                .fetch_all(pool)
+                .await
+                .unwrap()
+        }
+
+        tauri::async_runtime::block_on(async {
+            let pool = crate::db::test_pool().await;
+            let all = [
+                scanned("/src", "/src/api"),
+                scanned("/src", "/src/web"),
+                scanned("/work", "/work/tool"),
+            ];
+            store_index(&pool, None, &all).await.unwrap();
+            assert_eq!(indexed(&pool).await, ["/src/api", "/src/web", "/work/tool"]);
+
+            // Rescanning /src alone leaves the repos under /work alone
+            let roots = ["/src".to_string()];
+            store_index(&pool, Some(&roots), &all[1..2]).await.unwrap();
+            assert_eq!(indexed(&pool).await, ["/src/web", "/work/tool"]);
+
+            store_index(&pool, None, &all[1..2]).await.unwrap();
+            assert_eq!(indexed(&pool).await, ["/src/web"]);
+        });
+    }
+
+    #[test]
+    fn test_read_index_skips_rows_that_do_not_decode() {
+        let repo = |name: &str| LocalRepo {
+            name: name.into(),
+            path: format!("/src/{}", name),
+            current_branch: "main".into(),
+            is_dirty: false,
+            status: RepoStatus::default(),
+        };
+
+        tauri::async_runtime::block_on(async {
+            let pool = crate::db::test_pool().await;
+            for (path, data) in [
+                ("/src/web", serde_json::to_string(&repo("web")).unwrap()),
+                ("/src/old", r#"{"name":"old"}"#.to_string()),
+                ("/src/api", serde_json::to_string(&repo("api")).unwrap()),
+            ] {
+                sqlx::query(
+                    "INSERT INTO repos (path, root, name, fingerprint, data) VALUES (?, '/src', '', '', ?)",
+                )
+                .bind(path)
+                .bind(data)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+
+            let names: Vec<String> = read_index(&pool)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect();
+            assert_eq!(names, ["api", "web"]);
+        });
     }
 }
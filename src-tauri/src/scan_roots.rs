@@ -0,0 +1,427 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::path::Path;
+use tauri::AppHandle;
+
+use crate::error::{AuraError, AuraResult};
+
+// -- Types --
+
+pub const DEFAULT_MAX_DEPTH: u32 = 4;
+
+/// Directories skipped unless a root says otherwise: hidden ones and
+/// dependency and build output
+pub const DEFAULT_EXCLUDE: [&str; 3] = [".*", "node_modules", "target"];
+
+const MAX_DEPTH_LIMIT: u32 = 16;
+
+/// A directory searched for Git repos, with how to walk it.
+///
+/// Patterns are globs: `*` and `?` match within a path segment and `**`
+/// matches any number of segments. A pattern without a `/` matches a
+/// directory's name at any depth; one with a `/` matches its path relative
+/// to the root. A directory matching `include` is walked even if `exclude`
+/// matches it too.
+#[derive(Debug, Serialize, Clone)]
+pub struct ScanRoot {
+    pub id: String,
+    pub path: String,
+    pub max_depth: u32,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Walk into symlinked directories. Each directory is still walked once,
+    /// so links back up the tree can't loop.
+    pub follow_symlinks: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NewScanRoot {
+    pub path: String,
+    pub max_depth: Option<u32>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub follow_symlinks: Option<bool>,
+}
+
+/// Fields left out are kept as they are
+#[derive(Debug, Deserialize, Default)]
+pub struct ScanRootUpdate {
+    pub path: Option<String>,
+    pub max_depth: Option<u32>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub follow_symlinks: Option<bool>,
+}
+
+impl ScanRoot {
+    /// A root that isn't stored, walked with the default options
+    pub fn with_defaults(path: &str, max_depth: u32) -> Self {
+        ScanRoot {
+            id: String::new(),
+            path: path.to_string(),
+            max_depth,
+            include: vec![],
+            exclude: DEFAULT_EXCLUDE.iter().map(|p| p.to_string()).collect(),
+            follow_symlinks: false,
+            created_at: String::new(),
+        }
+    }
+
+    /// Whether the walk leaves out a directory, given its `/`-separated path
+    /// relative to the root
+    pub fn skips(&self, relative: &str) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        let matches = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| pattern_matches(pattern, relative, name))
+        };
+        if !matches(&self.exclude) {
+            return false;
+        }
+        // An include deeper down keeps the excluded directories above it
+        !(matches(&self.include)
+            || self
+                .include
+                .iter()
+                .any(|pattern| leads_to(pattern, relative)))
+    }
+}
+
+// -- Globs --
+
+/// `*` and `?` against one path segment
+fn segment_matches(pattern: &[char], text: &[char]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            segment_matches(&pattern[1..], text)
+                || (!text.is_empty() && segment_matches(pattern, &text[1..]))
+        }
+        (Some('?'), Some(_)) => segment_matches(&pattern[1..], &text[1..]),
+        (Some(p), Some(t)) if p == t => segment_matches(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+fn glob_segment(pattern: &str, segment: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let segment: Vec<char> = segment.chars().collect();
+    segment_matches(&pattern, &segment)
+}
+
+fn path_matches(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|i| path_matches(rest, &path[i..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(segment, path_rest)| {
+            glob_segment(first, segment) && path_matches(rest, path_rest)
+        }),
+    }
+}
+
+fn pattern_matches(pattern: &str, relative: &str, name: &str) -> bool {
+    let pattern = pattern.trim_matches('/');
+    if pattern.contains('/') {
+        let pattern: Vec<&str> = pattern.split('/').collect();
+        let path: Vec<&str> = relative.split('/').collect();
+        path_matches(&pattern, &path)
+    } else {
+        glob_segment(pattern, name)
+    }
+}
+
+/// Whether a path pattern can match something below `relative`
+fn leads_to(pattern: &str, relative: &str) -> bool {
+    let pattern: Vec<&str> = pattern.trim_matches('/').split('/').collect();
+    if pattern.len() < 2 {
+        return false;
+    }
+    let path: Vec<&str> = relative.split('/').collect();
+    for (i, segment) in path.iter().enumerate() {
+        match pattern.get(i) {
+            Some(&"**") => return true,
+            Some(p) if glob_segment(p, segment) => continue,
+            _ => return false,
+        }
+    }
+    path.len() < pattern.len()
+}
+
+// -- Validation --
+
+/// `~` and `~/...` against the home directory
+fn expand_home(path: &str) -> String {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            match std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
+                Ok(home) => format!("{}{}", home, rest),
+                Err(_) => path.to_string(),
+            }
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// The root's path as stored: trimmed, home expanded, no trailing separator
+fn normalize_path(path: &str) -> AuraResult<String> {
+    let path = expand_home(path.trim());
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    let path = if trimmed.is_empty() { &path } else { trimmed };
+    if path.is_empty() {
+        return Err(AuraError::invalid_input("A scan directory needs a path."));
+    }
+    if !Path::new(path).is_absolute() {
+        return Err(AuraError::invalid_input(format!(
+            "{} isn't an absolute path.",
+            path
+        )));
+    }
+    if !Path::new(path).is_dir() {
+        return Err(AuraError::invalid_input(format!(
+            "{} isn't a directory.",
+            path
+        )));
+    }
+    Ok(path.to_string())
+}
+
+fn normalize_patterns(patterns: Vec<String>) -> AuraResult<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::with_capacity(patterns.len());
+    for pattern in patterns {
+        let pattern = pattern.trim().replace('\\', "/");
+        if pattern.trim_matches('/').is_empty() {
+            return Err(AuraError::invalid_input("A pattern is empty."));
+        }
+        if !normalized.contains(&pattern) {
+            normalized.push(pattern);
+        }
+    }
+    Ok(normalized)
+}
+
+fn validate_depth(max_depth: u32) -> AuraResult<()> {
+    if max_depth > MAX_DEPTH_LIMIT {
+        return Err(AuraError::invalid_input(format!(
+            "The depth can be at most {}.",
+            MAX_DEPTH_LIMIT
+        )));
+    }
+    Ok(())
+}
+
+// -- Storage --
+
+type ScanRootRow = (String, String, i64, String, String, bool, String);
+
+const SELECT_ROOTS: &str =
+    "SELECT id, path, max_depth, include, exclude, follow_symlinks, created_at FROM scan_roots";
+
+fn from_row(row: ScanRootRow) -> AuraResult<ScanRoot> {
+    let (id, path, max_depth, include, exclude, follow_symlinks, created_at) = row;
+    Ok(ScanRoot {
+        id,
+        path,
+        max_depth: max_depth.clamp(0, MAX_DEPTH_LIMIT as i64) as u32,
+        include: serde_json::from_str(&include)?,
+        exclude: serde_json::from_str(&exclude)?,
+        follow_symlinks,
+        created_at,
+    })
+}
+
+async fn load_scan_root(pool: &SqlitePool, id: &str) -> AuraResult<ScanRoot> {
+    let row = sqlx::query_as::<_, ScanRootRow>(&format!("{} WHERE id = ?", SELECT_ROOTS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AuraError::invalid_input(format!("Unknown scan directory: {}", id)))?;
+    from_row(row)
+}
+
+/// All configured roots, by path
+pub(crate) async fn load_scan_roots(pool: &SqlitePool) -> AuraResult<Vec<ScanRoot>> {
+    let rows = sqlx::query_as::<_, ScanRootRow>(&format!("{} ORDER BY path", SELECT_ROOTS))
+        .fetch_all(pool)
+        .await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+async fn path_taken(pool: &SqlitePool, path: &str, except_id: Option<&str>) -> AuraResult<bool> {
+    let count = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM scan_roots WHERE path = ? AND id IS NOT ?",
+    )
+    .bind(path)
+    .bind(except_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(count > 0)
+}
+
+// -- Tauri commands --
+
+#[tauri::command]
+pub async fn list_scan_roots(app: AppHandle) -> AuraResult<Vec<ScanRoot>> {
+    let pool = crate::db::pool(&app).await?;
+    load_scan_roots(&pool).await
+}
+
+#[tauri::command]
+pub async fn create_scan_root(app: AppHandle, root: NewScanRoot) -> AuraResult<ScanRoot> {
+    let path = normalize_path(&root.path)?;
+    let max_depth = root.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+    validate_depth(max_depth)?;
+    let include = normalize_patterns(root.include.unwrap_or_default())?;
+    let exclude = match root.exclude {
+        Some(exclude) => normalize_patterns(exclude)?,
+        None => ScanRoot::with_defaults(&path, max_depth).exclude,
+    };
+    let pool = crate::db::pool(&app).await?;
+    if path_taken(&pool, &path, None).await? {
+        return Err(AuraError::invalid_input(format!(
+            "{} is already scanned.",
+            path
+        )));
+    }
+
+    let id = crate::db::new_id();
+    sqlx::query(
+        "INSERT INTO scan_roots (id, path, max_depth, include, exclude, follow_symlinks) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&path)
+    .bind(max_depth)
+    .bind(serde_json::to_string(&include)?)
+    .bind(serde_json::to_string(&exclude)?)
+    .bind(root.follow_symlinks.unwrap_or(false))
+    .execute(&pool)
+    .await?;
+
+    load_scan_root(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn update_scan_root(
+    app: AppHandle,
+    id: String,
+    update: ScanRootUpdate,
+) -> AuraResult<ScanRoot> {
+    let pool = crate::db::pool(&app).await?;
+    let existing = load_scan_root(&pool, &id).await?;
+    let path = match update.path {
+        Some(path) => normalize_path(&path)?,
+        None => existing.path.clone(),
+    };
+    let max_depth = update.max_depth.unwrap_or(existing.max_depth);
+    validate_depth(max_depth)?;
+    let include = normalize_patterns(update.include.unwrap_or(existing.include))?;
+    let exclude = normalize_patterns(update.exclude.unwrap_or(existing.exclude))?;
+    if path_taken(&pool, &path, Some(&id)).await? {
+        return Err(AuraError::invalid_input(format!(
+            "{} is already scanned.",
+            path
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE scan_roots SET path = ?, max_depth = ?, include = ?, exclude = ?, follow_symlinks = ? WHERE id = ?",
+    )
+    .bind(&path)
+    .bind(max_depth)
+    .bind(serde_json::to_string(&include)?)
+    .bind(serde_json::to_string(&exclude)?)
+    .bind(update.follow_symlinks.unwrap_or(existing.follow_symlinks))
+    .bind(&id)
+    .execute(&mut *tx)
+    .await?;
+    if path != existing.path {
+        sqlx::query("DELETE FROM repos WHERE root = ?")
+            .bind(&existing.path)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    load_scan_root(&pool, &id).await
+}
+
+/// Stop scanning a directory. Its repos leave the index too.
+#[tauri::command]
+pub async fn delete_scan_root(app: AppHandle, id: String) -> AuraResult<()> {
+    let pool = crate::db::pool(&app).await?;
+    let existing = load_scan_root(&pool, &id).await?;
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM scan_roots WHERE id = ?")
+        .bind(&id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM repos WHERE root = ?")
+        .bind(&existing.path)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_patterns(include: &[&str], exclude: &[&str]) -> ScanRoot {
+        ScanRoot {
+            include: include.iter().map(|p| p.to_string()).collect(),
+            exclude: exclude.iter().map(|p| p.to_string()).collect(),
+            ..ScanRoot::with_defaults("/home/dev", DEFAULT_MAX_DEPTH)
+        }
+    }
+
+    #[test]
+    fn test_globs() {
+        assert!(glob_segment(".*", ".config"));
+        assert!(glob_segment("*-old", "web-old"));
+        assert!(glob_segment("v?", "v2"));
+        assert!(!glob_segment("v?", "v10"));
+        assert!(pattern_matches(
+            "work/*/vendor",
+            "work/web/vendor",
+            "vendor"
+        ));
+        assert!(!pattern_matches("work/*/vendor", "work/vendor", "vendor"));
+        assert!(pattern_matches("**/build", "a/b/build", "build"));
+        assert!(pattern_matches("**/build", "build", "build"));
+    }
+
+    #[test]
+    fn test_default_excludes() {
+        let defaults = with_patterns(&[], &DEFAULT_EXCLUDE);
+        assert!(defaults.skips(".config"));
+        assert!(defaults.skips("web/node_modules"));
+        assert!(!defaults.skips("work"));
+    }
+
+    #[test]
+    fn test_include_overrides_exclude() {
+        let root = with_patterns(&[".config", "work/.archive"], &DEFAULT_EXCLUDE);
+        assert!(!root.skips(".config"));
+        assert!(!root.skips("work/.archive"));
+        assert!(root.skips(".cache"));
+        assert!(root.skips("play/.archive"));
+
+        // Hidden directories on the way to an included path are walked
+        let root = with_patterns(&[".dotfiles/*/repos"], &DEFAULT_EXCLUDE);
+        assert!(!root.skips(".dotfiles"));
+        assert!(!root.skips(".dotfiles/nvim/repos"));
+        assert!(root.skips(".dotfiles/nvim/.git"));
+    }
+
+    #[test]
+    fn test_normalize_patterns() {
+        assert_eq!(
+            normalize_patterns(vec![" vendor ".into(), "a\\b".into(), "vendor".into()]).unwrap(),
+            vec!["vendor".to_string(), "a/b".to_string()]
+        );
+        assert!(normalize_patterns(vec!["/".into()]).is_err());
+    }
+}
@@ -0,0 +1,547 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::process::{Child, Command};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::{AuraError, AuraResult};
+use crate::sessions::SessionTool;
+
+// -- Types --
+
+/// Processes of sessions launched during this run of the app, by session id.
+/// A watcher thread per session removes the entry when the process exits.
+#[derive(Default)]
+pub struct SessionRegistry(pub Mutex<HashMap<String, Child>>);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Running,
+    Exited,
+    Killed,
+    /// Still running when the app quit, so its end was never seen. It is
+    /// taken to have ended at its last heartbeat.
+    Lost,
+}
+
+impl SessionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionStatus::Running => "running",
+            SessionStatus::Exited => "exited",
+            SessionStatus::Killed => "killed",
+            SessionStatus::Lost => "lost",
+        }
+    }
+}
+
+impl std::str::FromStr for SessionStatus {
+    type Err = AuraError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(SessionStatus::Running),
+            "exited" => Ok(SessionStatus::Exited),
+            "killed" => Ok(SessionStatus::Killed),
+            "lost" => Ok(SessionStatus::Lost),
+            _ => Err(AuraError::invalid_input(format!(
+                "Unknown session status: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// A launched tool. Launchers that hand off to an already running app
+/// (`code`, `open -a`) exit straight away, so their sessions end at once.
+#[derive(Debug, Serialize, Clone)]
+pub struct Session {
+    pub id: String,
+    pub tool_id: String,
+    pub tool_name: String,
+    pub repo_path: String,
+    pub work_item_id: Option<String>,
+    /// From the work item cache; missing for items not synced yet
+    pub work_item_title: Option<String>,
+    pub pid: i64,
+    pub status: SessionStatus,
+    pub exit_code: Option<i64>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    /// Up to now for running sessions
+    pub duration_seconds: i64,
+}
+
+/// Filters for `list_sessions`; all optional
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct SessionFilter {
+    /// Only sessions that are still running
+    pub running: bool,
+    pub work_item_id: Option<String>,
+    pub repo_path: Option<String>,
+    /// Sessions started at or after this `YYYY-MM-DD[ HH:MM:SS]` (UTC)
+    pub since: Option<String>,
+    pub limit: Option<i64>,
+}
+
+const DEFAULT_LIMIT: i64 = 200;
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+type SessionRow = (
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    i64,
+    String,
+    Option<i64>,
+    String,
+    Option<String>,
+    i64,
+);
+
+const SELECT_SESSIONS: &str = "SELECT s.id, s.tool_id, s.tool_name, s.repo_path, s.work_item_id,
+        json_extract(w.data, '$.title'), s.pid, s.status, s.exit_code, s.started_at, s.ended_at,
+        strftime('%s', COALESCE(s.ended_at, datetime('now'))) - strftime('%s', s.started_at)
+    FROM sessions s
+    LEFT JOIN work_items w ON w.id = s.work_item_id";
+
+fn from_row(row: SessionRow) -> AuraResult<Session> {
+    let (
+        id,
+        tool_id,
+        tool_name,
+        repo_path,
+        work_item_id,
+        work_item_title,
+        pid,
+        status,
+        exit_code,
+        started_at,
+        ended_at,
+        duration_seconds,
+    ) = row;
+    Ok(Session {
+        id,
+        tool_id,
+        tool_name,
+        repo_path,
+        work_item_id,
+        work_item_title,
+        pid,
+        status: status.parse()?,
+        exit_code,
+        started_at,
+        ended_at,
+        duration_seconds,
+    })
+}
+
+async fn load_session(pool: &SqlitePool, id: &str) -> AuraResult<Session> {
+    let row = sqlx::query_as::<_, SessionRow>(&format!("{} WHERE s.id = ?", SELECT_SESSIONS))
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AuraError::invalid_input(format!("Unknown session: {}", id)))?;
+    from_row(row)
+}
+
+/// Sessions matching `filter`, newest first
+async fn query_sessions(pool: &SqlitePool, filter: SessionFilter) -> AuraResult<Vec<Session>> {
+    let rows = sqlx::query_as::<_, SessionRow>(&format!(
+        "{} WHERE (?1 = 0 OR s.status = 'running') AND (?2 IS NULL OR s.work_item_id = ?2)
+           AND (?3 IS NULL OR s.repo_path = ?3) AND (?4 IS NULL OR s.started_at >= ?4)
+         ORDER BY s.started_at DESC, s.rowid DESC LIMIT ?5",
+        SELECT_SESSIONS
+    ))
+    .bind(filter.running)
+    .bind(filter.work_item_id)
+    .bind(filter.repo_path)
+    .bind(filter.since)
+    .bind(filter.limit.unwrap_or(DEFAULT_LIMIT))
+    .fetch_all(pool)
+    .await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+/// Record the end of a session, unless it was already recorded
+async fn finish(
+    pool: &SqlitePool,
+    id: &str,
+    status: SessionStatus,
+    exit_code: Option<i32>,
+) -> AuraResult<()> {
+    sqlx::query(
+        "UPDATE sessions SET status = ?, exit_code = ?, ended_at = datetime('now')
+         WHERE id = ? AND status = 'running'",
+    )
+    .bind(status.as_str())
+    .bind(exit_code)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Mark sessions the database thinks are running but this run of the app
+/// never launched
+async fn mark_lost(pool: &SqlitePool, registry: &SessionRegistry) -> AuraResult<()> {
+    let running =
+        sqlx::query_scalar::<_, String>("SELECT id FROM sessions WHERE status = 'running'")
+            .fetch_all(pool)
+            .await?;
+    let lost: Vec<String> = {
+        let children = registry.0.lock().unwrap();
+        running
+            .into_iter()
+            .filter(|id| !children.contains_key(id))
+            .collect()
+    };
+    // Their end went unseen; the last heartbeat is the best guess
+    for id in lost {
+        sqlx::query(
+            "UPDATE sessions SET status = 'lost', ended_at = COALESCE(last_seen_at, started_at)
+             WHERE id = ? AND status = 'running'",
+        )
+        .bind(&id)
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+async fn heartbeat(pool: &SqlitePool, id: &str) -> AuraResult<()> {
+    sqlx::query(
+        "UPDATE sessions SET last_seen_at = datetime('now') WHERE id = ? AND status = 'running'",
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Poll a session's process until it exits, then record how it ended.
+/// Returns without recording anything if the session was killed, since
+/// `kill_session` records that itself. Records a heartbeat every minute
+/// meanwhile, so a session outliving the app still has an approximate end.
+fn watch(app: AppHandle, id: String) {
+    let registry = app.state::<SessionRegistry>();
+    let mut last_beat = Instant::now();
+    let (status, exit_code) = loop {
+        std::thread::sleep(Duration::from_secs(1));
+        {
+            let mut children = registry.0.lock().unwrap();
+            let Some(child) = children.get_mut(&id) else {
+                return;
+            };
+            match child.try_wait() {
+                Ok(None) => {}
+                Ok(Some(exit)) => {
+                    children.remove(&id);
+                    break (SessionStatus::Exited, exit.code());
+                }
+                Err(_) => {
+                    children.remove(&id);
+                    break (SessionStatus::Lost, None);
+                }
+            }
+        }
+        if last_beat.elapsed() >= HEARTBEAT_INTERVAL {
+            last_beat = Instant::now();
+            let _ = tauri::async_runtime::block_on(async {
+                let pool = crate::db::pool(&app).await?;
+                heartbeat(&pool, &id).await
+            });
+        }
+    };
+
+    let _ = tauri::async_runtime::block_on(async {
+        let pool = crate::db::pool(&app).await?;
+        finish(&pool, &id, status, exit_code).await
+    });
+}
+
+/// Record a launched session and keep its process so it can be watched,
+/// focused and killed
+pub async fn register(
+    app: &AppHandle,
+    tool: &SessionTool,
+    repo_path: &str,
+    work_item_id: Option<&str>,
+    child: Child,
+) -> AuraResult<Session> {
+    let pool = crate::db::pool(app).await?;
+    let id = crate::db::new_id();
+    let pid = child.id() as i64;
+    // Registered before the row exists, so a concurrent `mark_lost` never
+    // sees a running row without its process
+    let registry = app.state::<SessionRegistry>();
+    registry.0.lock().unwrap().insert(id.clone(), child);
+    let inserted = sqlx::query(
+        "INSERT INTO sessions (id, tool_id, tool_name, repo_path, work_item_id, pid) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&tool.id)
+    .bind(&tool.name)
+    .bind(repo_path)
+    .bind(work_item_id)
+    .bind(pid)
+    .execute(&pool)
+    .await;
+    if let Err(e) = inserted {
+        registry.0.lock().unwrap().remove(&id);
+        return Err(e.into());
+    }
+
+    let (app, session_id) = (app.clone(), id.clone());
+    std::thread::spawn(move || watch(app, session_id));
+
+    load_session(&pool, &id).await
+}
+
+/// Bring the windows of a process to the front
+fn focus_pid(pid: i64) -> AuraResult<()> {
+    let (tool, mut cmd) = if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("osascript");
+        cmd.args([
+            "-e",
+            &format!(
+                "tell application \"System Events\" to set frontmost of (first process whose unix id is {}) to true",
+                pid
+            ),
+        ]);
+        ("osascript", cmd)
+    } else if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("powershell");
+        cmd.args([
+            "-NoProfile",
+            "-Command",
+            &format!("(New-Object -ComObject WScript.Shell).AppActivate({})", pid),
+        ]);
+        ("powershell", cmd)
+    } else {
+        let mut cmd = Command::new("xdotool");
+        cmd.args(["search", "--pid", &pid.to_string(), "windowactivate"]);
+        ("xdotool", cmd)
+    };
+
+    let output = cmd.output().map_err(|e| AuraError::from_spawn(tool, e))?;
+    if !output.status.success() {
+        return Err(AuraError::cli_failed(
+            tool,
+            "The session has no window to focus.",
+        ));
+    }
+    Ok(())
+}
+
+// -- Tauri commands --
+
+/// Running and past sessions, newest first
+#[tauri::command]
+pub async fn list_sessions(
+    app: AppHandle,
+    registry: State<'_, SessionRegistry>,
+    filter: Option<SessionFilter>,
+) -> AuraResult<Vec<Session>> {
+    let pool = crate::db::pool(&app).await?;
+    mark_lost(&pool, &registry).await?;
+    query_sessions(&pool, filter.unwrap_or_default()).await
+}
+
+#[tauri::command]
+pub async fn focus_session(app: AppHandle, id: String) -> AuraResult<()> {
+    let pool = crate::db::pool(&app).await?;
+    let session = load_session(&pool, &id).await?;
+    if session.status != SessionStatus::Running {
+        return Err(AuraError::invalid_input(format!(
+            "{} is no longer running.",
+            session.tool_name
+        )));
+    }
+    tauri::async_runtime::spawn_blocking(move || focus_pid(session.pid))
+        .await
+        .map_err(AuraError::io)?
+}
+
+/// Kill a running session's process. Only sessions launched during this run
+/// of the app can be killed; an old pid may belong to something else now.
+#[tauri::command]
+pub async fn kill_session(
+    app: AppHandle,
+    registry: State<'_, SessionRegistry>,
+    id: String,
+) -> AuraResult<Session> {
+    let pool = crate::db::pool(&app).await?;
+    let child = registry.0.lock().unwrap().remove(&id);
+    let Some(mut child) = child else {
+        let session = load_session(&pool, &id).await?;
+        return Err(AuraError::invalid_input(format!(
+            "{} is no longer running.",
+            session.tool_name
+        )));
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        // Reap it so it doesn't linger as a zombie
+        child.kill().and_then(|_| child.wait())
+    })
+    .await
+    .map_err(AuraError::io)?
+    .map_err(AuraError::io)?;
+
+    finish(&pool, &id, SessionStatus::Killed, None).await?;
+    load_session(&pool, &id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_status_round_trip() {
+        for status in [
+            SessionStatus::Running,
+            SessionStatus::Exited,
+            SessionStatus::Killed,
+            SessionStatus::Lost,
+        ] {
+            assert_eq!(status.as_str().parse::<SessionStatus>().unwrap(), status);
+        }
+        assert!("paused".parse::<SessionStatus>().is_err());
+    }
+
+    async fn insert(pool: &SqlitePool, id: &str, repo_path: &str, started_at: &str) {
+        sqlx::query(
+            "INSERT INTO sessions (id, tool_id, tool_name, repo_path, work_item_id, pid, started_at)
+             VALUES (?, 'claude', 'Claude Code', ?, 'jira:PROJ-1', 1, ?)",
+        )
+        .bind(id)
+        .bind(repo_path)
+        .bind(started_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    fn ids(sessions: &[Session]) -> Vec<&str> {
+        sessions.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_query_sessions_filters() {
+        tauri::async_runtime::block_on(async {
+            let pool = crate::db::test_pool().await;
+            insert(&pool, "a", "/src/web", "2026-01-01 09:00:00").await;
+            insert(&pool, "b", "/src/api", "2026-01-02 09:00:00").await;
+            insert(&pool, "c", "/src/web", "2026-01-03 09:00:00").await;
+            sqlx::query("UPDATE sessions SET work_item_id = NULL WHERE id = 'c'")
+                .execute(&pool)
+                .await
+                .unwrap();
+            finish(&pool, "b", SessionStatus::Exited, Some(0))
+                .await
+                .unwrap();
+
+            let all = query_sessions(&pool, SessionFilter::default())
+                .await
+                .unwrap();
+            assert_eq!(ids(&all), ["c", "b", "a"]);
+
+            let filter = |f: SessionFilter| query_sessions(&pool, f);
+            let running = filter(SessionFilter {
+                running: true,
+                ..Default::default()
+            });
+            assert_eq!(ids(&running.await.unwrap()), ["c", "a"]);
+            let for_item = filter(SessionFilter {
+                work_item_id: Some("jira:PROJ-1".into()),
+                ..Default::default()
+            });
+            assert_eq!(ids(&for_item.await.unwrap()), ["b", "a"]);
+            let in_repo = filter(SessionFilter {
+                repo_path: Some("/src/web".into()),
+                ..Default::default()
+            });
+            assert_eq!(ids(&in_repo.await.unwrap()), ["c", "a"]);
+            let since = filter(SessionFilter {
+                since: Some("2026-01-02".into()),
+                ..Default::default()
+            });
+            assert_eq!(ids(&since.await.unwrap()), ["c", "b"]);
+            let limited = filter(SessionFilter {
+                limit: Some(1),
+                ..Default::default()
+            });
+            assert_eq!(ids(&limited.await.unwrap()), ["c"]);
+        });
+    }
+
+    #[test]
+    fn test_duration_of_ended_and_running_sessions() {
+        tauri::async_runtime::block_on(async {
+            let pool = crate::db::test_pool().await;
+            insert(&pool, "ended", "/src/web", "2026-01-01 09:00:00").await;
+            sqlx::query("UPDATE sessions SET status = 'exited', ended_at = '2026-01-01 10:30:15'")
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO sessions (id, tool_id, tool_name, repo_path, pid, started_at)
+                 VALUES ('running', 'claude', 'Claude Code', '/src/web', 1, datetime('now', '-90 seconds'))",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let ended = load_session(&pool, "ended").await.unwrap();
+            assert_eq!(ended.duration_seconds, 90 * 60 + 15);
+            // Counted up to now, give or take the clock ticking over
+            let running = load_session(&pool, "running").await.unwrap();
+            assert!((90..=91).contains(&running.duration_seconds));
+        });
+    }
+
+    #[test]
+    fn test_mark_lost_spares_sessions_of_this_run() {
+        tauri::async_runtime::block_on(async {
+            let pool = crate::db::test_pool().await;
+            insert(&pool, "previous-run", "/src/web", "2026-01-01 09:00:00").await;
+            sqlx::query(
+                "UPDATE sessions SET last_seen_at = '2026-01-01 09:20:00' WHERE id = 'previous-run'",
+            )
+                .execute(&pool)
+                .await
+                .unwrap();
+            insert(&pool, "this-run", "/src/web", "2026-01-01 10:00:00").await;
+            insert(&pool, "exited", "/src/web", "2026-01-01 11:00:00").await;
+            finish(&pool, "exited", SessionStatus::Exited, Some(1))
+                .await
+                .unwrap();
+
+            let registry = SessionRegistry::default();
+            let child = Command::new("sleep").arg("30").spawn().unwrap();
+            registry.0.lock().unwrap().insert("this-run".into(), child);
+
+            mark_lost(&pool, &registry).await.unwrap();
+            let status = |id: &'static str| {
+                let pool = pool.clone();
+                async move { load_session(&pool, id).await.unwrap().status }
+            };
+            assert_eq!(status("previous-run").await, SessionStatus::Lost);
+            let lost = load_session(&pool, "previous-run").await.unwrap();
+            assert_eq!(lost.ended_at.as_deref(), Some("2026-01-01 09:20:00"));
+            assert_eq!(status("this-run").await, SessionStatus::Running);
+            assert_eq!(status("exited").await, SessionStatus::Exited);
+
+            let mut child = registry.0.lock().unwrap().remove("this-run").unwrap();
+            child.kill().and_then(|_| child.wait()).unwrap();
+        });
+    }
+}
@@ -1,5 +1,26 @@
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use tauri::{AppHandle, State};
+
+use crate::context::WorkContext;
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::session_registry::{self, Session};
+
+/// How a tool receives work item context
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "via", rename_all = "snake_case")]
+pub enum ContextDelivery {
+    /// Arguments appended to the tool's own. `{prompt}` is replaced with the
+    /// rendered context, `{context_file}` with the path of a file holding it.
+    Args { args: Vec<String> },
+    /// The rendered context is written to the tool's stdin
+    Stdin,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SessionTool {
@@ -9,83 +30,622 @@ pub struct SessionTool {
     pub args: Vec<String>,
     pub available: bool,
     pub category: String, // "editor", "terminal", "ai"
+    /// `None` for tools that can't take work item context
+    pub context: Option<ContextDelivery>,
+    /// Extra environment variables; values take the same placeholders as args
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Ships with Aura. A custom tool with the same id overrides it.
+    #[serde(default)]
+    pub builtin: bool,
+    /// Defined or overridden in settings
+    #[serde(default)]
+    pub custom: bool,
 }
 
-/// Check if a command exists on PATH
+/// A custom tool as entered in settings
+#[derive(Debug, Deserialize)]
+pub struct SessionToolInput {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub category: String,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    pub context: Option<ContextDelivery>,
+}
+
+/// Context files live here, relative to the repo, and are kept out of git
+const CONTEXT_DIR: &str = ".aura/context";
+
+const CATEGORIES: [&str; 4] = ["editor", "terminal", "ai", "other"];
+/// Placeholders in args and env values
+const LAUNCH_PLACEHOLDERS: [&str; 3] = ["repo", "branch", "issue_key"];
+/// Further placeholders in context args
+const CONTEXT_PLACEHOLDERS: [&str; 2] = ["prompt", "context_file"];
+
+/// Check if a command exists on PATH, or as a file if given as a path
 fn command_exists(cmd: &str) -> bool {
-    Command::new("which")
+    if cmd.contains('/') || cmd.contains('\\') {
+        return Path::new(cmd).is_file();
+    }
+    Command::new(if cfg!(windows) { "where" } else { "which" })
         .arg(cmd)
         .output()
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
 
+fn context_args(args: &[&str]) -> Option<ContextDelivery> {
+    Some(ContextDelivery::Args {
+        args: args.iter().map(|a| a.to_string()).collect(),
+    })
+}
+
+/// Terminal emulators to try on Linux, in order: the user's `$TERMINAL`,
+/// the Debian/Ubuntu default, then common emulators. `{repo}` is replaced
+/// with the repository path for emulators that don't start in the working
+/// directory they are launched from.
+fn linux_terminals(env_terminal: Option<&str>) -> Vec<(String, Vec<String>)> {
+    let mut terminals = Vec::new();
+    if let Some(mut parts) = env_terminal.map(str::split_whitespace) {
+        if let Some(cmd) = parts.next() {
+            terminals.push((cmd.to_string(), parts.map(String::from).collect()));
+        }
+    }
+    let known: [(&str, &[&str]); 7] = [
+        ("x-terminal-emulator", &[]),
+        ("gnome-terminal", &["--working-directory={repo}"]),
+        ("konsole", &["--workdir", "{repo}"]),
+        ("kitty", &["--directory", "{repo}"]),
+        ("alacritty", &["--working-directory", "{repo}"]),
+        ("wezterm", &["start", "--cwd", "{repo}"]),
+        ("xterm", &[]),
+    ];
+    terminals.extend(known.iter().map(|(cmd, args)| {
+        (
+            cmd.to_string(),
+            args.iter().map(|a| a.to_string()).collect(),
+        )
+    }));
+    terminals
+}
+
+/// Windows Terminal, else PowerShell 7, else Windows PowerShell. A console
+/// program started from the GUI gets its own window.
+fn windows_terminals() -> Vec<(String, Vec<String>)> {
+    vec![
+        (
+            "wt".to_string(),
+            vec!["-d".to_string(), "{repo}".to_string()],
+        ),
+        ("pwsh".to_string(), vec!["-NoExit".to_string()]),
+        ("powershell".to_string(), vec!["-NoExit".to_string()]),
+    ]
+}
+
+/// The first terminal found for this platform, or the preferred one marked
+/// unavailable
+fn detect_terminal() -> SessionTool {
+    let candidates = if cfg!(target_os = "macos") {
+        vec![(
+            "open".to_string(),
+            vec!["-a".to_string(), "Terminal".to_string(), ".".to_string()],
+        )]
+    } else if cfg!(windows) {
+        windows_terminals()
+    } else {
+        linux_terminals(std::env::var("TERMINAL").ok().as_deref())
+    };
+    let found = candidates.iter().find(|(cmd, _)| command_exists(cmd));
+    let (command, args) = found.unwrap_or(&candidates[0]).clone();
+    SessionTool {
+        id: "terminal".to_string(),
+        name: "Terminal".to_string(),
+        command,
+        args,
+        available: found.is_some(),
+        category: "terminal".to_string(),
+        context: None,
+        env: BTreeMap::new(),
+        builtin: true,
+        custom: false,
+    }
+}
+
+/// Values substituted into a tool's arguments and environment
+struct LaunchVars {
+    repo: String,
+    branch: String,
+    issue_key: String,
+}
+
+impl LaunchVars {
+    fn new(repo_path: &str, issue_key: Option<&str>) -> Self {
+        let branch = crate::repos::get_current_branch(Path::new(repo_path));
+        LaunchVars {
+            repo: repo_path.to_string(),
+            // Detached or unreadable HEAD
+            branch: if branch == "HEAD" || branch == "unknown" {
+                String::new()
+            } else {
+                branch
+            },
+            issue_key: issue_key.unwrap_or_default().to_string(),
+        }
+    }
+
+    fn expand(&self, value: &str) -> String {
+        value
+            .replace("{repo}", &self.repo)
+            .replace("{branch}", &self.branch)
+            .replace("{issue_key}", &self.issue_key)
+    }
+}
+
+/// Expand placeholders in a tool's arguments. An argument that was only
+/// placeholders with nothing to fill in is dropped rather than passed empty.
+fn expand_args(args: &[String], vars: &LaunchVars) -> Vec<String> {
+    args.iter()
+        .filter_map(|a| {
+            let expanded = vars.expand(a);
+            (a.is_empty() || !expanded.is_empty()).then_some(expanded)
+        })
+        .collect()
+}
+
 /// Detect available session tools
 pub fn detect_tools() -> Vec<SessionTool> {
     let tools = vec![
-        ("vscode", "VS Code", "code", vec!["."], "editor"),
+        ("vscode", "VS Code", "code", vec!["."], "editor", None),
         (
-            "terminal",
-            "Terminal",
-            "open",
-            vec!["-a", "Terminal", "."],
-            "terminal",
+            "claude-code",
+            "Claude Code",
+            "claude",
+            vec![],
+            "ai",
+            context_args(&["{prompt}"]),
+        ),
+        (
+            "gh-copilot",
+            "GitHub Copilot",
+            "gh",
+            vec!["copilot"],
+            "ai",
+            context_args(&[
+                "-i",
+                "Read {context_file} and work on the item it describes.",
+            ]),
+        ),
+        (
+            "codex",
+            "Codex",
+            "codex",
+            vec![],
+            "ai",
+            context_args(&["{prompt}"]),
+        ),
+        (
+            "opencode",
+            "OpenCode",
+            "opencode",
+            vec![],
+            "ai",
+            context_args(&["--prompt", "{prompt}"]),
         ),
-        ("claude-code", "Claude Code", "claude", vec![], "ai"),
-        ("gh-copilot", "GitHub Copilot", "gh", vec!["copilot"], "ai"),
-        ("codex", "Codex", "codex", vec![], "ai"),
-        ("opencode", "OpenCode", "opencode", vec![], "ai"),
     ];
 
-    tools
+    let mut tools: Vec<SessionTool> = tools
         .into_iter()
-        .map(|(id, name, cmd, args, category)| SessionTool {
+        .map(|(id, name, cmd, args, category, context)| SessionTool {
             id: id.to_string(),
             name: name.to_string(),
             command: cmd.to_string(),
             args: args.into_iter().map(String::from).collect(),
             available: command_exists(cmd),
             category: category.to_string(),
+            context,
+            env: BTreeMap::new(),
+            builtin: true,
+            custom: false,
         })
-        .collect()
+        .collect();
+    tools.insert(1, detect_terminal());
+    tools
 }
 
-/// Launch a tool in a repository directory
-pub fn launch_tool(tool_id: &str, repo_path: &str) -> Result<(), String> {
-    let tools = detect_tools();
+/// Built-ins with custom tools applied: a custom tool replaces the built-in
+/// with the same id, the rest are appended
+fn merge_tools(builtins: Vec<SessionTool>, custom: Vec<SessionTool>) -> Vec<SessionTool> {
+    let mut tools = builtins;
+    for mut tool in custom {
+        tool.available = command_exists(&tool.command);
+        match tools.iter_mut().find(|t| t.id == tool.id) {
+            Some(builtin) => {
+                tool.builtin = true;
+                *builtin = tool;
+            }
+            None => tools.push(tool),
+        }
+    }
+    tools
+}
+
+fn find_tool<'a>(tools: &'a [SessionTool], tool_id: &str) -> AuraResult<&'a SessionTool> {
     let tool = tools
         .iter()
         .find(|t| t.id == tool_id)
-        .ok_or_else(|| format!("Unknown tool: {}", tool_id))?;
+        .ok_or_else(|| AuraError::invalid_input(format!("Unknown tool: {}", tool_id)))?;
 
     if !tool.available {
-        return Err(format!(
-            "{} is not installed or not found on PATH",
-            tool.name
-        ));
+        return Err(AuraError::CliMissing {
+            tool: tool.command.clone(),
+        });
     }
+    Ok(tool)
+}
+
+/// Launch a tool in a repository directory
+pub fn launch_tool(tools: &[SessionTool], tool_id: &str, repo_path: &str) -> AuraResult<Child> {
+    spawn_tool(find_tool(tools, tool_id)?, repo_path, None)
+}
+
+/// Write the context to `.aura/context/<key>.md` in the repo and make sure
+/// git ignores the directory. Returns the path relative to the repo.
+fn write_context_file(repo_path: &Path, context: &WorkContext) -> AuraResult<String> {
+    let name: String = context
+        .key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let relative = format!("{}/{}.md", CONTEXT_DIR, name);
+    let path = repo_path.join(&relative);
+    std::fs::create_dir_all(repo_path.join(CONTEXT_DIR)).map_err(AuraError::io)?;
+    std::fs::write(&path, &context.text).map_err(AuraError::io)?;
+
+    // Worktrees keep `.git` as a file, so ask git where the exclude file is
+    if let Ok(exclude) = crate::repos::git(repo_path, &["rev-parse", "--git-path", "info/exclude"])
+    {
+        let exclude = repo_path.join(PathBuf::from(exclude));
+        let current = std::fs::read_to_string(&exclude).unwrap_or_default();
+        if !current.lines().any(|l| l.trim() == "/.aura/") {
+            if let Some(dir) = exclude.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let separator = if current.is_empty() || current.ends_with('\n') {
+                ""
+            } else {
+                "\n"
+            };
+            let _ = std::fs::write(&exclude, format!("{}{}/.aura/\n", current, separator));
+        }
+    }
+    Ok(relative)
+}
+
+/// Launch an AI tool in a repository with a work item as its starting
+/// context, using whichever mechanism the tool supports
+pub fn launch_tool_with_context(
+    tools: &[SessionTool],
+    tool_id: &str,
+    repo_path: &str,
+    context: &WorkContext,
+) -> AuraResult<Child> {
+    spawn_tool(find_tool(tools, tool_id)?, repo_path, Some(context))
+}
+
+fn spawn_tool(
+    tool: &SessionTool,
+    repo_path: &str,
+    context: Option<&WorkContext>,
+) -> AuraResult<Child> {
+    let vars = LaunchVars::new(repo_path, context.map(|c| c.key.as_str()));
 
     let mut cmd = Command::new(&tool.command);
-    cmd.args(&tool.args);
+    cmd.args(expand_args(&tool.args, &vars));
+    cmd.envs(tool.env.iter().map(|(k, v)| (k, vars.expand(v))));
     cmd.current_dir(repo_path);
 
-    // For terminal and editors, we spawn and detach
-    cmd.spawn()
-        .map_err(|e| format!("Failed to launch {}: {}", tool.name, e))?;
+    let stdin_context = match (context, &tool.context) {
+        (None, _) => None,
+        (Some(_), None) => {
+            return Err(AuraError::invalid_input(format!(
+                "{} can't take work item context.",
+                tool.name
+            )));
+        }
+        (Some(context), Some(ContextDelivery::Args { args })) => {
+            let file = if args.iter().any(|a| a.contains("{context_file}")) {
+                write_context_file(Path::new(repo_path), context)?
+            } else {
+                String::new()
+            };
+            cmd.args(expand_args(args, &vars).iter().map(|a| {
+                a.replace("{context_file}", &file)
+                    .replace("{prompt}", &context.text)
+            }));
+            None
+        }
+        (Some(context), Some(ContextDelivery::Stdin)) => {
+            cmd.stdin(Stdio::piped());
+            Some(context)
+        }
+    };
+
+    // The child is handed to the session registry, which watches it
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| AuraError::io(format!("Failed to launch {}: {}", tool.name, e)))?;
+    if let Some(context) = stdin_context {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(context.text.as_bytes()).map_err(|e| {
+                AuraError::io(format!("Failed to send context to {}: {}", tool.name, e))
+            })?;
+        }
+    }
+
+    Ok(child)
+}
 
+// -- Custom tools --
+
+/// Reject `{name}` placeholders other than the allowed ones
+fn check_placeholders(value: &str, allowed: &[&str]) -> AuraResult<()> {
+    let mut rest = value;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 1..start + end];
+        if !allowed.contains(&name) {
+            return Err(AuraError::invalid_input(format!(
+                "Unknown placeholder {{{}}}. Use {}.",
+                name,
+                allowed
+                    .iter()
+                    .map(|p| format!("{{{}}}", p))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+        rest = &rest[start + end + 1..];
+    }
     Ok(())
 }
 
+fn validate(id: &str, tool: &SessionToolInput) -> AuraResult<()> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(AuraError::invalid_input(
+            "A tool id uses lowercase letters, digits, - and _.",
+        ));
+    }
+    if tool.name.trim().is_empty() {
+        return Err(AuraError::invalid_input("A tool needs a name."));
+    }
+    if tool.command.trim().is_empty() {
+        return Err(AuraError::invalid_input("A tool needs a command."));
+    }
+    if !CATEGORIES.contains(&tool.category.as_str()) {
+        return Err(AuraError::invalid_input(format!(
+            "Unknown category: {}",
+            tool.category
+        )));
+    }
+    for (key, value) in &tool.env {
+        if key.is_empty() || key.contains('=') || key.contains(char::is_whitespace) {
+            return Err(AuraError::invalid_input(format!(
+                "Invalid environment variable name: {}",
+                key
+            )));
+        }
+        check_placeholders(value, &LAUNCH_PLACEHOLDERS)?;
+    }
+    for arg in &tool.args {
+        check_placeholders(arg, &LAUNCH_PLACEHOLDERS)?;
+    }
+    if let Some(ContextDelivery::Args { args }) = &tool.context {
+        let allowed = [&LAUNCH_PLACEHOLDERS[..], &CONTEXT_PLACEHOLDERS[..]].concat();
+        for arg in args {
+            check_placeholders(arg, &allowed)?;
+        }
+    }
+    Ok(())
+}
+
+type ToolRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+);
+
+fn from_row(row: ToolRow) -> AuraResult<SessionTool> {
+    let (id, name, command, args, category, env, context) = row;
+    Ok(SessionTool {
+        id,
+        name,
+        command,
+        args: serde_json::from_str(&args)?,
+        available: false,
+        category,
+        context: context.map(|c| serde_json::from_str(&c)).transpose()?,
+        env: serde_json::from_str(&env)?,
+        builtin: false,
+        custom: true,
+    })
+}
+
+async fn load_custom_tools(pool: &SqlitePool) -> AuraResult<Vec<SessionTool>> {
+    let rows = sqlx::query_as::<_, ToolRow>(
+        "SELECT id, name, command, args, category, env, context FROM session_tools ORDER BY created_at, id",
+    )
+    .fetch_all(pool)
+    .await?;
+    rows.into_iter().map(from_row).collect()
+}
+
+/// Built-in and custom tools, with availability checked. Runs `which` for
+/// every tool, so it is kept off the async runtime.
+pub async fn all_tools(pool: &SqlitePool) -> AuraResult<Vec<SessionTool>> {
+    let custom = load_custom_tools(pool).await?;
+    tauri::async_runtime::spawn_blocking(move || merge_tools(detect_tools(), custom))
+        .await
+        .map_err(AuraError::io)
+}
+
+async fn tool_exists(pool: &SqlitePool, id: &str) -> AuraResult<bool> {
+    let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM session_tools WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+async fn saved_tool(pool: &SqlitePool, id: &str) -> AuraResult<SessionTool> {
+    all_tools(pool)
+        .await?
+        .into_iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| AuraError::invalid_input(format!("Unknown tool: {}", id)))
+}
+
 // -- Tauri commands --
 
+/// Built-in and custom tools, custom ones overriding built-ins by id
+#[tauri::command]
+pub async fn detect_session_tools(app: AppHandle) -> AuraResult<Vec<SessionTool>> {
+    let pool = crate::db::pool(&app).await?;
+    all_tools(&pool).await
+}
+
+/// Launch a tool and track it as a session, optionally for a work item
+#[tauri::command]
+pub async fn launch_session(
+    app: AppHandle,
+    tool_id: String,
+    repo_path: String,
+    work_item_id: Option<String>,
+) -> AuraResult<Session> {
+    let pool = crate::db::pool(&app).await?;
+    let tools = all_tools(&pool).await?;
+    let tool = find_tool(&tools, &tool_id)?.clone();
+    let path = repo_path.clone();
+    let child = tauri::async_runtime::spawn_blocking(move || launch_tool(&tools, &tool_id, &path))
+        .await
+        .map_err(AuraError::io)??;
+    session_registry::register(&app, &tool, &repo_path, work_item_id.as_deref(), child).await
+}
+
+/// Launch an AI tool with a cached work item's title, description,
+/// acceptance criteria and links as its starting context
+#[tauri::command]
+pub async fn launch_session_for_item(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    tool_id: String,
+    repo_path: String,
+    work_item_id: String,
+) -> AuraResult<Session> {
+    let pool = crate::db::pool(&app).await?;
+    let tools = all_tools(&pool).await?;
+    let tool = find_tool(&tools, &tool_id)?.clone();
+    let context = crate::context::work_item_context(&http, &pool, &work_item_id).await?;
+    let path = repo_path.clone();
+    let child = tauri::async_runtime::spawn_blocking(move || {
+        launch_tool_with_context(&tools, &tool_id, &path, &context)
+    })
+    .await
+    .map_err(AuraError::io)??;
+    session_registry::register(&app, &tool, &repo_path, Some(&work_item_id), child).await
+}
+
+/// Add a custom tool. Using a built-in's id overrides that built-in.
 #[tauri::command]
-pub fn detect_session_tools() -> Vec<SessionTool> {
-    detect_tools()
+pub async fn create_session_tool(
+    app: AppHandle,
+    id: String,
+    tool: SessionToolInput,
+) -> AuraResult<SessionTool> {
+    let id = id.trim().to_string();
+    validate(&id, &tool)?;
+    let pool = crate::db::pool(&app).await?;
+    if tool_exists(&pool, &id).await? {
+        return Err(AuraError::invalid_input(format!(
+            "There is already a custom tool with id {}.",
+            id
+        )));
+    }
+
+    sqlx::query(
+        "INSERT INTO session_tools (id, name, command, args, category, env, context) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(tool.name.trim())
+    .bind(tool.command.trim())
+    .bind(serde_json::to_string(&tool.args)?)
+    .bind(&tool.category)
+    .bind(serde_json::to_string(&tool.env)?)
+    .bind(tool.context.as_ref().map(serde_json::to_string).transpose()?)
+    .execute(&pool)
+    .await?;
+
+    saved_tool(&pool, &id).await
 }
 
 #[tauri::command]
-pub fn launch_session(tool_id: String, repo_path: String) -> Result<(), String> {
-    launch_tool(&tool_id, &repo_path)
+pub async fn update_session_tool(
+    app: AppHandle,
+    id: String,
+    tool: SessionToolInput,
+) -> AuraResult<SessionTool> {
+    validate(&id, &tool)?;
+    let pool = crate::db::pool(&app).await?;
+    if !tool_exists(&pool, &id).await? {
+        return Err(AuraError::invalid_input(format!(
+            "Unknown custom tool: {}",
+            id
+        )));
+    }
+
+    sqlx::query(
+        "UPDATE session_tools SET name = ?, command = ?, args = ?, category = ?, env = ?, context = ?, updated_at = datetime('now') WHERE id = ?",
+    )
+    .bind(tool.name.trim())
+    .bind(tool.command.trim())
+    .bind(serde_json::to_string(&tool.args)?)
+    .bind(&tool.category)
+    .bind(serde_json::to_string(&tool.env)?)
+    .bind(tool.context.as_ref().map(serde_json::to_string).transpose()?)
+    .bind(&id)
+    .execute(&pool)
+    .await?;
+
+    saved_tool(&pool, &id).await
+}
+
+/// Remove a custom tool; for an override this restores the built-in
+#[tauri::command]
+pub async fn delete_session_tool(app: AppHandle, id: String) -> AuraResult<()> {
+    let pool = crate::db::pool(&app).await?;
+    sqlx::query("DELETE FROM session_tools WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -102,7 +662,109 @@ mod tests {
 
     #[test]
     fn test_launch_unknown_tool() {
-        let result = launch_tool("nonexistent", "/tmp");
+        let result = launch_tool(&detect_tools(), "nonexistent", "/tmp");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_terminal_is_always_listed() {
+        let tools = detect_tools();
+        let terminal = tools.iter().find(|t| t.id == "terminal").unwrap();
+        assert_eq!(terminal.category, "terminal");
+    }
+
+    #[test]
+    fn test_linux_terminals_prefer_env() {
+        let terminals = linux_terminals(Some("kitty --single-instance"));
+        assert_eq!(terminals[0].0, "kitty");
+        assert_eq!(terminals[0].1, vec!["--single-instance"]);
+        assert_eq!(terminals[1].0, "x-terminal-emulator");
+
+        let terminals = linux_terminals(None);
+        assert_eq!(terminals[0].0, "x-terminal-emulator");
+        let vars = LaunchVars {
+            repo: "/src/web".into(),
+            branch: String::new(),
+            issue_key: String::new(),
+        };
+        assert_eq!(
+            expand_args(&terminals[1].1, &vars),
+            vec!["--working-directory=/src/web"]
+        );
+    }
+
+    #[test]
+    fn test_expand_args_drops_empty_placeholders() {
+        let vars = LaunchVars {
+            repo: "/src/web".into(),
+            branch: "main".into(),
+            issue_key: String::new(),
+        };
+        let args = vec![
+            "{repo}".to_string(),
+            "--title={branch}".to_string(),
+            "{issue_key}".to_string(),
+            String::new(),
+        ];
+        assert_eq!(
+            expand_args(&args, &vars),
+            vec!["/src/web", "--title=main", ""]
+        );
+    }
+
+    #[test]
+    fn test_custom_tool_overrides_builtin() {
+        let custom = |id: &str| SessionTool {
+            id: id.into(),
+            name: "Custom".into(),
+            command: "/nonexistent/tool".into(),
+            args: vec![],
+            available: true,
+            category: "editor".into(),
+            context: None,
+            env: BTreeMap::new(),
+            builtin: false,
+            custom: true,
+        };
+        let builtins = detect_tools();
+        let count = builtins.len();
+        let tools = merge_tools(builtins, vec![custom("vscode"), custom("zed")]);
+
+        assert_eq!(tools.len(), count + 1);
+        let vscode = tools.iter().find(|t| t.id == "vscode").unwrap();
+        assert!(vscode.builtin && vscode.custom);
+        assert_eq!(vscode.name, "Custom");
+        assert!(!vscode.available);
+        let zed = tools.iter().find(|t| t.id == "zed").unwrap();
+        assert!(!zed.builtin);
+    }
+
+    #[test]
+    fn test_validate_custom_tool() {
+        let input = |args: &[&str]| SessionToolInput {
+            name: "Zed".into(),
+            command: "zed".into(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            category: "editor".into(),
+            env: BTreeMap::new(),
+            context: None,
+        };
+        assert!(validate("zed", &input(&["{repo}", "--branch={branch}"])).is_ok());
+        assert!(validate("zed", &input(&["{prompt}"])).is_err());
+        assert!(validate("Zed Editor", &input(&[])).is_err());
+    }
+
+    #[test]
+    fn test_ai_tools_take_context() {
+        let tools = detect_tools();
+        for tool in tools.iter().filter(|t| t.category == "ai") {
+            assert!(
+                tool.context.is_some(),
+                "{} has no context delivery",
+                tool.id
+            );
+        }
+        let vscode = tools.iter().find(|t| t.id == "vscode").unwrap();
+        assert!(vscode.context.is_none());
+    }
 }
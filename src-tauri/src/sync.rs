@@ -0,0 +1,430 @@
+use chrono::{DateTime, Duration as ChronoDuration, SecondsFormat, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::connections::Connection;
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::provider::{
+    load_providers, sort_work_items, Provider, ProviderKind, WorkItem, WorkItemKind,
+};
+
+/// How often the scheduler wakes up to look for connections that are due
+const SCHEDULER_TICK: Duration = Duration::from_secs(30);
+const DEFAULT_INTERVAL_MINUTES: i64 = 5;
+const MIN_INTERVAL_MINUTES: i64 = 1;
+const MAX_INTERVAL_MINUTES: i64 = 60;
+
+/// Event emitted after a connection's cache was refreshed; payload is the
+/// connection id
+pub const SYNCED_EVENT: &str = "work-items-synced";
+
+/// Event emitted when a sync fails before reaching any connection (database
+/// or connection list unavailable); payload is the error message
+pub const SYNC_FAILED_EVENT: &str = "work-items-sync-failed";
+
+/// Connections with a sync currently running, so overlapping triggers
+/// (scheduler tick, page open, manual refresh) do not hit the same API twice.
+#[derive(Default)]
+pub struct SyncState(pub Mutex<HashSet<String>>);
+
+impl SyncState {
+    fn try_begin(&self, connection_id: &str) -> bool {
+        self.0.lock().unwrap().insert(connection_id.to_string())
+    }
+
+    fn finish(&self, connection_id: &str) {
+        self.0.lock().unwrap().remove(connection_id);
+    }
+
+    fn is_running(&self, connection_id: &str) -> bool {
+        self.0.lock().unwrap().contains(connection_id)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConnectionSyncStatus {
+    pub connection_id: String,
+    pub provider: ProviderKind,
+    pub label: String,
+    pub interval_minutes: i64,
+    pub last_synced_at: Option<String>,
+    pub last_error: Option<String>,
+    pub syncing: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CachedWorkItems {
+    pub items: Vec<WorkItem>,
+    pub sync: Vec<ConnectionSyncStatus>,
+}
+
+struct Schedule {
+    interval_minutes: i64,
+    last_attempt_at: Option<String>,
+    last_synced_at: Option<String>,
+    last_error: Option<String>,
+}
+
+fn now_rfc3339() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+// -- Cache storage --
+
+async fn load_schedules(pool: &SqlitePool) -> AuraResult<HashMap<String, Schedule>> {
+    let rows = sqlx::query_as::<_, (String, i64, Option<String>, Option<String>, Option<String>)>(
+        "SELECT connection_id, interval_minutes, last_attempt_at, last_synced_at, last_error FROM connection_sync",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(connection_id, interval_minutes, last_attempt_at, last_synced_at, last_error)| {
+                (
+                    connection_id,
+                    Schedule {
+                        interval_minutes,
+                        last_attempt_at,
+                        last_synced_at,
+                        last_error,
+                    },
+                )
+            },
+        )
+        .collect())
+}
+
+/// Replace the cached items of one connection and kind. Items that
+/// disappeared remotely (closed, unassigned) disappear from the cache too,
+/// unless the fetch wasn't `complete`: then nothing is removed, since the
+/// missing items may just be in a part that couldn't be reached.
+async fn replace_items(
+    pool: &SqlitePool,
+    connection: &Connection,
+    kind: WorkItemKind,
+    items: &[WorkItem],
+    complete: bool,
+) -> AuraResult<()> {
+    let synced_at = now_rfc3339();
+    let mut tx = pool.begin().await?;
+
+    if complete {
+        sqlx::query("DELETE FROM work_items WHERE connection_id = ? AND kind = ?")
+            .bind(&connection.id)
+            .bind(kind.as_str())
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    for item in items {
+        let data = serde_json::to_string(item)?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO work_items (id, connection_id, provider, kind, updated_at, data, synced_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&item.id)
+        .bind(&connection.id)
+        .bind(connection.provider.as_str())
+        .bind(kind.as_str())
+        .bind(&item.updated_at)
+        .bind(data)
+        .bind(&synced_at)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await.map_err(AuraError::from)
+}
+
+async fn record_attempt(
+    pool: &SqlitePool,
+    connection_id: &str,
+    error: Option<&str>,
+) -> AuraResult<()> {
+    let now = now_rfc3339();
+    // A failed attempt keeps the previous last_synced_at so the UI can still
+    // say how old the cached data is.
+    sqlx::query(
+        "INSERT INTO connection_sync (connection_id, last_attempt_at, last_synced_at, last_error) VALUES (?, ?, ?, ?)
+         ON CONFLICT(connection_id) DO UPDATE SET
+             last_attempt_at = excluded.last_attempt_at,
+             last_synced_at = COALESCE(excluded.last_synced_at, connection_sync.last_synced_at),
+             last_error = excluded.last_error",
+    )
+    .bind(connection_id)
+    .bind(&now)
+    .bind(if error.is_none() { Some(&now) } else { None })
+    .bind(error)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+async fn read_cache(pool: &SqlitePool, kind: Option<WorkItemKind>) -> AuraResult<Vec<WorkItem>> {
+    let rows = match kind {
+        Some(kind) => {
+            sqlx::query_scalar::<_, String>("SELECT data FROM work_items WHERE kind = ?")
+                .bind(kind.as_str())
+                .fetch_all(pool)
+                .await
+        }
+        None => {
+            sqlx::query_scalar::<_, String>("SELECT data FROM work_items")
+                .fetch_all(pool)
+                .await
+        }
+    }?;
+
+    // Rows written by an older model version are skipped rather than failing
+    // the whole list; the next sync rewrites them.
+    let mut items: Vec<WorkItem> = rows
+        .iter()
+        .filter_map(|data| serde_json::from_str(data).ok())
+        .collect();
+    sort_work_items(&mut items);
+    Ok(items)
+}
+
+/// One cached item by its unified id
+pub async fn cached_work_item(pool: &SqlitePool, id: &str) -> AuraResult<WorkItem> {
+    let data = sqlx::query_scalar::<_, String>("SELECT data FROM work_items WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            AuraError::invalid_input("This work item is not synced yet. Refresh and try again.")
+        })?;
+    serde_json::from_str(&data).map_err(AuraError::from)
+}
+
+// -- Sync --
+
+fn is_due(schedule: Option<&Schedule>, now: DateTime<Utc>) -> bool {
+    let Some(schedule) = schedule else {
+        return true;
+    };
+    let Some(last) = schedule
+        .last_attempt_at
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+    else {
+        return true;
+    };
+    now - last.with_timezone(&Utc) >= ChronoDuration::minutes(schedule.interval_minutes)
+}
+
+async fn sync_connection(app: &AppHandle, pool: &SqlitePool, provider: &dyn Provider) {
+    let connection = provider.connection();
+    let state = app.state::<SyncState>();
+    if !state.try_begin(&connection.id) {
+        return;
+    }
+
+    let (assigned, pull_requests) =
+        futures::join!(provider.fetch_assigned(), provider.fetch_pull_requests());
+
+    let mut errors = Vec::new();
+    for (item_kind, result) in [
+        (WorkItemKind::Issue, assigned),
+        (WorkItemKind::PullRequest, pull_requests),
+    ] {
+        let stored = match result {
+            Ok(fetched) => {
+                for (source, e) in &fetched.failures {
+                    errors.push(format!("{}: {}", source, e));
+                }
+                // Keep the cached items of the parts that failed
+                let complete = fetched.failures.is_empty();
+                replace_items(pool, connection, item_kind, &fetched.items, complete).await
+            }
+            Err(e) => Err(e),
+        };
+        if let Err(e) = stored {
+            errors.push(e.to_string());
+        }
+    }
+
+    let error = if errors.is_empty() {
+        None
+    } else {
+        Some(errors.join("; "))
+    };
+    let _ = record_attempt(pool, &connection.id, error.as_deref()).await;
+
+    state.finish(&connection.id);
+    let _ = app.emit(SYNCED_EVENT, &connection.id);
+}
+
+/// Sync every connection that is due, or all of them when `force` is set.
+/// `only` restricts the run to a single connection.
+pub async fn sync_providers(app: &AppHandle, force: bool, only: Option<String>) -> AuraResult<()> {
+    let pool = crate::db::pool(app).await?;
+    let http = app.state::<HttpClient>();
+    let providers = load_providers(&http, &pool).await?;
+    let schedules = load_schedules(&pool).await?;
+    let now = Utc::now();
+
+    let due: Vec<&Box<dyn Provider>> = providers
+        .iter()
+        .filter(|p| only.as_deref().is_none_or(|o| o == p.connection().id))
+        .filter(|p| force || is_due(schedules.get(&p.connection().id), now))
+        .collect();
+
+    futures::future::join_all(
+        due.into_iter()
+            .map(|p| sync_connection(app, &pool, p.as_ref())),
+    )
+    .await;
+    Ok(())
+}
+
+/// Run [`sync_providers`] on the async runtime without waiting for it
+fn spawn_sync(app: AppHandle, force: bool, only: Option<String>) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = sync_providers(&app, force, only).await {
+            let _ = app.emit(SYNC_FAILED_EVENT, e.to_string());
+        }
+    });
+}
+
+/// Start the background loop that keeps the cache fresh
+pub fn start_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = sync_providers(&app, false, None).await {
+                let _ = app.emit(SYNC_FAILED_EVENT, e.to_string());
+            }
+            tokio::time::sleep(SCHEDULER_TICK).await;
+        }
+    });
+}
+
+async fn sync_status(
+    pool: &SqlitePool,
+    http: &HttpClient,
+    state: &SyncState,
+) -> AuraResult<Vec<ConnectionSyncStatus>> {
+    let providers = load_providers(http, pool).await?;
+    let mut schedules = load_schedules(pool).await?;
+
+    Ok(providers
+        .iter()
+        .map(|p| {
+            let connection = p.connection();
+            let schedule = schedules.remove(&connection.id);
+            ConnectionSyncStatus {
+                connection_id: connection.id.clone(),
+                provider: connection.provider,
+                label: connection.label.clone(),
+                interval_minutes: schedule
+                    .as_ref()
+                    .map(|s| s.interval_minutes)
+                    .unwrap_or(DEFAULT_INTERVAL_MINUTES),
+                last_synced_at: schedule.as_ref().and_then(|s| s.last_synced_at.clone()),
+                last_error: schedule.and_then(|s| s.last_error),
+                syncing: state.is_running(&connection.id),
+            }
+        })
+        .collect())
+}
+
+// -- Tauri commands --
+
+/// Return cached work items immediately and refresh stale connections in the
+/// background. Listen for `work-items-synced` to pick up the new data.
+#[tauri::command]
+pub async fn get_cached_work_items(
+    app: AppHandle,
+    state: State<'_, SyncState>,
+    kind: Option<WorkItemKind>,
+) -> AuraResult<CachedWorkItems> {
+    let pool = crate::db::pool(&app).await?;
+    let items = read_cache(&pool, kind).await?;
+    let sync = sync_status(&pool, &app.state::<HttpClient>(), &state).await?;
+    spawn_sync(app.clone(), false, None);
+    Ok(CachedWorkItems { items, sync })
+}
+
+/// Force a refresh of one connection (or all) regardless of its interval
+#[tauri::command]
+pub fn sync_work_items(app: AppHandle, connection_id: Option<String>) {
+    spawn_sync(app, true, connection_id);
+}
+
+#[tauri::command]
+pub async fn get_sync_status(
+    app: AppHandle,
+    state: State<'_, SyncState>,
+) -> AuraResult<Vec<ConnectionSyncStatus>> {
+    let pool = crate::db::pool(&app).await?;
+    sync_status(&pool, &app.state::<HttpClient>(), &state).await
+}
+
+/// Set how often a connection is synced in the background (1-60 minutes)
+#[tauri::command]
+pub async fn set_sync_interval(
+    app: AppHandle,
+    connection_id: String,
+    minutes: i64,
+) -> AuraResult<()> {
+    let minutes = minutes.clamp(MIN_INTERVAL_MINUTES, MAX_INTERVAL_MINUTES);
+    let pool = crate::db::pool(&app).await?;
+    sqlx::query(
+        "INSERT INTO connection_sync (connection_id, interval_minutes) VALUES (?, ?)
+         ON CONFLICT(connection_id) DO UPDATE SET interval_minutes = excluded.interval_minutes",
+    )
+    .bind(&connection_id)
+    .bind(minutes)
+    .execute(&pool)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(interval_minutes: i64, last_attempt_at: Option<&str>) -> Schedule {
+        Schedule {
+            interval_minutes,
+            last_attempt_at: last_attempt_at.map(String::from),
+            last_synced_at: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = DateTime::parse_from_rfc3339("2024-03-01T10:10:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert!(is_due(None, now));
+        assert!(is_due(Some(&schedule(5, None)), now));
+        assert!(is_due(
+            Some(&schedule(5, Some("2024-03-01T10:05:00Z"))),
+            now
+        ));
+        assert!(!is_due(
+            Some(&schedule(5, Some("2024-03-01T10:06:00Z"))),
+            now
+        ));
+    }
+
+    #[test]
+    fn test_sync_state_prevents_overlap() {
+        let state = SyncState::default();
+        assert!(state.try_begin("jira"));
+        assert!(!state.try_begin("jira"));
+        assert!(state.is_running("jira"));
+        // Another account on the same provider syncs independently
+        assert!(state.try_begin("jira-work"));
+        state.finish("jira");
+        assert!(state.try_begin("jira"));
+    }
+}
@@ -0,0 +1,129 @@
+//! Helpers shared by the tests of several modules
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+
+/// A request received by `mock_server`
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    /// Path and query string
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// First header of that name, compared case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// A query string parameter, as sent (still percent-encoded)
+    pub fn query(&self, name: &str) -> Option<&str> {
+        let (_, query) = self.path.split_once('?')?;
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v)
+    }
+
+    /// The body as JSON; `Null` if it isn't any
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).unwrap_or(serde_json::Value::Null)
+    }
+}
+
+/// Requests received so far, oldest first
+pub type Requests = Arc<Mutex<Vec<MockRequest>>>;
+
+/// Serve HTTP on a local port, answering each request with the status and
+/// JSON body `respond` returns. Returns the server's base URL and the
+/// requests it received.
+pub fn mock_server(
+    respond: impl Fn(&MockRequest) -> (u16, String) + Send + 'static,
+) -> (String, Requests) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let log: Requests = Arc::new(Mutex::new(Vec::new()));
+    let requests = log.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let Some(request) = read_request(&mut stream) else {
+                continue;
+            };
+            let (status, body) = respond(&request);
+            requests.lock().unwrap().push(request);
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                reason(status),
+                body.len(),
+                body
+            );
+        }
+    });
+    (format!("http://{}", addr), log)
+}
+
+/// Read the headers, then as much body as they announce
+fn read_request(stream: &mut TcpStream) -> Option<MockRequest> {
+    let mut raw = Vec::new();
+    let mut buf = [0u8; 8192];
+    let head_len = loop {
+        if let Some(end) = raw.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        let n = stream.read(&mut buf).ok()?;
+        if n == 0 {
+            return None;
+        }
+        raw.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&raw[..head_len]).to_string();
+    let mut lines = head.lines();
+    // "GET /path HTTP/1.1"
+    let path = lines.next()?.split_whitespace().nth(1)?.to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+    let body_len = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    while raw.len() < head_len + body_len {
+        let n = stream.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        raw.extend_from_slice(&buf[..n]);
+    }
+    Some(MockRequest {
+        path,
+        headers,
+        body: raw[head_len..].to_vec(),
+    })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        _ => "Status",
+    }
+}
@@ -0,0 +1,608 @@
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+use tauri::{AppHandle, State};
+
+use crate::error::{AuraError, AuraResult};
+use crate::http::HttpClient;
+use crate::provider::{provider_for, Provider, WorkItem, WorkLogEntry};
+
+// -- Types --
+
+/// Time tracked on one work item
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct TrackedTime {
+    pub work_item_id: String,
+    /// From the work item cache; missing for items not synced yet
+    pub work_item_title: Option<String>,
+    /// Time covered by finished sessions; overlapping sessions count once
+    pub session_seconds: i64,
+    pub adjustment_seconds: i64,
+    pub exported_seconds: i64,
+    /// Part of `exported_seconds` from exports whose outcome is unknown: the
+    /// tracker call failed midway and the time may or may not be booked
+    pub unconfirmed_seconds: i64,
+    /// Tracked but not booked yet
+    pub pending_seconds: i64,
+    /// A session for the item is still running and isn't counted yet
+    pub running: bool,
+    pub last_worked_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WorklogExport {
+    /// Zero when less than a minute was left to book
+    pub booked_seconds: i64,
+    /// The tracker's worklog, interval or revision id
+    pub remote_id: Option<String>,
+    pub tracked: TrackedTime,
+}
+
+/// Work items with an export in flight, so a double click can't book twice
+static EXPORTING: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+const WORKLOG_COMMENT: &str = "Time tracked in Aura";
+
+// -- Totals --
+
+/// Seconds covered by a set of `(start, end)` intervals, counting overlaps
+/// once. Editor and terminal sessions on the same item often run side by
+/// side; that is still one stretch of work.
+fn covered_seconds(mut intervals: Vec<(i64, i64)>) -> i64 {
+    intervals.sort_unstable();
+    let mut total = 0;
+    let mut current: Option<(i64, i64)> = None;
+    for (start, end) in intervals {
+        current = match current {
+            Some((s, e)) if start <= e => Some((s, e.max(end))),
+            Some((s, e)) => {
+                total += e - s;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    total + current.map_or(0, |(s, e)| e - s)
+}
+
+/// `SUM(seconds)` of a table per work item
+async fn sum_by_item(
+    pool: &SqlitePool,
+    table: &str,
+    work_item_id: Option<&str>,
+) -> AuraResult<BTreeMap<String, i64>> {
+    let rows = sqlx::query_as::<_, (String, i64)>(&format!(
+        "SELECT work_item_id, SUM(seconds) FROM {} WHERE ?1 IS NULL OR work_item_id = ?1 GROUP BY work_item_id",
+        table
+    ))
+    .bind(work_item_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// Per-item totals, most recently worked on first. Sessions marked `lost`
+/// count up to their last heartbeat.
+async fn load_tracked(
+    pool: &SqlitePool,
+    work_item_id: Option<&str>,
+) -> AuraResult<Vec<TrackedTime>> {
+    let items = sqlx::query_as::<_, (String, Option<String>)>(
+        "SELECT t.work_item_id, json_extract(w.data, '$.title')
+         FROM (
+             SELECT work_item_id FROM sessions WHERE work_item_id IS NOT NULL
+             UNION SELECT work_item_id FROM time_adjustments
+             UNION SELECT work_item_id FROM worklog_exports
+         ) t
+         LEFT JOIN work_items w ON w.id = t.work_item_id
+         WHERE ?1 IS NULL OR t.work_item_id = ?1",
+    )
+    .bind(work_item_id)
+    .fetch_all(pool)
+    .await?;
+    let sessions = sqlx::query_as::<_, (String, i64, Option<i64>, Option<String>)>(
+        "SELECT work_item_id, CAST(strftime('%s', started_at) AS INTEGER),
+             CAST(strftime('%s', ended_at) AS INTEGER), COALESCE(ended_at, started_at)
+         FROM sessions
+         WHERE work_item_id IS NOT NULL AND (?1 IS NULL OR work_item_id = ?1)",
+    )
+    .bind(work_item_id)
+    .fetch_all(pool)
+    .await?;
+    let adjustments = sum_by_item(pool, "time_adjustments", work_item_id).await?;
+    let exports = sum_by_item(pool, "worklog_exports", work_item_id).await?;
+    let unconfirmed = sum_by_item(
+        pool,
+        "(SELECT * FROM worklog_exports WHERE status = 'pending')",
+        work_item_id,
+    )
+    .await?;
+
+    let mut intervals: BTreeMap<&str, Vec<(i64, i64)>> = BTreeMap::new();
+    let mut running = BTreeSet::new();
+    let mut last_worked: BTreeMap<&str, &str> = BTreeMap::new();
+    for (id, start, end, last) in &sessions {
+        match end {
+            Some(end) => intervals
+                .entry(id.as_str())
+                .or_default()
+                .push((*start, *end)),
+            None => {
+                running.insert(id.as_str());
+            }
+        }
+        if let Some(last) = last {
+            let latest = last_worked.entry(id.as_str()).or_default();
+            if last.as_str() > *latest {
+                *latest = last.as_str();
+            }
+        }
+    }
+
+    let mut tracked: Vec<TrackedTime> = items
+        .into_iter()
+        .map(|(id, title)| {
+            let session_seconds = intervals.remove(id.as_str()).map_or(0, covered_seconds);
+            let adjustment_seconds = adjustments.get(&id).copied().unwrap_or_default();
+            let exported_seconds = exports.get(&id).copied().unwrap_or_default();
+            TrackedTime {
+                work_item_title: title,
+                session_seconds,
+                adjustment_seconds,
+                exported_seconds,
+                unconfirmed_seconds: unconfirmed.get(&id).copied().unwrap_or_default(),
+                pending_seconds: session_seconds + adjustment_seconds - exported_seconds,
+                running: running.contains(id.as_str()),
+                last_worked_at: last_worked.get(id.as_str()).map(|s| s.to_string()),
+                work_item_id: id,
+            }
+        })
+        .collect();
+    tracked.sort_by(|a, b| b.last_worked_at.cmp(&a.last_worked_at));
+    Ok(tracked)
+}
+
+async fn tracked_for(pool: &SqlitePool, work_item_id: &str) -> AuraResult<TrackedTime> {
+    Ok(load_tracked(pool, Some(work_item_id))
+        .await?
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| TrackedTime {
+            work_item_id: work_item_id.to_string(),
+            ..Default::default()
+        }))
+}
+
+/// Book the unexported time, in whole minutes, with the item's tracker.
+/// The remainder under a minute waits for the next export.
+async fn export(
+    http: &HttpClient,
+    pool: &SqlitePool,
+    work_item_id: &str,
+) -> AuraResult<WorklogExport> {
+    let item = crate::sync::cached_work_item(pool, work_item_id).await?;
+    let connection = crate::connections::load_connection(pool, &item.connection_id).await?;
+    book(pool, provider_for(http, pool, connection).as_ref(), &item).await
+}
+
+/// Export without the lookups. The booking is recorded as pending before
+/// the tracker is called, so a crash or a failure midway can't lead to the
+/// same time being booked again.
+async fn book(
+    pool: &SqlitePool,
+    provider: &dyn Provider,
+    item: &WorkItem,
+) -> AuraResult<WorklogExport> {
+    let tracked = tracked_for(pool, &item.id).await?;
+    let seconds = tracked.pending_seconds / 60 * 60;
+    if seconds <= 0 {
+        return Ok(WorklogExport {
+            booked_seconds: 0,
+            remote_id: None,
+            tracked,
+        });
+    }
+
+    let export_id = crate::db::new_id();
+    sqlx::query(
+        "INSERT INTO worklog_exports (id, work_item_id, seconds, status) VALUES (?, ?, ?, 'pending')",
+    )
+    .bind(&export_id)
+    .bind(&item.id)
+    .bind(seconds)
+    .execute(pool)
+    .await?;
+
+    let entry = WorkLogEntry {
+        started: Utc::now() - Duration::seconds(seconds),
+        seconds,
+        comment: WORKLOG_COMMENT.to_string(),
+    };
+    let remote_id = match provider.log_work(item, &entry).await {
+        Ok(remote_id) => remote_id,
+        // The request may have reached the tracker; stays pending for the
+        // user to settle
+        Err(e @ (AuraError::Network { .. } | AuraError::Parse { .. })) => return Err(e),
+        // Turned down, so nothing was booked
+        Err(e) => {
+            sqlx::query("DELETE FROM worklog_exports WHERE id = ?")
+                .bind(&export_id)
+                .execute(pool)
+                .await?;
+            return Err(e);
+        }
+    };
+
+    sqlx::query("UPDATE worklog_exports SET status = 'exported', remote_id = ? WHERE id = ?")
+        .bind(&remote_id)
+        .bind(&export_id)
+        .execute(pool)
+        .await?;
+
+    Ok(WorklogExport {
+        booked_seconds: seconds,
+        remote_id,
+        tracked: tracked_for(pool, &item.id).await?,
+    })
+}
+
+/// Resolve the pending exports of an item once the user has checked the
+/// tracker: keep them as booked, or drop them so the time is booked again
+async fn settle_pending(pool: &SqlitePool, work_item_id: &str, booked: bool) -> AuraResult<()> {
+    let sql = if booked {
+        "UPDATE worklog_exports SET status = 'exported' WHERE work_item_id = ? AND status = 'pending'"
+    } else {
+        "DELETE FROM worklog_exports WHERE work_item_id = ? AND status = 'pending'"
+    };
+    sqlx::query(sql).bind(work_item_id).execute(pool).await?;
+    Ok(())
+}
+
+// -- Tauri commands --
+
+/// Time tracked per work item, or for one item
+#[tauri::command]
+pub async fn get_tracked_time(
+    app: AppHandle,
+    work_item_id: Option<String>,
+) -> AuraResult<Vec<TrackedTime>> {
+    let pool = crate::db::pool(&app).await?;
+    load_tracked(&pool, work_item_id.as_deref()).await
+}
+
+/// Add (or, with negative seconds, take off) time on a work item by hand
+#[tauri::command]
+pub async fn adjust_tracked_time(
+    app: AppHandle,
+    work_item_id: String,
+    seconds: i64,
+    note: Option<String>,
+) -> AuraResult<TrackedTime> {
+    if seconds == 0 {
+        return Err(AuraError::invalid_input(
+            "Enter the time to add or take off.",
+        ));
+    }
+    let pool = crate::db::pool(&app).await?;
+    crate::sync::cached_work_item(&pool, &work_item_id).await?;
+    let tracked = tracked_for(&pool, &work_item_id).await?;
+    if tracked.session_seconds + tracked.adjustment_seconds + seconds < 0 {
+        return Err(AuraError::invalid_input(
+            "That takes off more time than was tracked.",
+        ));
+    }
+
+    sqlx::query(
+        "INSERT INTO time_adjustments (id, work_item_id, seconds, note) VALUES (?, ?, ?, ?)",
+    )
+    .bind(crate::db::new_id())
+    .bind(&work_item_id)
+    .bind(seconds)
+    .bind(note.unwrap_or_default().trim())
+    .execute(&pool)
+    .await?;
+    tracked_for(&pool, &work_item_id).await
+}
+
+/// Book a work item's unexported time as a Jira worklog, Azure DevOps
+/// Completed Work or a FogBugz interval. Safe to repeat: only time not yet
+/// booked is sent.
+#[tauri::command]
+pub async fn export_worklog(
+    app: AppHandle,
+    http: State<'_, HttpClient>,
+    work_item_id: String,
+) -> AuraResult<WorklogExport> {
+    if !EXPORTING.lock().unwrap().insert(work_item_id.clone()) {
+        return Err(AuraError::invalid_input(
+            "This item's time is already being exported.",
+        ));
+    }
+    let result = match crate::db::pool(&app).await {
+        Ok(pool) => export(&http, &pool, &work_item_id).await,
+        Err(e) => Err(e),
+    };
+    EXPORTING.lock().unwrap().remove(&work_item_id);
+    result
+}
+
+/// Settle exports that failed midway: `booked` if the time shows up in the
+/// tracker, otherwise it becomes pending again
+#[tauri::command]
+pub async fn settle_worklog_export(
+    app: AppHandle,
+    work_item_id: String,
+    booked: bool,
+) -> AuraResult<TrackedTime> {
+    if EXPORTING.lock().unwrap().contains(&work_item_id) {
+        return Err(AuraError::invalid_input(
+            "This item's time is being exported.",
+        ));
+    }
+    let pool = crate::db::pool(&app).await?;
+    settle_pending(&pool, &work_item_id, booked).await?;
+    tracked_for(&pool, &work_item_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connections::{AuthMethod, Connection};
+    use crate::provider::{Fetched, ProviderKind, ProviderStatus, WorkItemKind};
+    use async_trait::async_trait;
+
+    /// Tracker that books whatever it is asked to, unless told to fail
+    struct FakeTracker {
+        connection: Connection,
+        fail_with: Mutex<Option<AuraError>>,
+        booked: Mutex<Vec<i64>>,
+    }
+
+    impl FakeTracker {
+        fn new() -> Self {
+            FakeTracker {
+                connection: Connection {
+                    id: "jira".into(),
+                    provider: ProviderKind::Jira,
+                    label: "Jira".into(),
+                    auth_method: AuthMethod::ApiToken,
+                    username: None,
+                    config: Default::default(),
+                    has_secret: true,
+                    created_at: String::new(),
+                    updated_at: String::new(),
+                },
+                fail_with: Mutex::new(None),
+                booked: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for FakeTracker {
+        fn connection(&self) -> &Connection {
+            &self.connection
+        }
+
+        async fn check_auth(&self) -> AuraResult<ProviderStatus> {
+            Err(AuraError::invalid_input("FakeTracker only books time"))
+        }
+
+        async fn fetch_assigned(&self) -> AuraResult<Fetched> {
+            Err(AuraError::invalid_input("FakeTracker only books time"))
+        }
+
+        async fn search(&self, _query: &str) -> AuraResult<Fetched> {
+            Err(AuraError::invalid_input("FakeTracker only books time"))
+        }
+
+        async fn log_work(
+            &self,
+            _item: &WorkItem,
+            entry: &WorkLogEntry,
+        ) -> AuraResult<Option<String>> {
+            if let Some(e) = self.fail_with.lock().unwrap().take() {
+                return Err(e);
+            }
+            let mut booked = self.booked.lock().unwrap();
+            booked.push(entry.seconds);
+            Ok(Some(format!("worklog-{}", booked.len())))
+        }
+    }
+
+    fn item(provider_id: &str) -> WorkItem {
+        WorkItem::new(
+            "jira",
+            ProviderKind::Jira,
+            WorkItemKind::Issue,
+            provider_id.into(),
+            format!("Title of {}", provider_id),
+            String::new(),
+        )
+    }
+
+    async fn session(
+        pool: &SqlitePool,
+        work_item_id: &str,
+        status: &str,
+        started_at: &str,
+        ended_at: Option<&str>,
+    ) {
+        sqlx::query(
+            "INSERT INTO sessions (id, tool_id, tool_name, repo_path, work_item_id, pid, status, started_at, ended_at)
+             VALUES (?, 'claude', 'Claude Code', '/src/web', ?, 1, ?, ?, ?)",
+        )
+        .bind(crate::db::new_id())
+        .bind(work_item_id)
+        .bind(status)
+        .bind(started_at)
+        .bind(ended_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn adjust(pool: &SqlitePool, work_item_id: &str, seconds: i64) {
+        sqlx::query("INSERT INTO time_adjustments (id, work_item_id, seconds) VALUES (?, ?, ?)")
+            .bind(crate::db::new_id())
+            .bind(work_item_id)
+            .bind(seconds)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_load_tracked_totals() {
+        tauri::async_runtime::block_on(async {
+            let pool = crate::db::test_pool().await;
+            let one = item("PROJ-1");
+            sqlx::query(
+                "INSERT INTO connections (id, provider, label, auth_method)
+                 VALUES ('jira', 'jira', 'Jira', 'api_token')",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO work_items (id, connection_id, provider, kind, updated_at, data, synced_at)
+                 VALUES (?, 'jira', 'jira', 'issue', '', ?, '')",
+            )
+            .bind(&one.id)
+            .bind(serde_json::to_string(&one).unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            // Two overlapping sessions make 20 minutes, plus 5 minutes of a
+            // session that was still running when the app quit
+            let at = |time: &str| format!("2026-01-01 {}", time);
+            session(
+                &pool,
+                &one.id,
+                "exited",
+                &at("09:00:00"),
+                Some(&at("09:10:00")),
+            )
+            .await;
+            session(
+                &pool,
+                &one.id,
+                "killed",
+                &at("09:05:00"),
+                Some(&at("09:20:00")),
+            )
+            .await;
+            session(
+                &pool,
+                &one.id,
+                "lost",
+                &at("10:00:00"),
+                Some(&at("10:05:00")),
+            )
+            .await;
+            adjust(&pool, &one.id, 600).await;
+            for (seconds, status) in [(900, "exported"), (300, "pending")] {
+                sqlx::query(
+                    "INSERT INTO worklog_exports (id, work_item_id, seconds, status) VALUES (?, ?, ?, ?)",
+                )
+                .bind(crate::db::new_id())
+                .bind(&one.id)
+                .bind(seconds)
+                .bind(status)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+            session(&pool, "jira:PROJ-2", "running", &at("11:00:00"), None).await;
+
+            let tracked = load_tracked(&pool, None).await.unwrap();
+            let ids: Vec<&str> = tracked.iter().map(|t| t.work_item_id.as_str()).collect();
+            assert_eq!(ids, ["jira:PROJ-2", "jira:PROJ-1"]);
+
+            let running = &tracked[0];
+            assert!(running.running);
+            assert_eq!(running.session_seconds, 0);
+            assert_eq!(running.work_item_title, None);
+
+            let one = &tracked[1];
+            assert!(!one.running);
+            assert_eq!(one.work_item_title.as_deref(), Some("Title of PROJ-1"));
+            assert_eq!(one.session_seconds, 1500);
+            assert_eq!(one.adjustment_seconds, 600);
+            assert_eq!(one.exported_seconds, 1200);
+            assert_eq!(one.unconfirmed_seconds, 300);
+            assert_eq!(one.pending_seconds, 900);
+            assert_eq!(one.last_worked_at.as_deref(), Some("2026-01-01 10:05:00"));
+
+            let only = load_tracked(&pool, Some("jira:PROJ-2")).await.unwrap();
+            assert_eq!(only.len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_export_never_books_twice() {
+        tauri::async_runtime::block_on(async {
+            let pool = crate::db::test_pool().await;
+            let tracker = FakeTracker::new();
+            let one = item("PROJ-1");
+            session(
+                &pool,
+                &one.id,
+                "exited",
+                "2026-01-01 09:00:00",
+                Some("2026-01-01 09:02:30"),
+            )
+            .await;
+
+            // Whole minutes only; the 30 seconds left wait
+            let export = book(&pool, &tracker, &one).await.unwrap();
+            assert_eq!(export.booked_seconds, 120);
+            assert_eq!(export.remote_id.as_deref(), Some("worklog-1"));
+            assert_eq!(export.tracked.pending_seconds, 30);
+            let again = book(&pool, &tracker, &one).await.unwrap();
+            assert_eq!(again.booked_seconds, 0);
+            assert_eq!(*tracker.booked.lock().unwrap(), [120]);
+
+            // Turned down by the tracker: nothing was booked
+            adjust(&pool, &one.id, 60).await;
+            *tracker.fail_with.lock().unwrap() = Some(AuraError::invalid_input("closed"));
+            assert!(book(&pool, &tracker, &one).await.is_err());
+            assert_eq!(
+                tracked_for(&pool, &one.id).await.unwrap().pending_seconds,
+                90
+            );
+
+            // Cut off midway: counts as booked until settled
+            *tracker.fail_with.lock().unwrap() = Some(AuraError::network("timed out"));
+            assert!(book(&pool, &tracker, &one).await.is_err());
+            let tracked = tracked_for(&pool, &one.id).await.unwrap();
+            assert_eq!(tracked.unconfirmed_seconds, 60);
+            assert_eq!(tracked.pending_seconds, 30);
+            assert_eq!(book(&pool, &tracker, &one).await.unwrap().booked_seconds, 0);
+
+            settle_pending(&pool, &one.id, false).await.unwrap();
+            assert_eq!(
+                book(&pool, &tracker, &one).await.unwrap().booked_seconds,
+                60
+            );
+            assert_eq!(*tracker.booked.lock().unwrap(), [120, 60]);
+
+            let tracked = tracked_for(&pool, &one.id).await.unwrap();
+            assert_eq!(tracked.exported_seconds, 180);
+            assert_eq!(tracked.unconfirmed_seconds, 0);
+        });
+    }
+
+    #[test]
+    fn test_covered_seconds_counts_overlaps_once() {
+        assert_eq!(covered_seconds(vec![]), 0);
+        assert_eq!(covered_seconds(vec![(0, 60)]), 60);
+        // Editor 0-100 with a terminal 50-150 open alongside, then 200-260
+        assert_eq!(covered_seconds(vec![(200, 260), (50, 150), (0, 100)]), 210);
+        // A short session inside a long one
+        assert_eq!(covered_seconds(vec![(0, 300), (10, 20)]), 300);
+    }
+}
@@ -3,6 +3,8 @@ use std::sync::Mutex;
 use tauri::State;
 use velopack::*;
 
+use crate::error::{AuraError, AuraResult};
+
 const UPDATE_URL: &str = "https://github.com/radaiko/Aura/releases/latest/download";
 
 fn current_channel() -> &'static str {
@@ -22,22 +24,22 @@ pub struct UpdateStatus {
     pub version: Option<String>,
 }
 
-fn get_manager() -> Result<UpdateManager, String> {
+fn get_manager() -> AuraResult<UpdateManager> {
     let source = sources::HttpSource::new(UPDATE_URL);
     let options = UpdateOptions {
         ExplicitChannel: Some(current_channel().to_string()),
         ..Default::default()
     };
-    UpdateManager::new(source, Some(options), None).map_err(|e| e.to_string())
+    UpdateManager::new(source, Some(options), None).map_err(AuraError::update)
 }
 
 #[tauri::command]
 pub async fn check_for_updates(
     state: State<'_, UpdaterState>,
-) -> Result<UpdateStatus, String> {
+) -> AuraResult<UpdateStatus> {
     let um = get_manager()?;
 
-    match um.check_for_updates().map_err(|e| e.to_string())? {
+    match um.check_for_updates().map_err(AuraError::update)? {
         UpdateCheck::UpdateAvailable(info) => {
             let version = info.TargetFullRelease.Version.to_string();
             *state.0.lock().unwrap() = Some(um);
@@ -54,29 +56,29 @@ pub async fn check_for_updates(
 }
 
 #[tauri::command]
-pub async fn download_update(state: State<'_, UpdaterState>) -> Result<(), String> {
+pub async fn download_update(state: State<'_, UpdaterState>) -> AuraResult<()> {
     let guard = state.0.lock().unwrap();
-    let um = guard.as_ref().ok_or("No update manager — check for updates first")?;
+    let um = guard.as_ref().ok_or_else(|| AuraError::update("No update manager — check for updates first"))?;
 
-    match um.check_for_updates().map_err(|e| e.to_string())? {
+    match um.check_for_updates().map_err(AuraError::update)? {
         UpdateCheck::UpdateAvailable(info) => {
-            um.download_updates(&info, None).map_err(|e| e.to_string())?;
+            um.download_updates(&info, None).map_err(AuraError::update)?;
             Ok(())
         }
-        _ => Err("No update available".into()),
+        _ => Err(AuraError::update("No update available")),
     }
 }
 
 #[tauri::command]
-pub async fn install_update(state: State<'_, UpdaterState>) -> Result<(), String> {
+pub async fn install_update(state: State<'_, UpdaterState>) -> AuraResult<()> {
     let guard = state.0.lock().unwrap();
-    let um = guard.as_ref().ok_or("No update manager")?;
+    let um = guard.as_ref().ok_or_else(|| AuraError::update("No update manager"))?;
 
-    match um.check_for_updates().map_err(|e| e.to_string())? {
+    match um.check_for_updates().map_err(AuraError::update)? {
         UpdateCheck::UpdateAvailable(info) => {
-            um.apply_updates_and_restart(&info).map_err(|e| e.to_string())?;
+            um.apply_updates_and_restart(&info).map_err(AuraError::update)?;
             Ok(())
         }
-        _ => Err("No update available".into()),
+        _ => Err(AuraError::update("No update available")),
     }
 }
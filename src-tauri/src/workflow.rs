@@ -0,0 +1,482 @@
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::error::{AuraError, AuraResult};
+use crate::links::LinkKind;
+use crate::provider::{ProviderKind, WorkItem};
+use crate::repos::git;
+
+/// `settings` key holding the branch name template
+const BRANCH_TEMPLATE_KEY: &str = "branch_template";
+const DEFAULT_BRANCH_TEMPLATE: &str = "{type}/{key}-{slug}";
+const PLACEHOLDERS: [&str; 4] = ["type", "key", "slug", "provider"];
+/// Longest title slug; the rest of the title is dropped at a word boundary
+const SLUG_MAX_LEN: usize = 40;
+
+#[derive(Debug, Deserialize, Default)]
+pub struct StartWorkOptions {
+    /// Check the branch out in a sibling worktree instead of the repo itself
+    #[serde(default)]
+    pub worktree: bool,
+    /// Branch name to use instead of the template
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StartedWork {
+    pub branch: String,
+    /// Ref the branch was created from; `None` if it already existed
+    pub base: Option<String>,
+    /// Directory to open with `launch_session`
+    pub path: String,
+    pub worktree: bool,
+}
+
+// -- Branch names --
+
+/// "Fix login: retry on 502!" -> "fix-login-retry-on-502"
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for word in title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+    {
+        if !slug.is_empty() && slug.len() + word.len() + 1 > SLUG_MAX_LEN {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word.to_ascii_lowercase());
+    }
+    slug.truncate(SLUG_MAX_LEN);
+    slug
+}
+
+/// Key of an item as people type it: `PROJ-123` for Jira, the bare number
+/// everywhere else
+fn item_key(item: &WorkItem) -> String {
+    let extra = |key: &str| item.extra.get(key).and_then(|v| v.as_u64());
+    match item.provider {
+        ProviderKind::Github => extra("number").map(|n| n.to_string()),
+        ProviderKind::AzureDevops => extra("work_item_id").map(|n| n.to_string()),
+        ProviderKind::Jira | ProviderKind::Fogbugz => None,
+    }
+    .unwrap_or_else(|| item.provider_id.clone())
+}
+
+/// Branch prefix from the item type ("Bug" -> "bug", "User Story" ->
+/// "user-story"), or from a `bug` label for GitHub issues
+fn item_type(item: &WorkItem) -> String {
+    match item.item_type.as_deref().map(slugify) {
+        Some(t) if !t.is_empty() => t,
+        _ if item.labels.iter().any(|l| l.eq_ignore_ascii_case("bug")) => "bug".to_string(),
+        _ => "feature".to_string(),
+    }
+}
+
+/// Reject unknown placeholders and templates that ignore the work item
+fn validate_template(template: &str) -> AuraResult<()> {
+    let mut rest = template;
+    let mut uses_item = false;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| AuraError::invalid_input("Unclosed { in the branch template."))?;
+        let name = &rest[start + 1..start + end];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(AuraError::invalid_input(format!(
+                "Unknown placeholder {{{}}} in the branch template. Use {}.",
+                name,
+                PLACEHOLDERS.map(|p| format!("{{{}}}", p)).join(", ")
+            )));
+        }
+        uses_item |= name == "key" || name == "slug";
+        rest = &rest[start + end + 1..];
+    }
+    if !uses_item {
+        return Err(AuraError::invalid_input(
+            "The branch template needs {key} or {slug}.",
+        ));
+    }
+    Ok(())
+}
+
+/// Make a string a valid git branch name: no spaces or special characters,
+/// no empty, dot-leading or `.lock` components
+fn sanitize_branch(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/') => c,
+            _ => '-',
+        })
+        .collect();
+    cleaned
+        .split('/')
+        .map(|part| {
+            let mut part = part.trim_matches(|c| c == '-' || c == '.').to_string();
+            while part.contains("--") {
+                part = part.replace("--", "-");
+            }
+            while part.contains("..") {
+                part = part.replace("..", ".");
+            }
+            part.trim_end_matches(".lock").to_string()
+        })
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn branch_name(template: &str, item: &WorkItem) -> String {
+    let name = template
+        .replace("{type}", &item_type(item))
+        .replace("{key}", &item_key(item))
+        .replace("{slug}", &slugify(&item.title))
+        .replace("{provider}", item.provider.as_str());
+    sanitize_branch(&name)
+}
+
+async fn branch_template(pool: &SqlitePool) -> AuraResult<String> {
+    Ok(crate::db::get_setting(pool, BRANCH_TEMPLATE_KEY)
+        .await?
+        .unwrap_or_else(|| DEFAULT_BRANCH_TEMPLATE.to_string()))
+}
+
+// -- Git --
+
+/// The remote's default branch (`origin/main`), else a local `main` or
+/// `master`, else whatever is checked out
+fn default_base(repo: &Path) -> String {
+    if let Ok(head) = git(
+        repo,
+        &["symbolic-ref", "--short", "refs/remotes/origin/HEAD"],
+    ) {
+        return head;
+    }
+    ["main", "master"]
+        .into_iter()
+        .find(|b| git(repo, &["rev-parse", "--verify", "--quiet", b]).is_ok())
+        .map(String::from)
+        .unwrap_or_else(|| "HEAD".to_string())
+}
+
+fn branch_exists(repo: &Path, branch: &str) -> bool {
+    git(
+        repo,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{}", branch),
+        ],
+    )
+    .is_ok()
+}
+
+/// Path of the worktree that has `branch` checked out, if any
+fn worktree_for(repo: &Path, branch: &str) -> Option<PathBuf> {
+    let list = git(repo, &["worktree", "list", "--porcelain"]).ok()?;
+    let wanted = format!("branch refs/heads/{}", branch);
+    let mut path = None;
+    for line in list.lines() {
+        if let Some(p) = line.strip_prefix("worktree ") {
+            path = Some(PathBuf::from(p));
+        } else if line == wanted {
+            return path;
+        }
+    }
+    None
+}
+
+/// "~/src/web" + "feature/PROJ-1-login" -> "~/src/web-feature-PROJ-1-login"
+fn worktree_path(repo: &Path, branch: &str) -> AuraResult<PathBuf> {
+    let name = repo
+        .file_name()
+        .ok_or_else(|| AuraError::invalid_input("Not a repository directory."))?;
+    let parent = repo
+        .parent()
+        .ok_or_else(|| AuraError::invalid_input("Not a repository directory."))?;
+    Ok(parent.join(format!(
+        "{}-{}",
+        name.to_string_lossy(),
+        branch.replace('/', "-")
+    )))
+}
+
+/// Create (or reuse) the branch and check it out. Runs git, so callers keep
+/// it off the async runtime.
+fn check_out(repo: &Path, branch: &str, worktree: bool) -> AuraResult<StartedWork> {
+    let exists = branch_exists(repo, branch);
+    let base = if exists {
+        None
+    } else {
+        // Best effort: branching from a stale default is better than failing
+        // because the remote is unreachable
+        let _ = git(repo, &["fetch", "--quiet", "origin"]);
+        Some(default_base(repo))
+    };
+
+    let path = if worktree {
+        match worktree_for(repo, branch) {
+            Some(path) => path,
+            None => {
+                let path = worktree_path(repo, branch)?;
+                if path.exists() {
+                    return Err(AuraError::invalid_input(format!(
+                        "{} already exists and is not a worktree of {}.",
+                        path.display(),
+                        branch
+                    )));
+                }
+                let path_arg = path.to_string_lossy();
+                match &base {
+                    Some(base) => git(
+                        repo,
+                        &[
+                            "worktree",
+                            "add",
+                            "--no-track",
+                            "-b",
+                            branch,
+                            &path_arg,
+                            base,
+                        ],
+                    )?,
+                    None => git(repo, &["worktree", "add", &path_arg, branch])?,
+                };
+                path
+            }
+        }
+    } else {
+        if crate::repos::get_current_branch(repo) != branch {
+            if crate::repos::is_repo_dirty(repo) {
+                return Err(AuraError::invalid_input(
+                    "The repository has uncommitted changes. Commit or stash them, or start work in a new worktree.",
+                ));
+            }
+            match &base {
+                Some(base) => git(
+                    repo,
+                    &["checkout", "--quiet", "--no-track", "-b", branch, base],
+                )?,
+                None => git(repo, &["checkout", "--quiet", branch])?,
+            };
+        }
+        repo.to_path_buf()
+    };
+
+    Ok(StartedWork {
+        branch: branch.to_string(),
+        base,
+        path: path.to_string_lossy().to_string(),
+        worktree,
+    })
+}
+
+// -- Tauri commands --
+
+#[tauri::command]
+pub async fn get_branch_template(app: AppHandle) -> AuraResult<String> {
+    let pool = crate::db::pool(&app).await?;
+    branch_template(&pool).await
+}
+
+#[tauri::command]
+pub async fn set_branch_template(app: AppHandle, template: String) -> AuraResult<()> {
+    let template = template.trim();
+    validate_template(template)?;
+    let pool = crate::db::pool(&app).await?;
+    crate::db::set_setting(&pool, BRANCH_TEMPLATE_KEY, template).await
+}
+
+/// Create a branch for a cached work item from the repo's default branch,
+/// optionally in a new worktree next to the repo, and link the two. Starting
+/// again on the same item reuses the branch and worktree.
+#[tauri::command]
+pub async fn start_work_on_item(
+    app: AppHandle,
+    work_item_id: String,
+    repo_path: String,
+    options: Option<StartWorkOptions>,
+) -> AuraResult<StartedWork> {
+    let options = options.unwrap_or_default();
+    let pool = crate::db::pool(&app).await?;
+    let item = crate::sync::cached_work_item(&pool, &work_item_id).await?;
+
+    let branch = match options.branch.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => sanitize_branch(name),
+        _ => branch_name(&branch_template(&pool).await?, &item),
+    };
+    if branch.is_empty() {
+        return Err(AuraError::invalid_input(
+            "The branch name is empty after removing invalid characters.",
+        ));
+    }
+
+    let repo = PathBuf::from(&repo_path);
+    if !repo.join(".git").exists() {
+        return Err(AuraError::invalid_input(format!(
+            "{} is not a Git repository.",
+            repo_path
+        )));
+    }
+    let worktree = options.worktree;
+    let started = {
+        let branch = branch.clone();
+        tauri::async_runtime::spawn_blocking(move || check_out(&repo, &branch, worktree))
+            .await
+            .map_err(AuraError::io)??
+    };
+
+    crate::links::insert_manual(&pool, &item.id, LinkKind::Branch, &repo_path, &branch).await?;
+    crate::links::insert_manual(&pool, &item.id, LinkKind::Repo, &repo_path, "").await?;
+
+    Ok(started)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::WorkItemKind;
+
+    #[test]
+    fn test_branch_name_from_template() {
+        let mut jira = WorkItem::new(
+            "c1",
+            ProviderKind::Jira,
+            WorkItemKind::Issue,
+            "PROJ-123".into(),
+            "Login fails: retry on 502 / timeout!".into(),
+            String::new(),
+        );
+        jira.item_type = Some("User Story".into());
+        assert_eq!(
+            branch_name(DEFAULT_BRANCH_TEMPLATE, &jira),
+            "user-story/PROJ-123-login-fails-retry-on-502-timeout"
+        );
+
+        let mut github = WorkItem::new(
+            "c2",
+            ProviderKind::Github,
+            WorkItemKind::Issue,
+            "acme/web#45".into(),
+            "Crash..on start".into(),
+            String::new(),
+        )
+        .with_extra("number", 45);
+        github.labels = vec!["Bug".into()];
+        assert_eq!(
+            branch_name("{type}/{key}-{slug}", &github),
+            "bug/45-crash-on-start"
+        );
+        assert_eq!(branch_name("me/{provider}-{key}", &github), "me/github-45");
+    }
+
+    #[test]
+    fn test_validate_template() {
+        assert!(validate_template(DEFAULT_BRANCH_TEMPLATE).is_ok());
+        assert!(validate_template("{type}/{title}").is_err());
+        assert!(validate_template("{type}/wip").is_err());
+        assert!(validate_template("{key").is_err());
+    }
+
+    #[test]
+    fn test_sanitize_branch() {
+        assert_eq!(sanitize_branch("feature/ a b.lock"), "feature/a-b");
+        assert_eq!(sanitize_branch("//.hidden//x~^:"), "hidden/x");
+    }
+
+    /// Repo with one commit on `main`, in a scratch directory of its own so
+    /// worktrees land next to it
+    fn scratch_repo() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aura-workflow-{}", crate::db::new_id()));
+        let repo = dir.join("web");
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::write(repo.join("README.md"), "web\n").unwrap();
+        git(&repo, &["init", "--quiet", "-b", "main"]).unwrap();
+        git(&repo, &["add", "README.md"]).unwrap();
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=Aura",
+                "-c",
+                "user.email=aura@example.com",
+                "commit",
+                "--quiet",
+                "-m",
+                "Initial commit",
+            ],
+        )
+        .unwrap();
+        repo
+    }
+
+    #[test]
+    fn test_check_out_creates_and_reuses_branch() {
+        let repo = scratch_repo();
+
+        let started = check_out(&repo, "feature/PROJ-1-login", false).unwrap();
+        assert_eq!(started.base.as_deref(), Some("main"));
+        assert_eq!(started.path, repo.to_string_lossy());
+        assert!(!started.worktree);
+        assert_eq!(
+            crate::repos::get_current_branch(&repo),
+            "feature/PROJ-1-login"
+        );
+
+        git(&repo, &["checkout", "--quiet", "main"]).unwrap();
+        let started = check_out(&repo, "feature/PROJ-1-login", false).unwrap();
+        assert_eq!(started.base, None);
+        assert_eq!(
+            crate::repos::get_current_branch(&repo),
+            "feature/PROJ-1-login"
+        );
+
+        // Switching branches must not carry uncommitted changes along
+        std::fs::write(repo.join("README.md"), "changed\n").unwrap();
+        let err = check_out(&repo, "feature/PROJ-2-logout", false).unwrap_err();
+        assert!(matches!(err, AuraError::InvalidInput { .. }));
+        assert!(!branch_exists(&repo, "feature/PROJ-2-logout"));
+
+        std::fs::remove_dir_all(repo.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_check_out_in_worktree() {
+        let repo = scratch_repo();
+        let expected = repo.parent().unwrap().join("web-feature-PROJ-1-login");
+
+        // A dirty main checkout doesn't matter when working in a worktree
+        std::fs::write(repo.join("README.md"), "changed\n").unwrap();
+        let started = check_out(&repo, "feature/PROJ-1-login", true).unwrap();
+        assert_eq!(started.base.as_deref(), Some("main"));
+        assert_eq!(started.path, expected.to_string_lossy());
+        assert!(started.worktree);
+        assert_eq!(
+            crate::repos::get_current_branch(&expected),
+            "feature/PROJ-1-login"
+        );
+        assert_eq!(crate::repos::get_current_branch(&repo), "main");
+
+        // Starting again reuses the worktree
+        let again = check_out(&repo, "feature/PROJ-1-login", true).unwrap();
+        assert_eq!(again.base, None);
+        assert_eq!(
+            PathBuf::from(&again.path).canonicalize().unwrap(),
+            expected.canonicalize().unwrap()
+        );
+
+        // An unrelated directory in the way is not taken over
+        let blocked = repo.parent().unwrap().join("web-feature-PROJ-2-logout");
+        std::fs::create_dir_all(&blocked).unwrap();
+        let err = check_out(&repo, "feature/PROJ-2-logout", true).unwrap_err();
+        assert!(matches!(err, AuraError::InvalidInput { .. }));
+
+        std::fs::remove_dir_all(repo.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+import { useState } from "react";
+import { createPortal } from "react-dom";
+import { invoke } from "@tauri-apps/api/core";
+import type { LocalRepo } from "../hooks/useRepos";
+import { startWorkOnItem } from "../hooks/useWorkflow";
+import { Spinner } from "./Spinner";
+import { errorMessage } from "../lib/errors";
+import type { SessionTool, StartedWork } from "../types";
+
+/**
+ * Branch button for an issue row. The menu is portalled out of the row's
+ * link so its inputs don't trigger navigation.
+ */
+export function StartWorkMenu({ workItemId }: { workItemId: string }) {
+  const [anchor, setAnchor] = useState<DOMRect | null>(null);
+  const [repos, setRepos] = useState<LocalRepo[]>([]);
+  const [tools, setTools] = useState<SessionTool[]>([]);
+  const [repoPath, setRepoPath] = useState("");
+  const [worktree, setWorktree] = useState(false);
+  const [busy, setBusy] = useState(false);
+  const [started, setStarted] = useState<StartedWork | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const open = async (e: React.MouseEvent<HTMLButtonElement>) => {
+    e.preventDefault();
+    e.stopPropagation();
+    setAnchor(e.currentTarget.getBoundingClientRect());
+    setStarted(null);
+    setError(null);
+    invoke<SessionTool[]>("detect_session_tools").then(setTools).catch(() => {});
+    try {
+      const found = await invoke<LocalRepo[]>("scan_repos");
+      setRepos(found);
+      setRepoPath((current) => current || found[0]?.path || "");
+    } catch (err) {
+      setError(errorMessage(err));
+    }
+  };
+
+  const start = async () => {
+    if (!repoPath) return;
+    setBusy(true);
+    setError(null);
+    try {
+      setStarted(await startWorkOnItem(workItemId, repoPath, { worktree }));
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  const launch = async (toolId: string) => {
+    if (!started) return;
+    try {
+      const tool = tools.find((t) => t.id === toolId);
+      // AI tools start with the ticket's text as their prompt
+      if (tool?.context) {
+        await invoke("launch_session_for_item", { toolId, repoPath: started.path, workItemId });
+      } else {
+        await invoke("launch_session", { toolId, repoPath: started.path, workItemId });
+      }
+      setAnchor(null);
+    } catch (err) {
+      setError(errorMessage(err));
+    }
+  };
+
+  return (
+    <>
+      <button
+        onClick={open}
+        title="Start work"
+        className="flex items-center justify-center w-6 h-6 rounded-md text-text-tertiary hover:text-text-primary hover:bg-accent-muted opacity-0 group-hover:opacity-100 transition-all"
+      >
+        <svg viewBox="0 0 16 16" fill="none" stroke="currentColor" strokeWidth="1.5" className="w-3 h-3">
+          <path d="M5 3v10M11 3v6" />
+          <circle cx="5" cy="13" r="1.5" />
+          <circle cx="11" cy="3" r="1.5" />
+          <path d="M11 9c0 2.2-1.8 4-4 4" />
+        </svg>
+      </button>
+
+      {anchor &&
+        createPortal(
+          <>
+            <div className="fixed inset-0 z-40" onClick={() => setAnchor(null)} />
+            <div
+              className="fixed z-50 w-72 bg-raised border border-border rounded-lg p-3 shadow-lg space-y-2.5"
+              style={{ top: anchor.bottom + 4, left: Math.max(8, anchor.right - 288) }}
+              onKeyDown={(e) => e.key === "Escape" && setAnchor(null)}
+            >
+              {started ? (
+                <>
+                  <div>
+                    <p className="text-xs text-text-primary font-mono truncate">{started.branch}</p>
+                    <p className="text-[11px] text-text-tertiary font-mono truncate" title={started.path}>
+                      {started.path}
+                    </p>
+                  </div>
+                  <div className="flex flex-wrap gap-1.5">
+                    {tools
+                      .filter((t) => t.available)
+                      .map((tool) => (
+                        <button
+                          key={tool.id}
+                          onClick={() => launch(tool.id)}
+                          className="px-2 py-1 text-[11px] bg-hover text-text-secondary rounded-md hover:bg-accent-muted hover:text-text-primary transition-colors"
+                        >
+                          {tool.name}
+                        </button>
+                      ))}
+                  </div>
+                </>
+              ) : (
+                <>
+                  <select
+                    value={repoPath}
+                    onChange={(e) => setRepoPath(e.target.value)}
+                    className="w-full px-2.5 py-1.5 bg-base border border-border rounded-md text-xs text-text-primary focus:outline-none focus:border-accent"
+                  >
+                    {repos.length === 0 && <option value="">No repositories found</option>}
+                    {repos.map((repo) => (
+                      <option key={repo.path} value={repo.path}>
+                        {repo.name}
+                      </option>
+                    ))}
+                  </select>
+                  <label className="flex items-center gap-2 text-xs text-text-secondary">
+                    <input type="checkbox" checked={worktree} onChange={(e) => setWorktree(e.target.checked)} />
+                    In a new worktree
+                  </label>
+                  <button
+                    onClick={start}
+                    disabled={busy || !repoPath}
+                    className="flex items-center justify-center gap-2 w-full px-3 py-1.5 bg-accent text-white text-xs font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50"
+                  >
+                    {busy && <Spinner className="text-white" />}
+                    Create branch
+                  </button>
+                </>
+              )}
+              {error && <p className="text-status-red text-xs">{error}</p>}
+            </div>
+          </>,
+          document.body,
+        )}
+    </>
+  );
+}
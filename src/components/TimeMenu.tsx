@@ -0,0 +1,186 @@
+import { useState } from "react";
+import { createPortal } from "react-dom";
+import { adjustTrackedTime, exportWorklog, formatSeconds, settleWorklogExport } from "../hooks/useTimeTracking";
+import { Spinner } from "./Spinner";
+import { errorMessage } from "../lib/errors";
+import type { TrackedTime } from "../types";
+
+/**
+ * Time chip for an issue row: session time with a manual adjust and a
+ * worklog export. Portalled like StartWorkMenu so its inputs stay clickable.
+ */
+export function TimeMenu({
+  workItemId,
+  tracked,
+  onChange,
+}: {
+  workItemId: string;
+  tracked?: TrackedTime;
+  onChange: () => void;
+}) {
+  const [anchor, setAnchor] = useState<DOMRect | null>(null);
+  const [minutes, setMinutes] = useState("");
+  const [note, setNote] = useState("");
+  const [busy, setBusy] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const total = tracked ? tracked.session_seconds + tracked.adjustment_seconds : 0;
+
+  const open = (e: React.MouseEvent<HTMLButtonElement>) => {
+    e.preventDefault();
+    e.stopPropagation();
+    setAnchor(e.currentTarget.getBoundingClientRect());
+    setMessage(null);
+    setError(null);
+  };
+
+  const run = async (action: () => Promise<void>) => {
+    setBusy(true);
+    setMessage(null);
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(errorMessage(err));
+    } finally {
+      setBusy(false);
+      // Also after a failure: a cut-off export is now awaiting settlement
+      onChange();
+    }
+  };
+
+  const adjust = () =>
+    run(async () => {
+      const value = Number(minutes);
+      if (!Number.isFinite(value) || value === 0) throw new Error("Enter minutes to add, or negative to take off.");
+      await adjustTrackedTime(workItemId, Math.round(value * 60), note || undefined);
+      setMinutes("");
+      setNote("");
+    });
+
+  const book = () =>
+    run(async () => {
+      const result = await exportWorklog(workItemId);
+      setMessage(
+        result.booked_seconds > 0 ? `Booked ${formatSeconds(result.booked_seconds)}` : "Nothing left to book",
+      );
+    });
+
+  const settle = (booked: boolean) =>
+    run(async () => {
+      await settleWorklogExport(workItemId, booked);
+    });
+
+  const unconfirmed = tracked?.unconfirmed_seconds ?? 0;
+
+  return (
+    <>
+      <button
+        onClick={open}
+        title="Time tracked"
+        className={`flex items-center justify-center gap-1 h-6 px-1.5 rounded-md text-[11px] font-mono hover:text-text-primary hover:bg-accent-muted transition-all ${
+          total > 0 || tracked?.running
+            ? "text-text-secondary"
+            : "w-6 text-text-tertiary opacity-0 group-hover:opacity-100"
+        }`}
+      >
+        {tracked?.running && <span className="w-1.5 h-1.5 rounded-full bg-status-green" />}
+        {total > 0 ? (
+          formatSeconds(total)
+        ) : (
+          <svg viewBox="0 0 16 16" fill="none" stroke="currentColor" strokeWidth="1.5" className="w-3 h-3">
+            <circle cx="8" cy="8" r="6" />
+            <path d="M8 5v3l2 1.5" />
+          </svg>
+        )}
+      </button>
+
+      {anchor &&
+        createPortal(
+          <>
+            <div className="fixed inset-0 z-40" onClick={() => setAnchor(null)} />
+            <div
+              className="fixed z-50 w-64 bg-raised border border-border rounded-lg p-3 shadow-lg space-y-2.5"
+              style={{ top: anchor.bottom + 4, left: Math.max(8, anchor.right - 256) }}
+              onKeyDown={(e) => e.key === "Escape" && setAnchor(null)}
+            >
+              <div className="grid grid-cols-3 gap-2 text-center">
+                {[
+                  ["Tracked", total],
+                  ["Booked", tracked?.exported_seconds ?? 0],
+                  ["To book", tracked?.pending_seconds ?? 0],
+                ].map(([label, seconds]) => (
+                  <div key={label}>
+                    <p className="text-xs text-text-primary font-mono">{formatSeconds(seconds as number)}</p>
+                    <p className="text-[10px] text-text-tertiary">{label}</p>
+                  </div>
+                ))}
+              </div>
+              <div className="flex gap-1.5">
+                <input
+                  type="number"
+                  value={minutes}
+                  onChange={(e) => setMinutes(e.target.value)}
+                  placeholder="± min"
+                  className="w-20 px-2 py-1 bg-base border border-border rounded-md text-xs text-text-primary focus:outline-none focus:border-accent"
+                />
+                <input
+                  type="text"
+                  value={note}
+                  onChange={(e) => setNote(e.target.value)}
+                  onKeyDown={(e) => e.key === "Enter" && adjust()}
+                  placeholder="Note"
+                  className="flex-1 min-w-0 px-2 py-1 bg-base border border-border rounded-md text-xs text-text-primary focus:outline-none focus:border-accent"
+                />
+                <button
+                  onClick={adjust}
+                  disabled={busy || !minutes}
+                  className="px-2 py-1 text-[11px] bg-hover text-text-secondary rounded-md hover:bg-accent-muted hover:text-text-primary transition-colors disabled:opacity-50"
+                >
+                  Adjust
+                </button>
+              </div>
+              <button
+                onClick={book}
+                disabled={busy}
+                className="flex items-center justify-center gap-2 w-full px-3 py-1.5 bg-accent text-white text-xs font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50"
+              >
+                {busy && <Spinner className="text-white" />}
+                Book time with tracker
+              </button>
+              {unconfirmed > 0 && (
+                <div className="space-y-1.5">
+                  <p className="text-status-amber text-xs">
+                    An export of {formatSeconds(unconfirmed)} was cut off. Check the tracker: was it booked?
+                  </p>
+                  <div className="flex gap-1.5">
+                    {[
+                      ["Booked", true],
+                      ["Not booked", false],
+                    ].map(([label, booked]) => (
+                      <button
+                        key={label as string}
+                        onClick={() => settle(booked as boolean)}
+                        disabled={busy}
+                        className="flex-1 px-2 py-1 text-[11px] bg-hover text-text-secondary rounded-md hover:bg-accent-muted hover:text-text-primary transition-colors disabled:opacity-50"
+                      >
+                        {label}
+                      </button>
+                    ))}
+                  </div>
+                </div>
+              )}
+              {message && <p className="text-text-secondary text-xs">{message}</p>}
+              {error && <p className="text-status-red text-xs">{error}</p>}
+              <p className="text-[10px] text-text-tertiary">
+                Tools that hand off to an app that is already open (code, open -a) end their session at launch, so
+                their time isn't tracked.
+              </p>
+            </div>
+          </>,
+          document.body,
+        )}
+    </>
+  );
+}
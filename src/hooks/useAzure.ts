@@ -1,6 +1,7 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { AzureAuthStatus, AzureWorkItem, AzurePullRequest } from "../types";
+import type { AzureAuthStatus, AzureWorkItem, AzurePullRequest, AzureProject } from "../types";
+import { useConnectionFetch } from "./useConnections";
 
 export function useAzureAuth() {
   const [status, setStatus] = useState<AzureAuthStatus | null>(null);
@@ -26,43 +27,27 @@ export function useAzureAuth() {
 }
 
 export function useAzureWorkItems() {
-  const [items, setItems] = useState<AzureWorkItem[]>([]);
-  const [loading, setLoading] = useState(false);
-  const [error, setError] = useState<string | null>(null);
-
-  const fetch = useCallback(async () => {
-    setLoading(true);
-    setError(null);
-    try {
-      const result = await invoke<AzureWorkItem[]>("azure_fetch_work_items");
-      setItems(result);
-    } catch (err) {
-      setError(String(err));
-    } finally {
-      setLoading(false);
-    }
-  }, []);
-
+  const { data: items, loading, error, fetch } = useConnectionFetch<AzureWorkItem>("azure_fetch_work_items");
   return { items, loading, error, fetch };
 }
 
 export function useAzurePRs() {
-  const [prs, setPrs] = useState<AzurePullRequest[]>([]);
-  const [loading, setLoading] = useState(false);
-  const [error, setError] = useState<string | null>(null);
+  const { data: prs, loading, error, fetch } = useConnectionFetch<AzurePullRequest>("azure_fetch_prs");
+  return { prs, loading, error, fetch };
+}
 
-  const fetch = useCallback(async () => {
-    setLoading(true);
-    setError(null);
-    try {
-      const result = await invoke<AzurePullRequest[]>("azure_fetch_prs");
-      setPrs(result);
-    } catch (err) {
-      setError(String(err));
-    } finally {
-      setLoading(false);
-    }
-  }, []);
+export async function listAzureProjects(connectionId: string): Promise<AzureProject[]> {
+  return invoke<AzureProject[]>("list_azure_projects", { connectionId });
+}
 
-  return { prs, loading, error, fetch };
+export async function addAzureProject(
+  connectionId: string,
+  organization: string,
+  project: string,
+): Promise<AzureProject> {
+  return invoke<AzureProject>("add_azure_project", { connectionId, organization, project });
+}
+
+export async function removeAzureProject(id: string): Promise<void> {
+  await invoke("remove_azure_project", { id });
 }
@@ -0,0 +1,101 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type {
+  AcrossProjects,
+  Connection,
+  ConnectionUpdate,
+  FromConnection,
+  NewConnection,
+  ProviderKind,
+  ProviderStatus,
+} from "../types";
+import { errorMessage } from "../lib/errors";
+
+export function useConnections() {
+  const [connections, setConnections] = useState<Connection[]>([]);
+  const [loading, setLoading] = useState(true);
+
+  const refresh = useCallback(async () => {
+    setLoading(true);
+    try {
+      const result = await invoke<Connection[]>("list_connections");
+      setConnections(result);
+    } catch {
+      setConnections([]);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  return { connections, loading, refresh };
+}
+
+/**
+ * Run a per-connection fetch command against several connections and merge
+ * the results. A failing connection does not hide the others' items, and
+ * neither does a failing project of a connection that reads from several.
+ */
+export function useConnectionFetch<T>(command: string) {
+  const [data, setData] = useState<FromConnection<T>[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const fetch = useCallback(
+    async (connections: Connection[]) => {
+      setLoading(true);
+      setError(null);
+      const results = await Promise.allSettled(
+        connections.map((c) => invoke<T[] | AcrossProjects<T>>(command, { connectionId: c.id })),
+      );
+      const merged: FromConnection<T>[] = [];
+      const errors: string[] = [];
+      results.forEach((result, i) => {
+        const connection = connections[i];
+        const prefix = connections.length > 1 ? `${connection.label}: ` : "";
+        if (result.status === "fulfilled") {
+          const value = result.value;
+          const items = Array.isArray(value) ? value : value.items;
+          merged.push(...items.map((d) => ({ ...d, connection_id: connection.id })));
+          if (!Array.isArray(value)) {
+            for (const failure of value.failures) {
+              errors.push(`${prefix}${failure.organization}/${failure.project}: ${errorMessage(failure.error)}`);
+            }
+          }
+        } else {
+          errors.push(`${prefix}${errorMessage(result.reason)}`);
+        }
+      });
+      setData(merged);
+      setError(errors.length > 0 ? errors.join("; ") : null);
+      setLoading(false);
+    },
+    [command],
+  );
+
+  return { data, loading, error, fetch };
+}
+
+export async function createConnection(connection: NewConnection): Promise<Connection> {
+  return invoke<Connection>("create_connection", { connection });
+}
+
+export async function updateConnection(id: string, update: ConnectionUpdate): Promise<Connection> {
+  return invoke<Connection>("update_connection", { id, update });
+}
+
+export async function deleteConnection(id: string): Promise<void> {
+  await invoke("delete_connection", { id });
+}
+
+export async function testConnection(id: string): Promise<ProviderStatus> {
+  return invoke<ProviderStatus>("test_connection", { id });
+}
+
+/** Connections of one provider that have the credentials they need saved */
+export function connectionsFor(connections: Connection[], provider: ProviderKind): Connection[] {
+  return connections.filter((c) => c.provider === provider && (c.auth_method === "cli" || c.has_secret));
+}
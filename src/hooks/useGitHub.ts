@@ -1,6 +1,7 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { AuthStatus, GitHubIssue } from "../types";
+import type { AuthStatus, GitHubIssue, GitHubUser } from "../types";
+import { useConnectionFetch } from "./useConnections";
 
 export function useGitHubAuth() {
   const [status, setStatus] = useState<AuthStatus | null>(null);
@@ -26,43 +27,19 @@ export function useGitHubAuth() {
 }
 
 export function useGitHubIssues() {
-  const [issues, setIssues] = useState<GitHubIssue[]>([]);
-  const [loading, setLoading] = useState(false);
-  const [error, setError] = useState<string | null>(null);
-
-  const fetch = useCallback(async () => {
-    setLoading(true);
-    setError(null);
-    try {
-      const result = await invoke<GitHubIssue[]>("github_fetch_issues");
-      setIssues(result);
-    } catch (err) {
-      setError(String(err));
-    } finally {
-      setLoading(false);
-    }
-  }, []);
-
+  const { data: issues, loading, error, fetch } = useConnectionFetch<GitHubIssue>("github_fetch_issues");
   return { issues, loading, error, fetch };
 }
 
 export function useGitHubPRs() {
-  const [prs, setPrs] = useState<GitHubIssue[]>([]);
-  const [loading, setLoading] = useState(false);
-  const [error, setError] = useState<string | null>(null);
+  const { data: prs, loading, error, fetch } = useConnectionFetch<GitHubIssue>("github_fetch_prs");
+  return { prs, loading, error, fetch };
+}
 
-  const fetch = useCallback(async () => {
-    setLoading(true);
-    setError(null);
-    try {
-      const result = await invoke<GitHubIssue[]>("github_fetch_prs");
-      setPrs(result);
-    } catch (err) {
-      setError(String(err));
-    } finally {
-      setLoading(false);
-    }
-  }, []);
+export async function saveGitHubPat(token: string): Promise<GitHubUser> {
+  return invoke<GitHubUser>("save_github_pat", { token });
+}
 
-  return { prs, loading, error, fetch };
+export async function deleteGitHubPat(): Promise<void> {
+  await invoke("delete_github_pat");
 }
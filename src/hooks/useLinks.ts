@@ -0,0 +1,38 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { Link, NewLink } from "../types";
+
+export function useLinks() {
+  const [links, setLinks] = useState<Link[]>([]);
+
+  const refresh = useCallback(async () => {
+    try {
+      setLinks(await invoke<Link[]>("list_links"));
+    } catch {
+      setLinks([]);
+    }
+  }, []);
+
+  /** Re-detect links from branch names in the given repos and cached PR titles */
+  const autoLink = useCallback(async (repoPaths?: string[]) => {
+    try {
+      setLinks(await invoke<Link[]>("auto_link", { repoPaths }));
+    } catch {
+      // Keep the previous links; detection is best-effort
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  return { links, refresh, autoLink };
+}
+
+export async function createLink(link: NewLink): Promise<Link> {
+  return invoke<Link>("create_link", { link });
+}
+
+export async function deleteLink(id: string): Promise<void> {
+  await invoke("delete_link", { id });
+}
@@ -1,33 +1,87 @@
-import { useState, useCallback } from "react";
+import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { errorMessage } from "../lib/errors";
+
+export type RepoOperation = "rebase" | "merge" | "cherry_pick" | "revert" | "bisect";
 
 export interface LocalRepo {
   name: string;
   path: string;
+  /** Short commit id when HEAD is detached */
   current_branch: string;
   is_dirty: boolean;
+  /** Tracking branch, e.g. `origin/main` */
+  upstream: string | null;
+  ahead: number;
+  behind: number;
+  stashes: number;
+  staged: number;
+  modified: number;
+  untracked: number;
+  conflicted: number;
+  detached: boolean;
+  /** A rebase, merge, ... stopped halfway */
+  operation: RepoOperation | null;
+}
+
+/** Payload of the `repo-scan-progress` event */
+interface RepoScanProgress {
+  repo: LocalRepo;
+  done: number;
+  total: number;
+  /** False when the indexed status was still current */
+  refreshed: boolean;
+}
+
+function upsertRepo(repos: LocalRepo[], repo: LocalRepo): LocalRepo[] {
+  const next = repos.filter((r) => r.path !== repo.path);
+  next.push(repo);
+  return next.sort((a, b) => a.name.toLowerCase().localeCompare(b.name.toLowerCase()));
 }
 
 export function useRepos() {
   const [repos, setRepos] = useState<LocalRepo[]>([]);
   const [loading, setLoading] = useState(false);
+  const [progress, setProgress] = useState<{ done: number; total: number } | null>(null);
   const [error, setError] = useState<string | null>(null);
 
-  const scan = useCallback(async (roots: string[]) => {
+  // Show the last scan's index straight away; the scan then updates it
+  useEffect(() => {
+    invoke<LocalRepo[]>("list_repos")
+      .then((indexed) => setRepos((current) => (current.length === 0 ? indexed : current)))
+      .catch(() => {});
+  }, []);
+
+  useEffect(() => {
+    const unlisten = listen<RepoScanProgress>("repo-scan-progress", ({ payload }) => {
+      setProgress({ done: payload.done, total: payload.total });
+      if (payload.refreshed) setRepos((current) => upsertRepo(current, payload.repo));
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  /**
+   * Rescan the configured scan roots; `force` asks git about every repo
+   * instead of only changed ones
+   */
+  const scan = useCallback(async (force = false): Promise<LocalRepo[]> => {
     setLoading(true);
     setError(null);
     try {
-      const result = await invoke<LocalRepo[]>("scan_repos", {
-        roots,
-        maxDepth: 4,
-      });
+      const result = await invoke<LocalRepo[]>("scan_repos", { force });
       setRepos(result);
+      return result;
     } catch (err) {
-      setError(String(err));
+      setError(errorMessage(err));
+      return [];
     } finally {
       setLoading(false);
+      setProgress(null);
     }
   }, []);
 
-  return { repos, loading, error, scan };
+  return { repos, loading, progress, error, scan };
 }
@@ -0,0 +1,68 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { NewSavedQuery, SavedQuery, SavedQueryUpdate, WorkItem, WorkItemList } from "../types";
+import { errorMessage } from "../lib/errors";
+
+export function useSavedQueries() {
+  const [queries, setQueries] = useState<SavedQuery[]>([]);
+
+  const refresh = useCallback(async () => {
+    try {
+      setQueries(await invoke<SavedQuery[]>("list_saved_queries"));
+    } catch {
+      setQueries([]);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  return { queries, refresh };
+}
+
+/** Items matching one saved query; `null` runs nothing */
+export function useSavedQueryResults(queryId: string | null) {
+  const [items, setItems] = useState<WorkItem[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const run = useCallback(async () => {
+    if (!queryId) {
+      setItems([]);
+      return;
+    }
+    setLoading(true);
+    setError(null);
+    try {
+      const result = await invoke<WorkItemList>("run_saved_query", { id: queryId });
+      setItems(result.items);
+      if (result.errors.length > 0) {
+        setError(result.errors.map((e) => (e.source ? `${e.source}: ` : "") + errorMessage(e.error)).join("; "));
+      }
+    } catch (err) {
+      setItems([]);
+      setError(errorMessage(err));
+    } finally {
+      setLoading(false);
+    }
+  }, [queryId]);
+
+  useEffect(() => {
+    run();
+  }, [run]);
+
+  return { items, loading, error, run };
+}
+
+export async function createSavedQuery(query: NewSavedQuery): Promise<SavedQuery> {
+  return invoke<SavedQuery>("create_saved_query", { query });
+}
+
+export async function updateSavedQuery(id: string, update: SavedQueryUpdate): Promise<SavedQuery> {
+  return invoke<SavedQuery>("update_saved_query", { id, update });
+}
+
+export async function deleteSavedQuery(id: string): Promise<void> {
+  await invoke("delete_saved_query", { id });
+}
@@ -0,0 +1,20 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { NewScanRoot, ScanRoot, ScanRootUpdate } from "../types";
+
+export async function listScanRoots(): Promise<ScanRoot[]> {
+  return invoke<ScanRoot[]>("list_scan_roots");
+}
+
+/** `~` is expanded; the directory has to exist */
+export async function createScanRoot(root: NewScanRoot): Promise<ScanRoot> {
+  return invoke<ScanRoot>("create_scan_root", { root });
+}
+
+export async function updateScanRoot(id: string, update: ScanRootUpdate): Promise<ScanRoot> {
+  return invoke<ScanRoot>("update_scan_root", { id, update });
+}
+
+/** Stop scanning a directory; its repos leave the index */
+export async function deleteScanRoot(id: string): Promise<void> {
+  await invoke("delete_scan_root", { id });
+}
@@ -0,0 +1,20 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { SessionTool, SessionToolInput } from "../types";
+
+export async function detectSessionTools(): Promise<SessionTool[]> {
+  return invoke<SessionTool[]>("detect_session_tools");
+}
+
+/** Add a custom tool; a built-in's id overrides that built-in */
+export async function createSessionTool(id: string, tool: SessionToolInput): Promise<SessionTool> {
+  return invoke<SessionTool>("create_session_tool", { id, tool });
+}
+
+export async function updateSessionTool(id: string, tool: SessionToolInput): Promise<SessionTool> {
+  return invoke<SessionTool>("update_session_tool", { id, tool });
+}
+
+/** Remove a custom tool, restoring the built-in it overrode */
+export async function deleteSessionTool(id: string): Promise<void> {
+  await invoke("delete_session_tool", { id });
+}
@@ -0,0 +1,35 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { Session, SessionFilter } from "../types";
+
+/** How often to re-check running sessions, since their ends aren't pushed */
+const POLL_MS = 5000;
+
+export function useSessions(filter?: SessionFilter) {
+  const [sessions, setSessions] = useState<Session[]>([]);
+  const key = JSON.stringify(filter ?? {});
+
+  const refresh = useCallback(async () => {
+    try {
+      setSessions(await invoke<Session[]>("list_sessions", { filter: JSON.parse(key) }));
+    } catch {
+      setSessions([]);
+    }
+  }, [key]);
+
+  useEffect(() => {
+    refresh();
+    const timer = setInterval(refresh, POLL_MS);
+    return () => clearInterval(timer);
+  }, [refresh]);
+
+  return { sessions, refresh };
+}
+
+export async function focusSession(id: string): Promise<void> {
+  await invoke("focus_session", { id });
+}
+
+export async function killSession(id: string): Promise<Session> {
+  return invoke<Session>("kill_session", { id });
+}
@@ -0,0 +1,43 @@
+import { useState, useEffect, useCallback } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { TrackedTime, WorklogExport } from "../types";
+
+export function useTrackedTime() {
+  const [tracked, setTracked] = useState<TrackedTime[]>([]);
+
+  const refresh = useCallback(async () => {
+    try {
+      setTracked(await invoke<TrackedTime[]>("get_tracked_time"));
+    } catch {
+      setTracked([]);
+    }
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  return { tracked, refresh };
+}
+
+/** Add time by hand, or take it off with negative seconds */
+export async function adjustTrackedTime(workItemId: string, seconds: number, note?: string): Promise<TrackedTime> {
+  return invoke<TrackedTime>("adjust_tracked_time", { workItemId, seconds, note });
+}
+
+/** Book the time not exported yet; repeating it never books twice */
+export async function exportWorklog(workItemId: string): Promise<WorklogExport> {
+  return invoke<WorklogExport>("export_worklog", { workItemId });
+}
+
+/** Resolve exports that failed midway: booked, or to be booked again */
+export async function settleWorklogExport(workItemId: string, booked: boolean): Promise<TrackedTime> {
+  return invoke<TrackedTime>("settle_worklog_export", { workItemId, booked });
+}
+
+export function formatSeconds(seconds: number): string {
+  const sign = seconds < 0 ? "-" : "";
+  const minutes = Math.floor(Math.abs(seconds) / 60);
+  if (minutes < 60) return `${sign}${minutes}m`;
+  return `${sign}${Math.floor(minutes / 60)}h ${minutes % 60}m`;
+}
@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { errorMessage } from "../lib/errors";
 
 interface UpdateStatus {
   update_available: boolean;
@@ -25,7 +26,7 @@ export function useUpdater() {
         setPhase("idle");
       }
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
       setPhase("error");
     }
   }, []);
@@ -38,7 +39,7 @@ export function useUpdater() {
       setPhase("installing");
       await invoke("install_update");
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
       setPhase("error");
     }
   }, []);
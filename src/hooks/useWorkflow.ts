@@ -0,0 +1,18 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { StartWorkOptions, StartedWork } from "../types";
+
+export async function getBranchTemplate(): Promise<string> {
+  return invoke<string>("get_branch_template");
+}
+
+export async function setBranchTemplate(template: string): Promise<void> {
+  await invoke("set_branch_template", { template });
+}
+
+export async function startWorkOnItem(
+  workItemId: string,
+  repoPath: string,
+  options?: StartWorkOptions,
+): Promise<StartedWork> {
+  return invoke<StartedWork>("start_work_on_item", { workItemId, repoPath, options });
+}
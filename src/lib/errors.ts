@@ -0,0 +1,15 @@
+import type { AuraError } from "../types";
+
+function isAuraError(err: unknown): err is AuraError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    "kind" in err &&
+    "message" in err
+  );
+}
+
+/** Human-readable text for an error thrown by `invoke` */
+export function errorMessage(err: unknown): string {
+  return isAuraError(err) ? err.message : String(err);
+}
@@ -1,27 +1,33 @@
-import { useEffect, useRef, useMemo } from "react";
+import { useEffect, useRef, useMemo, useState } from "react";
 import { useGitHubAuth, useGitHubIssues } from "../hooks/useGitHub";
 import { useAzureAuth, useAzureWorkItems } from "../hooks/useAzure";
-import { useJiraAuth, useJiraIssues } from "../hooks/useJira";
-import { useFogBugzAuth, useFogBugzCases } from "../hooks/useFogBugz";
+import { useJiraIssues } from "../hooks/useJira";
+import { useFogBugzCases } from "../hooks/useFogBugz";
+import { useConnections, connectionsFor } from "../hooks/useConnections";
+import { useSavedQueries, useSavedQueryResults } from "../hooks/useSavedQueries";
+import { useLinks } from "../hooks/useLinks";
+import { useTrackedTime } from "../hooks/useTimeTracking";
 import { PageHeader } from "../components/PageHeader";
 import { SkeletonRows } from "../components/SkeletonRows";
 import { ProviderIcon } from "../components/ProviderIcon";
 import { StatusPill } from "../components/StatusPill";
+import { StartWorkMenu } from "../components/StartWorkMenu";
+import { TimeMenu } from "../components/TimeMenu";
 import { timeAgo } from "../lib/timeAgo";
 import { repoFromUrl } from "../types";
-import type { GitHubIssue, AzureWorkItem, JiraIssue, FogBugzCase } from "../types";
+import type { GitHubIssue, AzureWorkItem, JiraIssue, FogBugzCase, FromConnection, WorkItem, Link } from "../types";
 
 type UnifiedIssue =
-  | { provider: "github"; data: GitHubIssue; updated: number }
-  | { provider: "azure"; data: AzureWorkItem; updated: number }
-  | { provider: "jira"; data: JiraIssue; updated: number }
-  | { provider: "fogbugz"; data: FogBugzCase; updated: number };
+  | { provider: "github"; data: FromConnection<GitHubIssue>; updated: number }
+  | { provider: "azure"; data: FromConnection<AzureWorkItem>; updated: number }
+  | { provider: "jira"; data: FromConnection<JiraIssue>; updated: number }
+  | { provider: "fogbugz"; data: FromConnection<FogBugzCase>; updated: number };
 
 function toUnified(
-  ghIssues: GitHubIssue[],
-  azItems: AzureWorkItem[],
-  jiraIssues: JiraIssue[],
-  fbCases: FogBugzCase[],
+  ghIssues: FromConnection<GitHubIssue>[],
+  azItems: FromConnection<AzureWorkItem>[],
+  jiraIssues: FromConnection<JiraIssue>[],
+  fbCases: FromConnection<FogBugzCase>[],
 ): UnifiedIssue[] {
   const items: UnifiedIssue[] = [
     ...ghIssues.map((d) => ({ provider: "github" as const, data: d, updated: new Date(d.updated_at).getTime() })),
@@ -40,7 +46,8 @@ function IssueKey({ item }: { item: UnifiedIssue }) {
     case "azure":
       return (
         <span className="font-mono">
-          <span className="text-text-secondary">{item.data.work_item_type}</span> #{item.data.id}
+          <span className="text-text-secondary">{item.data.work_item_type}</span> #{item.data.id}{" "}
+          <span className="text-text-tertiary">{item.data.project}</span>
         </span>
       );
     case "jira":
@@ -109,43 +116,171 @@ function getUpdated(item: UnifiedIssue): string {
 
 function getItemKey(item: UnifiedIssue): string {
   switch (item.provider) {
-    case "github": return `gh-${item.data.id}`;
-    case "azure": return `az-${item.data.id}`;
-    case "jira": return `jira-${item.data.key}`;
-    case "fogbugz": return `fb-${item.data.id}`;
+    case "github": return `${item.data.connection_id}-${item.data.id}`;
+    case "azure": return `${item.data.connection_id}-${item.data.organization}-${item.data.id}`;
+    case "jira": return `${item.data.connection_id}-${item.data.key}`;
+    case "fogbugz": return `${item.data.connection_id}-${item.data.id}`;
   }
 }
 
+/** Unified work item id, the key links are stored under */
+function getWorkItemId(item: UnifiedIssue): string {
+  switch (item.provider) {
+    case "github": return `${item.data.connection_id}:${repoFromUrl(item.data.repository_url)}#${item.data.number}`;
+    case "azure": return `${item.data.connection_id}:${item.data.organization}/${item.data.id}`;
+    case "jira": return `${item.data.connection_id}:${item.data.key}`;
+    case "fogbugz": return `${item.data.connection_id}:${item.data.id}`;
+  }
+}
+
+/** Branches and pull requests linked to one work item */
+function LinkChips({ links }: { links: Link[] }) {
+  const branches = links.filter((l) => l.kind === "branch");
+  const prs = links.filter((l) => l.kind === "pull_request");
+  if (branches.length === 0 && prs.length === 0) return null;
+  const title = [
+    ...branches.map((l) => `${l.target} (${l.repo_path})`),
+    ...prs.map((l) => l.target_title ?? l.target),
+  ].join("\n");
+  return (
+    <span className="hidden sm:inline text-[10px] px-1.5 py-0.5 rounded-full bg-raised text-text-tertiary font-mono" title={title}>
+      {branches.length > 0 && `${branches.length} br`}
+      {branches.length > 0 && prs.length > 0 && " · "}
+      {prs.length > 0 && `${prs.length} PR`}
+    </span>
+  );
+}
+
+/** Results of a saved query, already in the unified work item shape */
+function SavedQueryResults({
+  items,
+  loading,
+  error,
+  onRetry,
+}: {
+  items: WorkItem[];
+  loading: boolean;
+  error: string | null;
+  onRetry: () => void;
+}) {
+  if (error) {
+    return (
+      <div className="flex items-center justify-between bg-status-red/10 border border-status-red/20 rounded-lg px-3 py-2 mb-3">
+        <p className="text-xs text-status-red">{error}</p>
+        <button onClick={onRetry} className="text-xs text-text-secondary hover:text-text-primary transition-colors ml-3">
+          Retry
+        </button>
+      </div>
+    );
+  }
+  if (loading && items.length === 0) return <SkeletonRows count={4} />;
+  if (items.length === 0) {
+    return (
+      <div className="flex flex-col items-center justify-center py-16 text-center">
+        <p className="text-sm text-text-secondary">Nothing matches this query.</p>
+      </div>
+    );
+  }
+  return (
+    <ul className="space-y-0.5">
+      {items.map((item, i) => (
+        <li
+          key={item.id}
+          className="animate-fade-in-up"
+          style={{ animationDelay: i < 20 ? `${i * 30}ms` : "0ms" }}
+        >
+          <a
+            href={item.url}
+            target="_blank"
+            rel="noopener noreferrer"
+            className="flex items-center gap-3 px-3 py-2.5 rounded-md hover:bg-hover transition-colors group"
+          >
+            <ProviderIcon provider={item.provider === "azure_devops" ? "azure" : item.provider} />
+            <div className="min-w-0 flex-1">
+              <p className="text-[13px] font-medium text-text-primary group-hover:text-white truncate">{item.title}</p>
+              <p className="text-xs text-text-secondary mt-0.5 font-mono">
+                {item.item_type && <span className="text-text-secondary">{item.item_type} </span>}
+                {item.provider_id} <span className="text-text-tertiary">{item.project}</span>
+              </p>
+            </div>
+            <div className="flex items-center gap-2 shrink-0">
+              {item.kind === "issue" && <StartWorkMenu workItemId={item.id} />}
+              {item.labels.slice(0, 2).map((label) => (
+                <span
+                  key={label}
+                  className="hidden sm:inline text-[10px] px-1.5 py-0.5 rounded-full bg-raised text-text-secondary"
+                >
+                  {label}
+                </span>
+              ))}
+              <StatusPill status={item.status} />
+              <span className="text-[11px] font-mono text-text-tertiary w-6 text-right">{timeAgo(item.updated_at)}</span>
+            </div>
+          </a>
+        </li>
+      ))}
+    </ul>
+  );
+}
+
 export function IssuesPage({ active }: { active: boolean }) {
+  const { connections, loading: connLoading } = useConnections();
+
   const { status: ghStatus, loading: ghAuthLoading } = useGitHubAuth();
   const { issues, loading: ghLoading, error: ghError, fetch: ghFetch } = useGitHubIssues();
 
   const { status: azStatus, loading: azAuthLoading } = useAzureAuth();
   const { items: azItems, loading: azLoading, error: azError, fetch: azFetch } = useAzureWorkItems();
 
-  const { config: jiraConfig, status: jiraStatus, loading: jiraAuthLoading } = useJiraAuth();
   const { issues: jiraIssues, loading: jiraLoading, error: jiraError, fetch: jiraFetch } = useJiraIssues();
-
-  const { config: fbConfig, status: fbStatus, loading: fbAuthLoading } = useFogBugzAuth();
   const { cases: fbCases, loading: fbLoading, error: fbError, fetch: fbFetch } = useFogBugzCases();
 
+  const { queries, refresh: queriesRefresh } = useSavedQueries();
+  const [view, setView] = useState<string | null>(null);
+  const saved = useSavedQueryResults(view);
+
   const mounted = useRef(false);
 
+  // CLI connections need the CLI signed in; the others need a saved secret
+  const ghConnections = useMemo(
+    () =>
+      connectionsFor(connections, "github").filter(
+        // Enterprise hosts have their own `gh` login, checked on fetch
+        (c) =>
+          c.auth_method !== "cli" ||
+          (c.config.host ? ghStatus?.cli_available : ghStatus && ghStatus.auth_method !== "none"),
+      ),
+    [connections, ghStatus],
+  );
+  const azConnections = useMemo(
+    () => (azStatus?.logged_in ? connectionsFor(connections, "azure_devops") : []),
+    [connections, azStatus],
+  );
+  const jiraConnections = useMemo(() => connectionsFor(connections, "jira"), [connections]);
+  const fbConnections = useMemo(() => connectionsFor(connections, "fogbugz"), [connections]);
+
+  const fetchAll = () => {
+    if (ghConnections.length > 0) ghFetch(ghConnections);
+    if (azConnections.length > 0) azFetch(azConnections);
+    if (jiraConnections.length > 0) jiraFetch(jiraConnections);
+    if (fbConnections.length > 0) fbFetch(fbConnections);
+  };
+
   useEffect(() => {
-    if (ghStatus?.auth_method !== "none") ghFetch();
-  }, [ghStatus, ghFetch]);
+    if (ghConnections.length > 0) ghFetch(ghConnections);
+  }, [ghConnections, ghFetch]);
 
   useEffect(() => {
-    if (azStatus?.logged_in && azStatus.organization && azStatus.project) azFetch();
-  }, [azStatus, azFetch]);
+    if (azConnections.length > 0) azFetch(azConnections);
+  }, [azConnections, azFetch]);
 
   useEffect(() => {
-    if (jiraStatus?.valid && jiraConfig) jiraFetch(jiraConfig);
-  }, [jiraStatus, jiraConfig, jiraFetch]);
+    if (jiraConnections.length > 0) jiraFetch(jiraConnections);
+  }, [jiraConnections, jiraFetch]);
 
   useEffect(() => {
-    if (fbStatus?.valid && fbConfig) fbFetch(fbConfig);
-  }, [fbStatus, fbConfig, fbFetch]);
+    if (fbConnections.length > 0) fbFetch(fbConnections);
+  }, [fbConnections, fbFetch]);
 
   useEffect(() => {
     if (!mounted.current) {
@@ -153,20 +288,24 @@ export function IssuesPage({ active }: { active: boolean }) {
       return;
     }
     if (active) {
-      if (ghStatus?.auth_method !== "none") ghFetch();
-      if (azStatus?.logged_in && azStatus.organization && azStatus.project) azFetch();
-      if (jiraStatus?.valid && jiraConfig) jiraFetch(jiraConfig);
-      if (fbStatus?.valid && fbConfig) fbFetch(fbConfig);
+      queriesRefresh();
+      if (view) saved.run();
+      else fetchAll();
     }
   }, [active]);
 
-  const ghConnected = ghStatus && ghStatus.auth_method !== "none";
-  const azConnected = azStatus?.logged_in && azStatus.organization && azStatus.project;
-  const jiraConnected = jiraStatus?.valid && jiraConfig;
-  const fbConnected = fbStatus?.valid && fbConfig;
-  const allAuthLoading = ghAuthLoading && azAuthLoading && jiraAuthLoading && fbAuthLoading;
+  useEffect(() => {
+    // The selected query may have been deleted in Settings
+    if (view && !queries.some((q) => q.id === view)) setView(null);
+  }, [queries, view]);
+
+  const ghConnected = ghConnections.length > 0;
+  const azConnected = azConnections.length > 0;
+  const jiraConnected = jiraConnections.length > 0;
+  const fbConnected = fbConnections.length > 0;
+  const allAuthLoading = connLoading || (ghAuthLoading && azAuthLoading);
   const anyLoading = ghLoading || azLoading || jiraLoading || fbLoading;
-  const neitherConnected = !ghAuthLoading && !azAuthLoading && !jiraAuthLoading && !fbAuthLoading &&
+  const neitherConnected = !connLoading && !ghAuthLoading && !azAuthLoading &&
     !ghConnected && !azConnected && !jiraConnected && !fbConnected;
 
   const unified = useMemo(
@@ -174,109 +313,143 @@ export function IssuesPage({ active }: { active: boolean }) {
     [issues, azItems, jiraIssues, fbCases],
   );
 
+  const { links } = useLinks();
+  const linksByItem = useMemo(() => {
+    const map = new Map<string, Link[]>();
+    for (const link of links) {
+      map.set(link.work_item_id, [...(map.get(link.work_item_id) ?? []), link]);
+    }
+    return map;
+  }, [links]);
+
+  const { tracked, refresh: refreshTracked } = useTrackedTime();
+  const trackedByItem = useMemo(() => new Map(tracked.map((t) => [t.work_item_id, t])), [tracked]);
+
   const hasData = unified.length > 0;
   const isInitialLoad = allAuthLoading && !hasData;
   const isLoading = anyLoading && !hasData;
 
   const errors: { provider: string; message: string; retry: () => void }[] = [];
-  if (ghError && ghConnected) errors.push({ provider: "GitHub", message: ghError, retry: ghFetch });
-  if (azError && azConnected) errors.push({ provider: "Azure DevOps", message: azError, retry: azFetch });
-  if (jiraError && jiraConnected) errors.push({ provider: "Jira", message: jiraError, retry: () => jiraFetch(jiraConfig) });
-  if (fbError && fbConnected) errors.push({ provider: "FogBugz", message: fbError, retry: () => fbFetch(fbConfig) });
-
-  const handleRefresh = () => {
-    if (ghConnected) ghFetch();
-    if (azConnected) azFetch();
-    if (jiraConnected) jiraFetch(jiraConfig);
-    if (fbConnected) fbFetch(fbConfig);
-  };
+  if (ghError && ghConnected) errors.push({ provider: "GitHub", message: ghError, retry: () => ghFetch(ghConnections) });
+  if (azError && azConnected) errors.push({ provider: "Azure DevOps", message: azError, retry: () => azFetch(azConnections) });
+  if (jiraError && jiraConnected) errors.push({ provider: "Jira", message: jiraError, retry: () => jiraFetch(jiraConnections) });
+  if (fbError && fbConnected) errors.push({ provider: "FogBugz", message: fbError, retry: () => fbFetch(fbConnections) });
 
   return (
     <div>
       <PageHeader
         title="Issues"
-        loading={anyLoading && hasData}
-        count={hasData ? unified.length : undefined}
-        onRefresh={neitherConnected ? undefined : handleRefresh}
-        refreshDisabled={anyLoading}
+        loading={view ? saved.loading && saved.items.length > 0 : anyLoading && hasData}
+        count={view ? saved.items.length || undefined : hasData ? unified.length : undefined}
+        onRefresh={view ? saved.run : neitherConnected ? undefined : fetchAll}
+        refreshDisabled={view ? saved.loading : anyLoading}
       />
 
-      {errors.map((err) => (
-        <div key={err.provider} className="flex items-center justify-between bg-status-red/10 border border-status-red/20 rounded-lg px-3 py-2 mb-3">
-          <p className="text-xs text-status-red">
-            <span className="font-medium">{err.provider}</span>: {err.message}
-          </p>
-          <button onClick={err.retry} className="text-xs text-text-secondary hover:text-text-primary transition-colors ml-3">
-            Retry
-          </button>
-        </div>
-      ))}
-
-      {isInitialLoad && <SkeletonRows count={5} />}
-
-      {neitherConnected && (
-        <div className="flex flex-col items-center justify-center py-16 text-center">
-          <div className="w-10 h-10 rounded-full bg-raised flex items-center justify-center mb-3">
-            <svg viewBox="0 0 20 20" fill="none" stroke="currentColor" strokeWidth="1.5" className="w-5 h-5 text-text-tertiary">
-              <circle cx="10" cy="10" r="7" />
-              <path d="M10 7v3l2 1" />
-            </svg>
-          </div>
-          <p className="text-sm text-text-secondary mb-1">No services connected</p>
-          <p className="text-xs text-text-tertiary">Set up GitHub CLI, Azure CLI, or add credentials in Settings.</p>
+      {queries.length > 0 && (
+        <div className="flex items-center gap-1 mb-3 overflow-x-auto">
+          {[{ id: null, name: "Assigned to me" }, ...queries].map((q) => (
+            <button
+              key={q.id ?? "assigned"}
+              onClick={() => setView(q.id)}
+              className={`text-[11px] px-2 py-1 rounded-md transition-colors shrink-0 ${
+                view === q.id ? "bg-raised text-text-primary" : "text-text-tertiary hover:text-text-primary"
+              }`}
+            >
+              {q.name}
+            </button>
+          ))}
         </div>
       )}
 
-      {isLoading && !isInitialLoad && <SkeletonRows count={4} />}
-
-      {hasData && (
-        <ul className="space-y-0.5">
-          {unified.map((item, i) => (
-            <li
-              key={getItemKey(item)}
-              className="animate-fade-in-up"
-              style={{ animationDelay: i < 20 ? `${i * 30}ms` : "0ms" }}
-            >
-              <a
-                href={getUrl(item)}
-                target="_blank"
-                rel="noopener noreferrer"
-                className="flex items-center gap-3 px-3 py-2.5 rounded-md hover:bg-hover transition-colors group"
-              >
-                <ProviderIcon provider={item.provider} />
-                <div className="min-w-0 flex-1">
-                  <p className="text-[13px] font-medium text-text-primary group-hover:text-white truncate">
-                    {getTitle(item)}
-                  </p>
-                  <p className="text-xs text-text-secondary mt-0.5">
-                    <IssueKey item={item} />
-                  </p>
-                </div>
-                <div className="flex items-center gap-2 shrink-0">
-                  {getLabels(item).map((label) => (
-                    <span
-                      key={label.name}
-                      className="hidden sm:inline text-[10px] px-1.5 py-0.5 rounded-full bg-raised text-text-secondary"
-                      style={label.color ? { backgroundColor: `#${label.color}20`, color: `#${label.color}` } : undefined}
-                    >
-                      {label.name}
-                    </span>
-                  ))}
-                  <StatusPill status={getStatus(item)} />
-                  <span className="text-[11px] font-mono text-text-tertiary w-6 text-right">
-                    {timeAgo(getUpdated(item))}
-                  </span>
-                </div>
-              </a>
-            </li>
+      {view ? (
+        <SavedQueryResults items={saved.items} loading={saved.loading} error={saved.error} onRetry={saved.run} />
+      ) : (
+        <>
+          {errors.map((err) => (
+            <div key={err.provider} className="flex items-center justify-between bg-status-red/10 border border-status-red/20 rounded-lg px-3 py-2 mb-3">
+              <p className="text-xs text-status-red">
+                <span className="font-medium">{err.provider}</span>: {err.message}
+              </p>
+              <button onClick={err.retry} className="text-xs text-text-secondary hover:text-text-primary transition-colors ml-3">
+                Retry
+              </button>
+            </div>
           ))}
-        </ul>
-      )}
 
-      {!isLoading && !isInitialLoad && !neitherConnected && !hasData && errors.length === 0 && (
-        <div className="flex flex-col items-center justify-center py-16 text-center">
-          <p className="text-sm text-text-secondary">No open issues assigned to you.</p>
-        </div>
+          {isInitialLoad && <SkeletonRows count={5} />}
+
+          {neitherConnected && (
+            <div className="flex flex-col items-center justify-center py-16 text-center">
+              <div className="w-10 h-10 rounded-full bg-raised flex items-center justify-center mb-3">
+                <svg viewBox="0 0 20 20" fill="none" stroke="currentColor" strokeWidth="1.5" className="w-5 h-5 text-text-tertiary">
+                  <circle cx="10" cy="10" r="7" />
+                  <path d="M10 7v3l2 1" />
+                </svg>
+              </div>
+              <p className="text-sm text-text-secondary mb-1">No services connected</p>
+              <p className="text-xs text-text-tertiary">Set up GitHub CLI, Azure CLI, or add credentials in Settings.</p>
+            </div>
+          )}
+
+          {isLoading && !isInitialLoad && <SkeletonRows count={4} />}
+
+          {hasData && (
+            <ul className="space-y-0.5">
+              {unified.map((item, i) => (
+                <li
+                  key={getItemKey(item)}
+                  className="animate-fade-in-up"
+                  style={{ animationDelay: i < 20 ? `${i * 30}ms` : "0ms" }}
+                >
+                  <a
+                    href={getUrl(item)}
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    className="flex items-center gap-3 px-3 py-2.5 rounded-md hover:bg-hover transition-colors group"
+                  >
+                    <ProviderIcon provider={item.provider} />
+                    <div className="min-w-0 flex-1">
+                      <p className="text-[13px] font-medium text-text-primary group-hover:text-white truncate">
+                        {getTitle(item)}
+                      </p>
+                      <p className="text-xs text-text-secondary mt-0.5">
+                        <IssueKey item={item} />
+                      </p>
+                    </div>
+                    <div className="flex items-center gap-2 shrink-0">
+                      <StartWorkMenu workItemId={getWorkItemId(item)} />
+                      <TimeMenu
+                        workItemId={getWorkItemId(item)}
+                        tracked={trackedByItem.get(getWorkItemId(item))}
+                        onChange={refreshTracked}
+                      />
+                      <LinkChips links={linksByItem.get(getWorkItemId(item)) ?? []} />
+                      {getLabels(item).map((label) => (
+                        <span
+                          key={label.name}
+                          className="hidden sm:inline text-[10px] px-1.5 py-0.5 rounded-full bg-raised text-text-secondary"
+                          style={label.color ? { backgroundColor: `#${label.color}20`, color: `#${label.color}` } : undefined}
+                        >
+                          {label.name}
+                        </span>
+                      ))}
+                      <StatusPill status={getStatus(item)} />
+                      <span className="text-[11px] font-mono text-text-tertiary w-6 text-right">
+                        {timeAgo(getUpdated(item))}
+                      </span>
+                    </div>
+                  </a>
+                </li>
+              ))}
+            </ul>
+          )}
+
+          {!isLoading && !isInitialLoad && !neitherConnected && !hasData && errors.length === 0 && (
+            <div className="flex flex-col items-center justify-center py-16 text-center">
+              <p className="text-sm text-text-secondary">No open issues assigned to you.</p>
+            </div>
+          )}
+        </>
       )}
     </div>
   );
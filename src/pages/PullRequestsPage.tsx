@@ -1,18 +1,36 @@
 import { useEffect, useRef, useMemo } from "react";
 import { useGitHubAuth, useGitHubPRs } from "../hooks/useGitHub";
 import { useAzureAuth, useAzurePRs } from "../hooks/useAzure";
+import { useConnections, connectionsFor } from "../hooks/useConnections";
 import { PageHeader } from "../components/PageHeader";
 import { SkeletonRows } from "../components/SkeletonRows";
 import { ProviderIcon } from "../components/ProviderIcon";
 import { timeAgo } from "../lib/timeAgo";
 import { repoFromUrl } from "../types";
-import type { GitHubIssue, AzurePullRequest } from "../types";
+import type { GitHubIssue, AzurePullRequest, FromConnection } from "../types";
+
+const ROLE_LABELS: Record<AzurePullRequest["role"], string> = {
+  author: "Author",
+  reviewer: "Reviewer",
+  required_reviewer: "Required",
+};
+
+const VOTE_LABELS: Record<NonNullable<AzurePullRequest["vote"]>, string> = {
+  approved: "Approved",
+  approved_with_suggestions: "Approved with suggestions",
+  no_vote: "No vote",
+  waiting_for_author: "Waiting for author",
+  rejected: "Rejected",
+};
 
 type UnifiedPR =
-  | { provider: "github"; data: GitHubIssue; updated: number }
-  | { provider: "azure"; data: AzurePullRequest; updated: number };
+  | { provider: "github"; data: FromConnection<GitHubIssue>; updated: number }
+  | { provider: "azure"; data: FromConnection<AzurePullRequest>; updated: number };
 
-function toUnified(ghPrs: GitHubIssue[], azPrs: AzurePullRequest[]): UnifiedPR[] {
+function toUnified(
+  ghPrs: FromConnection<GitHubIssue>[],
+  azPrs: FromConnection<AzurePullRequest>[],
+): UnifiedPR[] {
   const items: UnifiedPR[] = [
     ...ghPrs.map((d) => ({ provider: "github" as const, data: d, updated: new Date(d.updated_at).getTime() })),
     ...azPrs.map((d) => ({ provider: "azure" as const, data: d, updated: new Date(d.creation_date).getTime() })),
@@ -22,6 +40,8 @@ function toUnified(ghPrs: GitHubIssue[], azPrs: AzurePullRequest[]): UnifiedPR[]
 }
 
 export function PullRequestsPage({ active }: { active: boolean }) {
+  const { connections, loading: connLoading } = useConnections();
+
   const { status: ghStatus, loading: ghAuthLoading } = useGitHubAuth();
   const { prs: ghPrs, loading: ghLoading, error: ghError, fetch: ghFetch } = useGitHubPRs();
 
@@ -30,42 +50,54 @@ export function PullRequestsPage({ active }: { active: boolean }) {
 
   const mounted = useRef(false);
 
+  const ghConnections = useMemo(
+    () =>
+      connectionsFor(connections, "github").filter(
+        // Enterprise hosts have their own `gh` login, checked on fetch
+        (c) =>
+          c.auth_method !== "cli" ||
+          (c.config.host ? ghStatus?.cli_available : ghStatus && ghStatus.auth_method !== "none"),
+      ),
+    [connections, ghStatus],
+  );
+  const azConnections = useMemo(
+    () => (azStatus?.logged_in ? connectionsFor(connections, "azure_devops") : []),
+    [connections, azStatus],
+  );
+
+  const fetchAll = () => {
+    if (ghConnections.length > 0) ghFetch(ghConnections);
+    if (azConnections.length > 0) azFetch(azConnections);
+  };
+
   useEffect(() => {
-    if (ghStatus?.auth_method !== "none") ghFetch();
-  }, [ghStatus, ghFetch]);
+    if (ghConnections.length > 0) ghFetch(ghConnections);
+  }, [ghConnections, ghFetch]);
 
   useEffect(() => {
-    if (azStatus?.logged_in && azStatus.organization && azStatus.project) azFetch();
-  }, [azStatus, azFetch]);
+    if (azConnections.length > 0) azFetch(azConnections);
+  }, [azConnections, azFetch]);
 
   useEffect(() => {
     if (!mounted.current) {
       mounted.current = true;
       return;
     }
-    if (active) {
-      if (ghStatus?.auth_method !== "none") ghFetch();
-      if (azStatus?.logged_in && azStatus.organization && azStatus.project) azFetch();
-    }
+    if (active) fetchAll();
   }, [active]);
 
-  const ghConnected = ghStatus && ghStatus.auth_method !== "none";
-  const azConnected = azStatus?.logged_in && azStatus.organization && azStatus.project;
+  const ghConnected = ghConnections.length > 0;
+  const azConnected = azConnections.length > 0;
   const anyLoading = ghLoading || azLoading;
-  const neitherConnected = !ghAuthLoading && !azAuthLoading && !ghConnected && !azConnected;
+  const neitherConnected = !connLoading && !ghAuthLoading && !azAuthLoading && !ghConnected && !azConnected;
 
   const unified = useMemo(() => toUnified(ghPrs, azPrs), [ghPrs, azPrs]);
   const hasData = unified.length > 0;
-  const isInitialLoad = ghAuthLoading && azAuthLoading && !hasData;
+  const isInitialLoad = (connLoading || (ghAuthLoading && azAuthLoading)) && !hasData;
 
   const errors: { provider: string; message: string; retry: () => void }[] = [];
-  if (ghError && ghConnected) errors.push({ provider: "GitHub", message: ghError, retry: ghFetch });
-  if (azError && azConnected) errors.push({ provider: "Azure DevOps", message: azError, retry: azFetch });
-
-  const handleRefresh = () => {
-    if (ghConnected) ghFetch();
-    if (azConnected) azFetch();
-  };
+  if (ghError && ghConnected) errors.push({ provider: "GitHub", message: ghError, retry: () => ghFetch(ghConnections) });
+  if (azError && azConnected) errors.push({ provider: "Azure DevOps", message: azError, retry: () => azFetch(azConnections) });
 
   return (
     <div>
@@ -73,7 +105,7 @@ export function PullRequestsPage({ active }: { active: boolean }) {
         title="Pull Requests"
         loading={anyLoading && hasData}
         count={hasData ? unified.length : undefined}
-        onRefresh={neitherConnected ? undefined : handleRefresh}
+        onRefresh={neitherConnected ? undefined : fetchAll}
         refreshDisabled={anyLoading}
       />
 
@@ -114,7 +146,11 @@ export function PullRequestsPage({ active }: { active: boolean }) {
         <ul className="space-y-0.5">
           {unified.map((item, i) => (
             <li
-              key={`${item.provider}-${item.data.id}`}
+              key={
+                item.provider === "github"
+                  ? `${item.data.connection_id}-${item.data.id}`
+                  : `${item.data.connection_id}-${item.data.organization}-${item.data.id}`
+              }
               className="animate-fade-in-up"
               style={{ animationDelay: i < 20 ? `${i * 30}ms` : "0ms" }}
             >
@@ -136,6 +172,7 @@ export function PullRequestsPage({ active }: { active: boolean }) {
                       </>
                     ) : (
                       <>
+                        <span className="text-text-tertiary">{item.data.project} / </span>
                         {item.data.repository}
                         <span className="text-text-tertiary mx-1.5">{item.data.source_branch}</span>
                         <span className="text-text-tertiary">&rarr;</span>
@@ -146,7 +183,13 @@ export function PullRequestsPage({ active }: { active: boolean }) {
                 </div>
                 <div className="flex items-center gap-2 shrink-0">
                   {item.provider === "azure" && (
-                    <span className="text-[11px] text-text-secondary">{item.data.created_by}</span>
+                    <>
+                      <span className="hidden sm:inline text-[10px] px-1.5 py-0.5 rounded-full bg-raised text-text-secondary">
+                        {ROLE_LABELS[item.data.role]}
+                        {item.data.vote && item.data.vote !== "no_vote" && ` · ${VOTE_LABELS[item.data.vote]}`}
+                      </span>
+                      <span className="text-[11px] text-text-secondary">{item.data.created_by}</span>
+                    </>
                   )}
                   <span className="text-[11px] font-mono text-text-tertiary w-6 text-right">
                     {timeAgo(item.provider === "github" ? item.data.updated_at : item.data.creation_date)}
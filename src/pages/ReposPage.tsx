@@ -1,16 +1,14 @@
 import { useEffect, useState, useRef, useMemo, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { useRepos, type LocalRepo } from "../hooks/useRepos";
+import { useRepos, type LocalRepo, type RepoOperation } from "../hooks/useRepos";
+import { useLinks } from "../hooks/useLinks";
+import { useSessions, focusSession, killSession } from "../hooks/useSessions";
+import { formatSeconds } from "../hooks/useTimeTracking";
+import { listScanRoots } from "../hooks/useScanRoots";
 import { PageHeader } from "../components/PageHeader";
 import { SkeletonRows } from "../components/SkeletonRows";
-import Database from "@tauri-apps/plugin-sql";
-
-interface SessionTool {
-  id: string;
-  name: string;
-  available: boolean;
-  category: string;
-}
+import { errorMessage } from "../lib/errors";
+import type { Link, Session, SessionTool } from "../types";
 
 interface TreeNode {
   repos: LocalRepo[];
@@ -64,8 +62,64 @@ function countRepos(node: TreeNode): number {
   return count;
 }
 
+/** Sessions launched from Aura that are still running */
+function RunningSessions({ sessions, onChange }: { sessions: Session[]; onChange: () => void }) {
+  const [error, setError] = useState<string | null>(null);
+
+  const act = async (action: () => Promise<unknown>) => {
+    setError(null);
+    try {
+      await action();
+    } catch (err) {
+      setError(errorMessage(err));
+    }
+    onChange();
+  };
+
+  return (
+    <div className="mb-4">
+      <p className="text-[11px] font-medium text-text-tertiary uppercase tracking-wide px-3 mb-1">Running sessions</p>
+      {sessions.map((session) => (
+        <div
+          key={session.id}
+          className="flex items-center justify-between px-3 py-1.5 rounded-md hover:bg-hover transition-colors group"
+        >
+          <div className="flex items-center gap-2.5 min-w-0 flex-1">
+            <span className="w-1.5 h-1.5 rounded-full bg-status-green shrink-0" />
+            <span className="text-xs text-text-primary shrink-0">{session.tool_name}</span>
+            <span className="text-xs text-text-tertiary font-mono truncate" title={session.repo_path}>
+              {session.repo_path.split("/").pop()}
+            </span>
+            {session.work_item_title && (
+              <span className="text-xs text-text-secondary truncate">{session.work_item_title}</span>
+            )}
+          </div>
+          <div className="flex items-center gap-2 shrink-0">
+            <span className="text-[11px] text-text-tertiary">{formatSeconds(session.duration_seconds)}</span>
+            <button
+              onClick={() => act(() => focusSession(session.id))}
+              className="px-2 py-0.5 text-[11px] text-text-secondary rounded hover:bg-accent-muted hover:text-text-primary opacity-0 group-hover:opacity-100 transition-all"
+            >
+              Focus
+            </button>
+            <button
+              onClick={() => act(() => killSession(session.id))}
+              className="px-2 py-0.5 text-[11px] text-text-secondary rounded hover:bg-status-red/10 hover:text-status-red opacity-0 group-hover:opacity-100 transition-all"
+            >
+              Stop
+            </button>
+          </div>
+        </div>
+      ))}
+      {error && <p className="text-status-red text-xs px-3 mt-1">{error}</p>}
+    </div>
+  );
+}
+
 export function ReposPage({ active }: { active: boolean }) {
-  const { repos, loading, error, scan } = useRepos();
+  const { repos, loading, progress, error, scan } = useRepos();
+  const { links, autoLink } = useLinks();
+  const { sessions, refresh: refreshSessions } = useSessions({ running: true });
   const [roots, setRoots] = useState<string[]>([]);
   const [tools, setTools] = useState<SessionTool[]>([]);
   const [dbError, setDbError] = useState<string | null>(null);
@@ -87,17 +141,30 @@ export function ReposPage({ active }: { active: boolean }) {
 
   const loadRoots = async () => {
     try {
-      const db = await Database.load("sqlite:aura.db");
-      const rows = await db.select<{ path: string }[]>("SELECT path FROM scan_roots");
-      const paths = rows.map((r) => r.path);
+      const paths = (await listScanRoots()).map((r) => r.path);
       setRoots(paths);
       rootsRef.current = paths;
-      if (paths.length > 0) scan(paths);
+      if (paths.length > 0) rescan();
     } catch (err) {
-      setDbError(String(err));
+      setDbError(errorMessage(err));
     }
   };
 
+  // Branch names only change between scans, so re-detect links after each one
+  const rescan = async (force = false) => {
+    const found = await scan(force);
+    if (found.length > 0) autoLink(found.map((r) => r.path));
+  };
+
+  const ticketsByRepo = useMemo(() => {
+    const map = new Map<string, Link[]>();
+    for (const link of links) {
+      if (link.kind !== "repo" || !link.repo_path) continue;
+      map.set(link.repo_path, [...(map.get(link.repo_path) ?? []), link]);
+    }
+    return map;
+  }, [links]);
+
   const tree = useMemo(() => buildTree(repos, roots), [repos, roots]);
   const [collapsed, setCollapsed] = useState<Set<string>>(new Set());
   const toggleFolder = useCallback((path: string) => {
@@ -115,9 +182,15 @@ export function ReposPage({ active }: { active: boolean }) {
         title="Repositories"
         loading={loading && repos.length > 0}
         count={repos.length > 0 ? repos.length : undefined}
-        onRefresh={roots.length > 0 ? () => scan(roots) : undefined}
+        onRefresh={roots.length > 0 ? () => rescan(true) : undefined}
         refreshDisabled={loading}
-      />
+      >
+        {progress && (
+          <span className="text-[11px] font-mono text-text-tertiary">
+            {progress.done}/{progress.total}
+          </span>
+        )}
+      </PageHeader>
 
       {dbError && (
         <div className="bg-status-red/10 border border-status-red/20 rounded-lg px-3 py-2 mb-3">
@@ -143,10 +216,12 @@ export function ReposPage({ active }: { active: boolean }) {
         </div>
       )}
 
+      {sessions.length > 0 && <RunningSessions sessions={sessions} onChange={refreshSessions} />}
+
       {loading && repos.length === 0 && roots.length > 0 && <SkeletonRows count={6} />}
 
       {repos.length > 0 && (
-        <FolderTree node={tree} tools={tools} collapsed={collapsed} onToggle={toggleFolder} />
+        <FolderTree node={tree} tools={tools} tickets={ticketsByRepo} collapsed={collapsed} onToggle={toggleFolder} />
       )}
 
       {!loading && repos.length === 0 && roots.length > 0 && !error && (
@@ -161,6 +236,7 @@ export function ReposPage({ active }: { active: boolean }) {
 function FolderTree({
   node,
   tools,
+  tickets,
   depth = 0,
   collapsed,
   onToggle,
@@ -168,6 +244,7 @@ function FolderTree({
 }: {
   node: TreeNode;
   tools: SessionTool[];
+  tickets: Map<string, Link[]>;
   depth?: number;
   collapsed: Set<string>;
   onToggle: (path: string) => void;
@@ -211,6 +288,7 @@ function FolderTree({
               <FolderTree
                 node={child}
                 tools={tools}
+                tickets={tickets}
                 depth={depth + 1}
                 collapsed={collapsed}
                 onToggle={onToggle}
@@ -221,13 +299,70 @@ function FolderTree({
         );
       })}
       {sortedRepos.map((repo) => (
-        <RepoRow key={repo.path} repo={repo} tools={tools} />
+        <RepoRow key={repo.path} repo={repo} tools={tools} tickets={tickets.get(repo.path) ?? []} />
       ))}
     </div>
   );
 }
 
-function RepoRow({ repo, tools }: { repo: LocalRepo; tools: SessionTool[] }) {
+const OPERATION_LABELS: Record<RepoOperation, string> = {
+  rebase: "Rebasing",
+  merge: "Merging",
+  cherry_pick: "Cherry-picking",
+  revert: "Reverting",
+  bisect: "Bisecting",
+};
+
+/** Counts that need attention before switching context; empty when clean and in sync */
+function RepoStatusBadges({ repo }: { repo: LocalRepo }) {
+  const changes = [
+    [repo.conflicted, "conflicted", "text-status-red"],
+    [repo.staged, "staged", "text-status-green"],
+    [repo.modified, "modified", "text-status-amber"],
+    [repo.untracked, "untracked", "text-text-tertiary"],
+  ] as const;
+
+  return (
+    <div className="flex items-center gap-1.5 shrink-0 text-[10px] font-mono">
+      {repo.operation && (
+        <span className="px-1.5 py-0.5 rounded-full bg-status-red/10 text-status-red">
+          {OPERATION_LABELS[repo.operation]}
+        </span>
+      )}
+      {changes.map(
+        ([count, label, color]) =>
+          count > 0 && (
+            <span key={label} title={`${count} ${label}`} className={color}>
+              {label === "untracked" ? "?" : label[0].toUpperCase()}
+              {count}
+            </span>
+          ),
+      )}
+      {repo.stashes > 0 && (
+        <span title={`${repo.stashes} stashed`} className="text-text-tertiary">
+          ≡{repo.stashes}
+        </span>
+      )}
+      {repo.ahead > 0 && (
+        <span title={`${repo.ahead} ahead of ${repo.upstream}`} className="text-text-secondary">
+          ↑{repo.ahead}
+        </span>
+      )}
+      {repo.behind > 0 && (
+        <span title={`${repo.behind} behind ${repo.upstream}`} className="text-text-secondary">
+          ↓{repo.behind}
+        </span>
+      )}
+      {!repo.upstream && !repo.detached && (
+        <span title="No upstream branch" className="text-text-tertiary">
+          local
+        </span>
+      )}
+    </div>
+  );
+}
+
+function RepoRow({ repo, tools, tickets }: { repo: LocalRepo; tools: SessionTool[]; tickets: Link[] }) {
   const availableTools = tools.filter((t) => t.available);
 
   const launch = async (toolId: string) => {
@@ -243,10 +378,28 @@ function RepoRow({ repo, tools }: { repo: LocalRepo; tools: SessionTool[] }) {
       <div className="flex items-center gap-2.5 min-w-0 flex-1">
         <span
           className={`w-2 h-2 rounded-full shrink-0 ${
-            repo.is_dirty ? "bg-status-amber" : "bg-status-green"
+            repo.operation || repo.conflicted > 0
+              ? "bg-status-red"
+              : repo.is_dirty
+                ? "bg-status-amber"
+                : "bg-status-green"
           }`}
         />
         <span className="text-[13px] font-medium text-text-primary truncate">{repo.name}</span>
+        {tickets.slice(0, 3).map((t) => (
+          <a
+            key={t.id}
+            href={t.work_item_url ?? undefined}
+            target="_blank"
+            rel="noopener noreferrer"
+            title={t.work_item_title ?? undefined}
+            className="text-[10px] px-1.5 py-0.5 rounded-full bg-raised text-text-secondary font-mono shrink-0 hover:text-text-primary"
+          >
+            {t.work_item_key ?? t.work_item_id}
+          </a>
+        ))}
+        {tickets.length > 3 && <span className="text-[10px] text-text-tertiary font-mono">+{tickets.length - 3}</span>}
+        <RepoStatusBadges repo={repo} />
       </div>
       <div className="flex items-center gap-1.5 shrink-0 opacity-0 group-hover:opacity-100 transition-opacity">
         {availableTools.map((tool) => (
@@ -267,7 +420,12 @@ function RepoRow({ repo, tools }: { repo: LocalRepo; tools: SessionTool[] }) {
           <circle cx="11" cy="3" r="1.5" />
           <path d="M11 9c0 2.2-1.8 4-4 4" />
         </svg>
-        <span className="text-xs text-text-secondary font-mono">{repo.current_branch}</span>
+        <span
+          className={`text-xs font-mono ${repo.detached ? "text-status-amber" : "text-text-secondary"}`}
+          title={repo.detached ? "Detached HEAD" : repo.upstream ?? undefined}
+        >
+          {repo.current_branch}
+        </span>
       </div>
     </div>
   );
@@ -1,20 +1,32 @@
 import { useEffect, useState, useRef } from "react";
-import { useGitHubAuth } from "../hooks/useGitHub";
-import { useAzureAuth } from "../hooks/useAzure";
-import { useJiraAuth, saveJiraConfig, deleteJiraConfig } from "../hooks/useJira";
-import { useFogBugzAuth, saveFogBugzConfig, deleteFogBugzConfig } from "../hooks/useFogBugz";
-import { invoke } from "@tauri-apps/api/core";
+import { useGitHubAuth, saveGitHubPat, deleteGitHubPat } from "../hooks/useGitHub";
+import { useAzureAuth, listAzureProjects, addAzureProject, removeAzureProject } from "../hooks/useAzure";
+import {
+  useConnections,
+  createConnection,
+  deleteConnection,
+  testConnection,
+} from "../hooks/useConnections";
+import { useSavedQueries, createSavedQuery, deleteSavedQuery } from "../hooks/useSavedQueries";
+import { getBranchTemplate, setBranchTemplate } from "../hooks/useWorkflow";
+import { detectSessionTools, createSessionTool, updateSessionTool, deleteSessionTool } from "../hooks/useSessionTools";
+import { listScanRoots, createScanRoot, updateScanRoot, deleteScanRoot } from "../hooks/useScanRoots";
 import { PathAutocomplete } from "../components/PathAutocomplete";
 import { Spinner } from "../components/Spinner";
 import { PageHeader } from "../components/PageHeader";
-import Database from "@tauri-apps/plugin-sql";
-
-interface SessionTool {
-  id: string;
-  name: string;
-  available: boolean;
-  category: string;
-}
+import { errorMessage } from "../lib/errors";
+import type {
+  AuthMethod,
+  AzureProject,
+  Connection,
+  ConnectionConfig,
+  ProviderKind,
+  ProviderStatus,
+  ScanRoot,
+  SessionTool,
+  SessionToolCategory,
+  SessionToolInput,
+} from "../types";
 
 function SettingsCard({
   title,
@@ -42,6 +54,7 @@ function ConnectionStatus({
   sublabel,
   onCheck,
   onDisconnect,
+  removable,
 }: {
   connected: boolean;
   loading: boolean;
@@ -49,6 +62,8 @@ function ConnectionStatus({
   sublabel?: string;
   onCheck: () => void;
   onDisconnect?: () => void;
+  /** Offer to remove the connection even while it is not authenticated */
+  removable?: boolean;
 }) {
   return (
     <div className="flex items-center justify-between">
@@ -74,7 +89,7 @@ function ConnectionStatus({
         >
           Re-check
         </button>
-        {onDisconnect && connected && (
+        {onDisconnect && (connected || removable) && (
           <button
             onClick={onDisconnect}
             className="text-[11px] text-text-tertiary hover:text-status-red transition-colors"
@@ -87,66 +102,737 @@ function ConnectionStatus({
   );
 }
 
-export function SettingsPage({ active }: { active: boolean }) {
-  const { status: ghStatus, loading: ghLoading, refresh: ghRefresh } = useGitHubAuth();
-  const { status: azStatus, loading: azLoading, refresh: azRefresh } = useAzureAuth();
-  const {
-    config: jiraConfig,
-    status: jiraStatus,
-    loading: jiraLoading,
-    refresh: jiraRefresh,
-  } = useJiraAuth();
-  const {
-    config: fbConfig,
-    status: fbStatus,
-    loading: fbLoading,
-    refresh: fbRefresh,
-  } = useFogBugzAuth();
-  const [tools, setTools] = useState<SessionTool[]>([]);
-  const [roots, setRoots] = useState<{ id: string; path: string }[]>([]);
-  const [newRoot, setNewRoot] = useState("");
-  const mounted = useRef(false);
+const inputClass =
+  "w-full bg-base border border-border rounded-md px-3 py-1.5 text-sm text-text-primary placeholder-text-tertiary focus:outline-none focus:border-accent/50 focus:ring-1 focus:ring-accent/20";
 
-  const [jiraForm, setJiraForm] = useState({
-    instance_url: "",
-    email: "",
-    api_token: "",
-  });
-  const [jiraSaving, setJiraSaving] = useState(false);
-  const [jiraFormError, setJiraFormError] = useState<string | null>(null);
+interface ConfigField {
+  key: keyof ConnectionConfig;
+  label: string;
+  placeholder: string;
+  type?: string;
+  optional?: boolean;
+}
 
-  const [fbForm, setFbForm] = useState({
-    instance_url: "",
-    email: "",
-    password: "",
+/** Saved accounts of one provider with their status, plus a form to add one */
+function ProviderConnections({
+  provider,
+  authMethod,
+  connections,
+  fields,
+  secretLabel,
+  secretPlaceholder,
+  secretLink,
+  labelPlaceholder,
+  addLabel,
+  optional,
+  onChange,
+}: {
+  provider: ProviderKind;
+  authMethod: AuthMethod;
+  connections: Connection[];
+  fields: ConfigField[];
+  secretLabel?: string;
+  secretPlaceholder?: string;
+  secretLink?: string;
+  labelPlaceholder: string;
+  addLabel: string;
+  /** Keep the form collapsed even when nothing is saved yet */
+  optional?: boolean;
+  onChange: () => void;
+}) {
+  const [statuses, setStatuses] = useState<Record<string, ProviderStatus | null>>({});
+  const [checking, setChecking] = useState<Record<string, boolean>>({});
+  const [adding, setAdding] = useState(false);
+  const [form, setForm] = useState<{
+    label: string;
+    secret: string;
+    config: Partial<Record<keyof ConnectionConfig, string>>;
+  }>({
+    label: "",
+    secret: "",
+    config: {},
   });
-  const [fbSaving, setFbSaving] = useState(false);
-  const [fbFormError, setFbFormError] = useState<string | null>(null);
+  const [saving, setSaving] = useState(false);
+  const [formError, setFormError] = useState<string | null>(null);
 
-  useEffect(() => {
-    invoke<SessionTool[]>("detect_session_tools").then(setTools);
-    loadRoots();
-  }, []);
+  const check = async (connection: Connection) => {
+    setChecking((c) => ({ ...c, [connection.id]: true }));
+    try {
+      const status = await testConnection(connection.id);
+      setStatuses((s) => ({ ...s, [connection.id]: status }));
+    } catch {
+      setStatuses((s) => ({ ...s, [connection.id]: null }));
+    } finally {
+      setChecking((c) => ({ ...c, [connection.id]: false }));
+    }
+  };
 
   useEffect(() => {
-    if (jiraConfig) {
-      setJiraForm({
-        instance_url: jiraConfig.instance_url,
-        email: jiraConfig.email,
-        api_token: jiraConfig.api_token,
+    connections.forEach(check);
+  }, [connections]);
+
+  const remove = async (connection: Connection) => {
+    await deleteConnection(connection.id);
+    onChange();
+  };
+
+  const handleSave = async () => {
+    const missing = fields.some((f) => !f.optional && !form.config[f.key]?.trim());
+    if (!form.label.trim() || missing || (secretLabel && !form.secret.trim())) {
+      setFormError("All fields are required.");
+      return;
+    }
+    const invalidNumber = fields.some((f) => {
+      const value = form.config[f.key]?.trim();
+      return f.type === "number" && value && !(Number.isInteger(Number(value)) && Number(value) > 0);
+    });
+    if (invalidNumber) {
+      setFormError("Limits must be positive whole numbers.");
+      return;
+    }
+    setSaving(true);
+    setFormError(null);
+    try {
+      const config: ConnectionConfig = {};
+      fields.forEach((f) => {
+        const value = form.config[f.key]?.trim();
+        if (value) Object.assign(config, { [f.key]: f.type === "number" ? Number(value) : value });
       });
+      await createConnection({
+        provider,
+        label: form.label.trim(),
+        auth_method: authMethod,
+        config,
+        secret: secretLabel ? form.secret.trim() : undefined,
+      });
+      setForm({ label: "", secret: "", config: {} });
+      setAdding(false);
+      onChange();
+    } catch (err) {
+      setFormError(errorMessage(err));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const showForm = adding || (!optional && connections.length === 0);
+
+  return (
+    <div className="space-y-3">
+      {connections.map((c) => {
+        const status = statuses[c.id];
+        return (
+          <ConnectionStatus
+            key={c.id}
+            connected={!!status?.authenticated}
+            loading={!!checking[c.id]}
+            label={c.label}
+            sublabel={
+              status?.account ?? c.username ?? c.config.instance_url ?? c.config.host
+            }
+            onCheck={() => check(c)}
+            onDisconnect={() => remove(c)}
+            removable
+          />
+        );
+      })}
+      {showForm ? (
+        <div className="space-y-2.5">
+          <div>
+            <label className="text-[11px] text-text-tertiary block mb-1">Label</label>
+            <input
+              type="text"
+              value={form.label}
+              onChange={(e) => setForm((f) => ({ ...f, label: e.target.value }))}
+              placeholder={labelPlaceholder}
+              className={inputClass}
+            />
+          </div>
+          {fields.map((field) => (
+            <div key={field.key}>
+              <label className="text-[11px] text-text-tertiary block mb-1">{field.label}</label>
+              <input
+                type={field.type ?? "text"}
+                value={form.config[field.key] ?? ""}
+                onChange={(e) =>
+                  setForm((f) => ({ ...f, config: { ...f.config, [field.key]: e.target.value } }))
+                }
+                placeholder={field.placeholder}
+                className={inputClass}
+              />
+            </div>
+          ))}
+          {secretLabel && (
+            <div>
+              <label className="text-[11px] text-text-tertiary block mb-1">
+                {secretLabel}
+                {secretLink && (
+                  <a
+                    href={secretLink}
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    className="ml-2 text-accent/60 hover:text-accent underline"
+                  >
+                    Create token
+                  </a>
+                )}
+              </label>
+              <input
+                type="password"
+                value={form.secret}
+                onChange={(e) => setForm((f) => ({ ...f, secret: e.target.value }))}
+                placeholder={secretPlaceholder}
+                className={inputClass}
+              />
+            </div>
+          )}
+          {formError && <p className="text-status-red text-xs">{formError}</p>}
+          <div className="flex items-center gap-3">
+            <button
+              onClick={handleSave}
+              disabled={saving}
+              className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50"
+            >
+              {saving ? "Connecting..." : "Connect"}
+            </button>
+            {(connections.length > 0 || optional) && (
+              <button
+                onClick={() => setAdding(false)}
+                className="text-[11px] text-text-tertiary hover:text-text-primary transition-colors"
+              >
+                Cancel
+              </button>
+            )}
+          </div>
+        </div>
+      ) : (
+        <button
+          onClick={() => setAdding(true)}
+          className="text-[11px] text-text-tertiary hover:text-text-primary transition-colors"
+        >
+          {addLabel}
+        </button>
+      )}
+    </div>
+  );
+}
+
+const QUERY_PLACEHOLDERS: Record<ProviderKind, string> = {
+  github: "is:open label:bug repo:owner/repo",
+  jira: 'project = WEB AND status = "In QA" ORDER BY updated DESC',
+  fogbugz: "project:Web status:active",
+  azure_devops: "SELECT [System.Id] FROM workitems WHERE [System.TeamProject] = @project AND [System.State] = 'Active'",
+};
+
+/** Named queries run against one connection in place of "assigned to me" */
+function SavedQueries({ connections }: { connections: Connection[] }) {
+  const { queries, refresh } = useSavedQueries();
+  const [connectionId, setConnectionId] = useState("");
+  const [name, setName] = useState("");
+  const [query, setQuery] = useState("");
+  const [saving, setSaving] = useState(false);
+  const [formError, setFormError] = useState<string | null>(null);
+
+  const selected = connections.find((c) => c.id === connectionId) ?? connections[0];
+  const labelOf = (id: string) => connections.find((c) => c.id === id)?.label ?? id;
+
+  const handleAdd = async () => {
+    if (!selected || !name.trim() || !query.trim()) {
+      setFormError("Name and query are required.");
+      return;
     }
-  }, [jiraConfig]);
+    setSaving(true);
+    setFormError(null);
+    try {
+      await createSavedQuery({ connection_id: selected.id, name: name.trim(), query: query.trim() });
+      setName("");
+      setQuery("");
+      refresh();
+    } catch (err) {
+      setFormError(errorMessage(err));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleRemove = async (id: string) => {
+    await deleteSavedQuery(id);
+    refresh();
+  };
+
+  if (connections.length === 0) {
+    return <p className="text-xs text-text-tertiary">Add a connection to save queries for it.</p>;
+  }
+
+  return (
+    <div className="space-y-2.5">
+      {queries.length > 0 && (
+        <div className="space-y-1">
+          {queries.map((q) => (
+            <div key={q.id} className="flex items-center justify-between gap-2 px-2.5 py-1.5 rounded-md bg-base group">
+              <div className="min-w-0">
+                <span className="text-xs text-text-primary">{q.name}</span>
+                <span className="text-[11px] text-text-tertiary ml-2">{labelOf(q.connection_id)}</span>
+                <p className="text-[11px] font-mono text-text-secondary truncate">{q.query}</p>
+              </div>
+              <button
+                onClick={() => handleRemove(q.id)}
+                className="text-[11px] text-text-tertiary hover:text-status-red opacity-0 group-hover:opacity-100 transition-all shrink-0"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+      <div className="flex gap-2">
+        <select
+          value={selected?.id ?? ""}
+          onChange={(e) => setConnectionId(e.target.value)}
+          className={`${inputClass} w-auto`}
+        >
+          {connections.map((c) => (
+            <option key={c.id} value={c.id}>
+              {c.label}
+            </option>
+          ))}
+        </select>
+        <input
+          type="text"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          placeholder="Waiting on QA"
+          className={inputClass}
+        />
+      </div>
+      <div className="flex gap-2">
+        <input
+          type="text"
+          value={query}
+          onChange={(e) => setQuery(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+          placeholder={selected ? QUERY_PLACEHOLDERS[selected.provider] : ""}
+          className={`${inputClass} font-mono`}
+        />
+        <button
+          onClick={handleAdd}
+          disabled={saving}
+          className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50 shrink-0"
+        >
+          Save
+        </button>
+      </div>
+      {formError && <p className="text-status-red text-xs">{formError}</p>}
+    </div>
+  );
+}
+
+/** Template for branches created with "Start work" on an issue */
+function BranchTemplate() {
+  const [template, setTemplate] = useState("");
+  const [saved, setSaved] = useState("");
+  const [formError, setFormError] = useState<string | null>(null);
 
   useEffect(() => {
-    if (fbConfig) {
-      setFbForm({
-        instance_url: fbConfig.instance_url,
-        email: fbConfig.email,
-        password: fbConfig.password,
+    getBranchTemplate()
+      .then((t) => {
+        setTemplate(t);
+        setSaved(t);
+      })
+      .catch(() => {});
+  }, []);
+
+  const handleSave = async () => {
+    setFormError(null);
+    try {
+      await setBranchTemplate(template);
+      setSaved(template.trim());
+    } catch (err) {
+      setFormError(errorMessage(err));
+    }
+  };
+
+  return (
+    <div className="space-y-1.5">
+      <div className="flex gap-2">
+        <input
+          type="text"
+          value={template}
+          onChange={(e) => setTemplate(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleSave()}
+          placeholder="{type}/{key}-{slug}"
+          className={`${inputClass} font-mono`}
+        />
+        <button
+          onClick={handleSave}
+          disabled={template.trim() === saved}
+          className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50 shrink-0"
+        >
+          Save
+        </button>
+      </div>
+      <p className="text-[11px] text-text-tertiary">
+        Placeholders: <span className="font-mono">{"{type}"}</span> (bug, story, ...),{" "}
+        <span className="font-mono">{"{key}"}</span>, <span className="font-mono">{"{slug}"}</span> (from the title),{" "}
+        <span className="font-mono">{"{provider}"}</span>
+      </p>
+      {formError && <p className="text-status-red text-xs">{formError}</p>}
+    </div>
+  );
+}
+
+/** Add, edit or override a session tool. `tool` is the one being edited, if any. */
+function SessionToolForm({
+  tool,
+  onSaved,
+  onCancel,
+}: {
+  tool: SessionTool | null;
+  onSaved: () => void;
+  onCancel: () => void;
+}) {
+  const [id, setId] = useState(tool?.id ?? "");
+  const [name, setName] = useState(tool?.name ?? "");
+  const [command, setCommand] = useState(tool?.command ?? "");
+  const [args, setArgs] = useState(tool?.args.join("\n") ?? "{repo}");
+  const [category, setCategory] = useState<SessionToolCategory>(tool?.category ?? "editor");
+  const [env, setEnv] = useState(
+    Object.entries(tool?.env ?? {})
+      .map(([k, v]) => `${k}=${v}`)
+      .join("\n"),
+  );
+  const context = tool?.context ?? null;
+  const [contextMode, setContextMode] = useState<"none" | "args" | "stdin">(context?.via ?? "none");
+  const [contextArgs, setContextArgs] = useState(context?.via === "args" ? context.args.join("\n") : "{prompt}");
+  const [saving, setSaving] = useState(false);
+  const [formError, setFormError] = useState<string | null>(null);
+
+  const lines = (text: string) => text.split("\n").filter((l) => l.trim() !== "");
+
+  const handleSave = async () => {
+    const input: SessionToolInput = {
+      name,
+      command,
+      args: lines(args),
+      category,
+      env: Object.fromEntries(
+        lines(env).map((line) => {
+          const at = line.indexOf("=");
+          return at < 0 ? [line.trim(), ""] : [line.slice(0, at).trim(), line.slice(at + 1)];
+        }),
+      ),
+      context:
+        contextMode === "args"
+          ? { via: "args", args: lines(contextArgs) }
+          : contextMode === "stdin"
+            ? { via: "stdin" }
+            : null,
+    };
+    setSaving(true);
+    setFormError(null);
+    try {
+      // Editing a built-in that isn't overridden yet creates the override
+      if (tool?.custom) {
+        await updateSessionTool(tool.id, input);
+      } else {
+        await createSessionTool(id, input);
+      }
+      onSaved();
+    } catch (err) {
+      setFormError(errorMessage(err));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <div className="space-y-2.5 mt-3 pt-3 border-t border-border">
+      <div className="grid grid-cols-2 gap-2">
+        <input
+          type="text"
+          value={id}
+          onChange={(e) => setId(e.target.value)}
+          disabled={!!tool}
+          placeholder="id (e.g. zed)"
+          className={`${inputClass} font-mono disabled:opacity-50`}
+        />
+        <input type="text" value={name} onChange={(e) => setName(e.target.value)} placeholder="Name" className={inputClass} />
+        <input
+          type="text"
+          value={command}
+          onChange={(e) => setCommand(e.target.value)}
+          placeholder="Command"
+          className={`${inputClass} font-mono`}
+        />
+        <select
+          value={category}
+          onChange={(e) => setCategory(e.target.value as SessionToolCategory)}
+          className={inputClass}
+        >
+          <option value="editor">Editor</option>
+          <option value="terminal">Terminal</option>
+          <option value="ai">AI</option>
+          <option value="other">Other</option>
+        </select>
+      </div>
+      <div className="grid grid-cols-2 gap-2">
+        <div>
+          <label className="text-[11px] text-text-tertiary block mb-1">Arguments, one per line</label>
+          <textarea value={args} onChange={(e) => setArgs(e.target.value)} rows={3} className={`${inputClass} font-mono`} />
+        </div>
+        <div>
+          <label className="text-[11px] text-text-tertiary block mb-1">Environment, KEY=value per line</label>
+          <textarea value={env} onChange={(e) => setEnv(e.target.value)} rows={3} className={`${inputClass} font-mono`} />
+        </div>
+      </div>
+      <div className="flex gap-2 items-start">
+        <select
+          value={contextMode}
+          onChange={(e) => setContextMode(e.target.value as "none" | "args" | "stdin")}
+          className={`${inputClass} w-48 shrink-0`}
+        >
+          <option value="none">No work item context</option>
+          <option value="args">Context as arguments</option>
+          <option value="stdin">Context on stdin</option>
+        </select>
+        {contextMode === "args" && (
+          <textarea
+            value={contextArgs}
+            onChange={(e) => setContextArgs(e.target.value)}
+            rows={2}
+            className={`${inputClass} font-mono`}
+          />
+        )}
+      </div>
+      <p className="text-[11px] text-text-tertiary">
+        Placeholders: <span className="font-mono">{"{repo}"}</span>, <span className="font-mono">{"{branch}"}</span>,{" "}
+        <span className="font-mono">{"{issue_key}"}</span>; context arguments also take{" "}
+        <span className="font-mono">{"{prompt}"}</span> and <span className="font-mono">{"{context_file}"}</span>
+      </p>
+      <div className="flex gap-2">
+        <button
+          onClick={handleSave}
+          disabled={saving}
+          className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50"
+        >
+          Save
+        </button>
+        <button
+          onClick={onCancel}
+          className="px-3 py-1.5 text-sm text-text-secondary rounded-md hover:bg-hover transition-colors"
+        >
+          Cancel
+        </button>
+      </div>
+      {formError && <p className="text-status-red text-xs">{formError}</p>}
+    </div>
+  );
+}
+
+/** Depth, glob patterns and symlink following for one scan directory */
+function ScanRootForm({ root, onSaved, onCancel }: { root: ScanRoot; onSaved: () => void; onCancel: () => void }) {
+  const [maxDepth, setMaxDepth] = useState(String(root.max_depth));
+  const [include, setInclude] = useState(root.include.join("\n"));
+  const [exclude, setExclude] = useState(root.exclude.join("\n"));
+  const [followSymlinks, setFollowSymlinks] = useState(root.follow_symlinks);
+  const [saving, setSaving] = useState(false);
+  const [formError, setFormError] = useState<string | null>(null);
+
+  const lines = (text: string) => text.split("\n").filter((l) => l.trim() !== "");
+
+  const handleSave = async () => {
+    setSaving(true);
+    setFormError(null);
+    try {
+      await updateScanRoot(root.id, {
+        max_depth: Number(maxDepth),
+        include: lines(include),
+        exclude: lines(exclude),
+        follow_symlinks: followSymlinks,
       });
+      onSaved();
+    } catch (err) {
+      setFormError(errorMessage(err));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  return (
+    <div className="space-y-2.5 px-2.5 py-2.5">
+      <div className="flex items-center gap-4">
+        <label className="flex items-center gap-2 text-xs text-text-secondary">
+          Depth
+          <input
+            type="number"
+            min={0}
+            max={16}
+            value={maxDepth}
+            onChange={(e) => setMaxDepth(e.target.value)}
+            className={`${inputClass} w-16`}
+          />
+        </label>
+        <label className="flex items-center gap-2 text-xs text-text-secondary">
+          <input type="checkbox" checked={followSymlinks} onChange={(e) => setFollowSymlinks(e.target.checked)} />
+          Follow symlinks
+        </label>
+      </div>
+      <div className="grid grid-cols-2 gap-2">
+        <div>
+          <label className="text-[11px] text-text-tertiary block mb-1">Exclude, one pattern per line</label>
+          <textarea value={exclude} onChange={(e) => setExclude(e.target.value)} rows={3} className={`${inputClass} font-mono`} />
+        </div>
+        <div>
+          <label className="text-[11px] text-text-tertiary block mb-1">Include even if excluded</label>
+          <textarea
+            value={include}
+            onChange={(e) => setInclude(e.target.value)}
+            rows={3}
+            placeholder={".config\nwork/.archive"}
+            className={`${inputClass} font-mono`}
+          />
+        </div>
+      </div>
+      <p className="text-[11px] text-text-tertiary">
+        <span className="font-mono">*</span> and <span className="font-mono">?</span> match within a name,{" "}
+        <span className="font-mono">**</span> across directories. Patterns with a <span className="font-mono">/</span>{" "}
+        match the path from this directory; others match a directory name anywhere.
+      </p>
+      <div className="flex gap-2">
+        <button
+          onClick={handleSave}
+          disabled={saving}
+          className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50"
+        >
+          Save
+        </button>
+        <button
+          onClick={onCancel}
+          className="px-3 py-1.5 text-sm text-text-secondary rounded-md hover:bg-hover transition-colors"
+        >
+          Cancel
+        </button>
+      </div>
+      {formError && <p className="text-status-red text-xs">{formError}</p>}
+    </div>
+  );
+}
+
+/** Organization/project pairs an Azure DevOps connection fetches from */
+function AzureProjects({ connection }: { connection: Connection }) {
+  const [projects, setProjects] = useState<AzureProject[]>([]);
+  const [organization, setOrganization] = useState("");
+  const [project, setProject] = useState("");
+  const [saving, setSaving] = useState(false);
+  const [formError, setFormError] = useState<string | null>(null);
+
+  const load = async () => {
+    try {
+      setProjects(await listAzureProjects(connection.id));
+    } catch {
+      setProjects([]);
+    }
+  };
+
+  useEffect(() => {
+    load();
+  }, [connection.id]);
+
+  const handleAdd = async () => {
+    if (!organization.trim() || !project.trim()) {
+      setFormError("Organization and project are required.");
+      return;
+    }
+    setSaving(true);
+    setFormError(null);
+    try {
+      await addAzureProject(connection.id, organization.trim(), project.trim());
+      setProject("");
+      load();
+    } catch (err) {
+      setFormError(errorMessage(err));
+    } finally {
+      setSaving(false);
     }
-  }, [fbConfig]);
+  };
+
+  const handleRemove = async (id: string) => {
+    await removeAzureProject(id);
+    load();
+  };
+
+  return (
+    <div className="space-y-2.5">
+      {projects.length === 0 ? (
+        <p className="text-xs text-text-tertiary">
+          No projects added. The <code className="font-mono">az devops configure</code> defaults are used.
+        </p>
+      ) : (
+        <div className="space-y-1">
+          {projects.map((p) => (
+            <div key={p.id} className="flex items-center justify-between gap-2 py-1 group">
+              <span className="text-xs font-mono text-text-secondary truncate">
+                {p.organization} / {p.project}
+              </span>
+              <button
+                onClick={() => handleRemove(p.id)}
+                className="text-[11px] text-text-tertiary hover:text-status-red opacity-0 group-hover:opacity-100 transition-all shrink-0"
+              >
+                Remove
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+      <div className="flex gap-2">
+        <input
+          type="text"
+          value={organization}
+          onChange={(e) => setOrganization(e.target.value)}
+          placeholder="https://dev.azure.com/yourorg"
+          className={inputClass}
+        />
+        <input
+          type="text"
+          value={project}
+          onChange={(e) => setProject(e.target.value)}
+          onKeyDown={(e) => e.key === "Enter" && handleAdd()}
+          placeholder="Project"
+          className={inputClass}
+        />
+        <button
+          onClick={handleAdd}
+          disabled={saving}
+          className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50 shrink-0"
+        >
+          Add
+        </button>
+      </div>
+      {formError && <p className="text-status-red text-xs">{formError}</p>}
+    </div>
+  );
+}
+
+export function SettingsPage({ active }: { active: boolean }) {
+  const { status: ghStatus, loading: ghLoading, refresh: ghRefresh } = useGitHubAuth();
+  const { status: azStatus, loading: azLoading, refresh: azRefresh } = useAzureAuth();
+  const { connections, refresh: connRefresh } = useConnections();
+  const [tools, setTools] = useState<SessionTool[]>([]);
+  const [editing, setEditing] = useState<SessionTool | "new" | null>(null);
+  const [roots, setRoots] = useState<ScanRoot[]>([]);
+  const [newRoot, setNewRoot] = useState("");
+  const [editingRoot, setEditingRoot] = useState<string | null>(null);
+  const [rootError, setRootError] = useState<string | null>(null);
+  const mounted = useRef(false);
+
+  const [ghToken, setGhToken] = useState("");
+  const [ghSaving, setGhSaving] = useState(false);
+  const [ghFormError, setGhFormError] = useState<string | null>(null);
+
+  useEffect(() => {
+    loadTools();
+    loadRoots();
+  }, []);
 
   useEffect(() => {
     if (!mounted.current) {
@@ -156,97 +842,68 @@ export function SettingsPage({ active }: { active: boolean }) {
     if (active) {
       ghRefresh();
       azRefresh();
-      jiraRefresh();
-      fbRefresh();
-      invoke<SessionTool[]>("detect_session_tools").then(setTools);
+      connRefresh();
+      loadTools();
       loadRoots();
     }
   }, [active]);
 
-  const loadRoots = async () => {
-    const db = await Database.load("sqlite:aura.db");
-    const rows = await db.select<{ id: string; path: string }[]>(
-      "SELECT id, path FROM scan_roots ORDER BY path"
-    );
-    setRoots(rows);
+  const loadTools = () => {
+    detectSessionTools().then(setTools).catch(() => {});
   };
 
-  const addRoot = async () => {
-    const trimmed = newRoot.trim();
-    if (!trimmed) return;
-    const db = await Database.load("sqlite:aura.db");
-    await db.execute(
-      "INSERT OR IGNORE INTO scan_roots (id, path) VALUES (?, ?)",
-      [crypto.randomUUID(), trimmed]
-    );
-    setNewRoot("");
-    loadRoots();
+  const removeTool = async (id: string) => {
+    await deleteSessionTool(id);
+    loadTools();
   };
 
-  const removeRoot = async (id: string) => {
-    const db = await Database.load("sqlite:aura.db");
-    await db.execute("DELETE FROM scan_roots WHERE id = ?", [id]);
-    loadRoots();
+  const loadRoots = () => {
+    listScanRoots().then(setRoots).catch(() => {});
   };
 
-  const handleJiraSave = async () => {
-    const { instance_url, email, api_token } = jiraForm;
-    if (!instance_url.trim() || !email.trim() || !api_token.trim()) {
-      setJiraFormError("All fields are required.");
-      return;
-    }
-    setJiraSaving(true);
-    setJiraFormError(null);
+  const addRoot = async () => {
+    const trimmed = newRoot.trim();
+    if (!trimmed) return;
+    setRootError(null);
     try {
-      await saveJiraConfig({
-        instance_url: instance_url.trim(),
-        email: email.trim(),
-        api_token: api_token.trim(),
-      });
-      await jiraRefresh();
+      await createScanRoot({ path: trimmed });
+      setNewRoot("");
+      loadRoots();
     } catch (err) {
-      setJiraFormError(String(err));
-    } finally {
-      setJiraSaving(false);
+      setRootError(errorMessage(err));
     }
   };
 
-  const handleJiraDisconnect = async () => {
-    await deleteJiraConfig();
-    setJiraForm({ instance_url: "", email: "", api_token: "" });
-    await jiraRefresh();
+  const removeRoot = async (id: string) => {
+    await deleteScanRoot(id);
+    loadRoots();
   };
 
-  const handleFogBugzSave = async () => {
-    const { instance_url, email, password } = fbForm;
-    if (!instance_url.trim() || !email.trim() || !password.trim()) {
-      setFbFormError("All fields are required.");
+  const handleGhSave = async () => {
+    if (!ghToken.trim()) {
+      setGhFormError("Token is required.");
       return;
     }
-    setFbSaving(true);
-    setFbFormError(null);
+    setGhSaving(true);
+    setGhFormError(null);
     try {
-      await saveFogBugzConfig({
-        instance_url: instance_url.trim(),
-        email: email.trim(),
-        password: password.trim(),
-      });
-      await fbRefresh();
+      await saveGitHubPat(ghToken.trim());
+      setGhToken("");
+      await ghRefresh();
     } catch (err) {
-      setFbFormError(String(err));
+      setGhFormError(errorMessage(err));
     } finally {
-      setFbSaving(false);
+      setGhSaving(false);
     }
   };
 
-  const handleFogBugzDisconnect = async () => {
-    await deleteFogBugzConfig();
-    setFbForm({ instance_url: "", email: "", password: "" });
-    await fbRefresh();
+  const handleGhDisconnect = async () => {
+    await deleteGitHubPat();
+    await ghRefresh();
   };
 
-  const inputClass =
-    "w-full bg-base border border-border rounded-md px-3 py-1.5 text-sm text-text-primary placeholder-text-tertiary focus:outline-none focus:border-accent/50 focus:ring-1 focus:ring-accent/20";
+  const byProvider = (provider: ProviderKind, authMethod?: AuthMethod) =>
+    connections.filter((c) => c.provider === provider && (!authMethod || c.auth_method === authMethod));
 
   return (
     <div className="max-w-[720px] mx-auto">
@@ -254,20 +911,98 @@ export function SettingsPage({ active }: { active: boolean }) {
 
       <div className="space-y-4">
         {/* GitHub */}
-        <SettingsCard title="GitHub" description="Authenticates via GitHub CLI (gh)">
+        <SettingsCard
+          title="GitHub"
+          description="Authenticates via GitHub CLI (gh), or a personal access token. GitHub Enterprise hosts use the CLI login for that host."
+        >
           <ConnectionStatus
-            connected={!!ghStatus?.cli_authenticated}
+            connected={!!ghStatus && ghStatus.auth_method !== "none"}
             loading={ghLoading}
             label={
-              ghStatus?.cli_authenticated
+              ghStatus?.auth_method === "cli"
                 ? "Connected"
-                : ghStatus?.cli_available
-                  ? "CLI installed but not authenticated"
-                  : "GitHub CLI not found"
+                : ghStatus?.auth_method === "pat"
+                  ? "Connected with access token"
+                  : ghStatus?.cli_available
+                    ? "CLI installed but not authenticated"
+                    : "GitHub CLI not found"
             }
             sublabel={ghStatus?.username ? `@${ghStatus.username}` : undefined}
             onCheck={ghRefresh}
+            onDisconnect={ghStatus?.auth_method === "pat" ? handleGhDisconnect : undefined}
           />
+          {ghStatus?.auth_method === "none" && (
+            <div className="space-y-2.5 mt-3">
+              <div>
+                <label className="text-[11px] text-text-tertiary block mb-1">
+                  Personal Access Token
+                  <a
+                    href="https://github.com/settings/tokens"
+                    target="_blank"
+                    rel="noopener noreferrer"
+                    className="ml-2 text-accent/60 hover:text-accent underline"
+                  >
+                    Create token
+                  </a>
+                </label>
+                <input
+                  type="password"
+                  value={ghToken}
+                  onChange={(e) => setGhToken(e.target.value)}
+                  placeholder="ghp_..."
+                  className={inputClass}
+                />
+              </div>
+              {ghFormError && <p className="text-status-red text-xs">{ghFormError}</p>}
+              <button
+                onClick={handleGhSave}
+                disabled={ghSaving}
+                className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50"
+              >
+                {ghSaving ? "Connecting..." : "Connect"}
+              </button>
+            </div>
+          )}
+          {ghStatus?.cli_available && (
+            <>
+              <div className="border-t border-border my-3" />
+              <ProviderConnections
+                provider="github"
+                authMethod="cli"
+                connections={byProvider("github", "cli").filter((c) => c.config.host)}
+                fields={[{ key: "host", label: "Enterprise host", placeholder: "github.yourcompany.com" }]}
+                labelPlaceholder="GitHub Enterprise"
+                addLabel="Add GitHub Enterprise host"
+                optional
+                onChange={connRefresh}
+              />
+            </>
+          )}
+          {ghStatus && ghStatus.auth_method !== "none" && (
+            <>
+              <div className="border-t border-border my-3" />
+              <ProviderConnections
+                provider="github"
+                authMethod="pat"
+                connections={byProvider("github", "pat")}
+                fields={[
+                  {
+                    key: "host",
+                    label: "Enterprise host (leave empty for github.com)",
+                    placeholder: "github.yourcompany.com",
+                    optional: true,
+                  },
+                ]}
+                secretLabel="Personal Access Token"
+                secretPlaceholder="ghp_..."
+                secretLink="https://github.com/settings/tokens"
+                labelPlaceholder="Work account"
+                addLabel="Add account with access token"
+                optional
+                onChange={connRefresh}
+              />
+            </>
+          )}
         </SettingsCard>
 
         {/* Azure DevOps */}
@@ -289,155 +1024,97 @@ export function SettingsPage({ active }: { active: boolean }) {
             }
             onCheck={azRefresh}
           />
+          {azStatus?.logged_in && (
+            <>
+              <div className="border-t border-border my-3" />
+              {byProvider("azure_devops").map((c) => (
+                <AzureProjects key={c.id} connection={c} />
+              ))}
+            </>
+          )}
         </SettingsCard>
 
         {/* Jira */}
-        <SettingsCard title="Jira Cloud" description="API token authentication">
-          {jiraStatus?.valid ? (
-            <ConnectionStatus
-              connected
-              loading={jiraLoading && !!jiraStatus}
-              label="Connected"
-              sublabel={jiraStatus.display_name ?? undefined}
-              onCheck={jiraRefresh}
-              onDisconnect={handleJiraDisconnect}
-            />
-          ) : (
-            <div className="space-y-2.5">
-              <div>
-                <label className="text-[11px] text-text-tertiary block mb-1">
-                  Instance URL
-                </label>
-                <input
-                  type="text"
-                  value={jiraForm.instance_url}
-                  onChange={(e) =>
-                    setJiraForm((f) => ({ ...f, instance_url: e.target.value }))
-                  }
-                  placeholder="https://yourteam.atlassian.net"
-                  className={inputClass}
-                />
-              </div>
-              <div>
-                <label className="text-[11px] text-text-tertiary block mb-1">
-                  Email
-                </label>
-                <input
-                  type="email"
-                  value={jiraForm.email}
-                  onChange={(e) =>
-                    setJiraForm((f) => ({ ...f, email: e.target.value }))
-                  }
-                  placeholder="you@company.com"
-                  className={inputClass}
-                />
-              </div>
-              <div>
-                <label className="text-[11px] text-text-tertiary block mb-1">
-                  API Token
-                  <a
-                    href="https://id.atlassian.com/manage-profile/security/api-tokens"
-                    target="_blank"
-                    rel="noopener noreferrer"
-                    className="ml-2 text-accent/60 hover:text-accent underline"
-                  >
-                    Create token
-                  </a>
-                </label>
-                <input
-                  type="password"
-                  value={jiraForm.api_token}
-                  onChange={(e) =>
-                    setJiraForm((f) => ({ ...f, api_token: e.target.value }))
-                  }
-                  placeholder="Your Jira API token"
-                  className={inputClass}
-                />
-              </div>
-              {jiraFormError && (
-                <p className="text-status-red text-xs">{jiraFormError}</p>
-              )}
-              <button
-                onClick={handleJiraSave}
-                disabled={jiraSaving}
-                className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50"
-              >
-                {jiraSaving ? "Connecting..." : "Connect"}
-              </button>
-            </div>
-          )}
+        <SettingsCard title="Jira" description="Cloud API token or Server / Data Center personal access token">
+          <ProviderConnections
+            provider="jira"
+            authMethod="api_token"
+            connections={byProvider("jira", "api_token")}
+            fields={[
+              { key: "instance_url", label: "Instance URL", placeholder: "https://yourteam.atlassian.net" },
+              { key: "email", label: "Email", placeholder: "you@company.com", type: "email" },
+              { key: "max_results", label: "Issue limit", placeholder: "1000", type: "number", optional: true },
+            ]}
+            secretLabel="API Token"
+            secretPlaceholder="Your Jira API token"
+            secretLink="https://id.atlassian.com/manage-profile/security/api-tokens"
+            labelPlaceholder="Jira"
+            addLabel="Add another site"
+            onChange={connRefresh}
+          />
+          <div className="border-t border-border my-3" />
+          <ProviderConnections
+            provider="jira"
+            authMethod="pat"
+            connections={byProvider("jira", "pat")}
+            fields={[
+              { key: "instance_url", label: "Instance URL", placeholder: "https://jira.yourcompany.com" },
+              { key: "max_results", label: "Issue limit", placeholder: "1000", type: "number", optional: true },
+            ]}
+            secretLabel="Personal Access Token"
+            secretPlaceholder="Your Jira personal access token"
+            labelPlaceholder="Jira Server"
+            addLabel="Add Jira Server / Data Center site"
+            optional
+            onChange={connRefresh}
+          />
         </SettingsCard>
 
         {/* FogBugz */}
         <SettingsCard
           title="FogBugz"
-          description="Username and password authentication"
+          description="Username and password, or an API token generated in FogBugz"
         >
-          {fbStatus?.valid ? (
-            <ConnectionStatus
-              connected
-              loading={fbLoading && !!fbStatus}
-              label="Connected"
-              sublabel={fbStatus.person_name ?? undefined}
-              onCheck={fbRefresh}
-              onDisconnect={handleFogBugzDisconnect}
-            />
-          ) : (
-            <div className="space-y-2.5">
-              <div>
-                <label className="text-[11px] text-text-tertiary block mb-1">
-                  Instance URL
-                </label>
-                <input
-                  type="text"
-                  value={fbForm.instance_url}
-                  onChange={(e) =>
-                    setFbForm((f) => ({ ...f, instance_url: e.target.value }))
-                  }
-                  placeholder="https://yourteam.fogbugz.com"
-                  className={inputClass}
-                />
-              </div>
-              <div>
-                <label className="text-[11px] text-text-tertiary block mb-1">
-                  Email
-                </label>
-                <input
-                  type="email"
-                  value={fbForm.email}
-                  onChange={(e) =>
-                    setFbForm((f) => ({ ...f, email: e.target.value }))
-                  }
-                  placeholder="you@company.com"
-                  className={inputClass}
-                />
-              </div>
-              <div>
-                <label className="text-[11px] text-text-tertiary block mb-1">
-                  Password
-                </label>
-                <input
-                  type="password"
-                  value={fbForm.password}
-                  onChange={(e) =>
-                    setFbForm((f) => ({ ...f, password: e.target.value }))
-                  }
-                  placeholder="Your FogBugz password"
-                  className={inputClass}
-                />
-              </div>
-              {fbFormError && (
-                <p className="text-status-red text-xs">{fbFormError}</p>
-              )}
-              <button
-                onClick={handleFogBugzSave}
-                disabled={fbSaving}
-                className="px-3 py-1.5 bg-accent text-white text-sm font-medium rounded-md hover:bg-accent/90 transition-colors disabled:opacity-50"
-              >
-                {fbSaving ? "Connecting..." : "Connect"}
-              </button>
-            </div>
-          )}
+          <ProviderConnections
+            provider="fogbugz"
+            authMethod="password"
+            connections={byProvider("fogbugz", "password")}
+            fields={[
+              { key: "instance_url", label: "Instance URL", placeholder: "https://yourteam.fogbugz.com" },
+              { key: "email", label: "Email", placeholder: "you@company.com", type: "email" },
+            ]}
+            secretLabel="Password"
+            secretPlaceholder="Your FogBugz password"
+            labelPlaceholder="FogBugz"
+            addLabel="Add another site"
+            onChange={connRefresh}
+          />
+          <div className="border-t border-border my-3" />
+          <ProviderConnections
+            provider="fogbugz"
+            authMethod="api_token"
+            connections={byProvider("fogbugz", "api_token")}
+            fields={[{ key: "instance_url", label: "Instance URL", placeholder: "https://yourteam.fogbugz.com" }]}
+            secretLabel="API Token"
+            secretPlaceholder="Your FogBugz API token"
+            labelPlaceholder="FogBugz"
+            addLabel="Add site with API token"
+            optional
+            onChange={connRefresh}
+          />
+        </SettingsCard>
+
+        {/* Saved Queries */}
+        <SettingsCard
+          title="Saved Queries"
+          description="Custom JQL, WIQL, GitHub or FogBugz searches shown as views on the Issues page"
+        >
+          <SavedQueries connections={connections} />
+        </SettingsCard>
+
+        {/* Branch Names */}
+        <SettingsCard title="Branch Names" description="How branches are named when you start work on an issue">
+          <BranchTemplate />
         </SettingsCard>
 
         {/* Scan Directories */}
@@ -448,19 +1125,41 @@ export function SettingsPage({ active }: { active: boolean }) {
           {roots.length > 0 && (
             <div className="space-y-1 mb-3">
               {roots.map((root) => (
-                <div
-                  key={root.id}
-                  className="flex items-center justify-between px-2.5 py-1.5 rounded-md bg-base"
-                >
-                  <span className="text-xs text-text-secondary font-mono truncate">
-                    {root.path}
-                  </span>
-                  <button
-                    onClick={() => removeRoot(root.id)}
-                    className="text-[11px] text-text-tertiary hover:text-status-red transition-colors ml-2 shrink-0"
-                  >
-                    Remove
-                  </button>
+                <div key={root.id} className="rounded-md bg-base">
+                  <div className="flex items-center justify-between px-2.5 py-1.5">
+                    <span className="text-xs text-text-secondary font-mono truncate">
+                      {root.path}
+                    </span>
+                    <span className="flex items-center gap-2 ml-2 shrink-0">
+                      <span className="text-[10px] text-text-tertiary">
+                        depth {root.max_depth}
+                        {root.include.length > 0 && ` · ${root.include.length} included`}
+                        {root.follow_symlinks && " · symlinks"}
+                      </span>
+                      <button
+                        onClick={() => setEditingRoot(editingRoot === root.id ? null : root.id)}
+                        className="text-[11px] text-text-tertiary hover:text-text-primary transition-colors"
+                      >
+                        Options
+                      </button>
+                      <button
+                        onClick={() => removeRoot(root.id)}
+                        className="text-[11px] text-text-tertiary hover:text-status-red transition-colors"
+                      >
+                        Remove
+                      </button>
+                    </span>
+                  </div>
+                  {editingRoot === root.id && (
+                    <ScanRootForm
+                      root={root}
+                      onSaved={() => {
+                        setEditingRoot(null);
+                        loadRoots();
+                      }}
+                      onCancel={() => setEditingRoot(null)}
+                    />
+                  )}
                 </div>
               ))}
             </div>
@@ -479,18 +1178,19 @@ export function SettingsPage({ active }: { active: boolean }) {
               Add
             </button>
           </div>
+          {rootError && <p className="text-status-red text-xs mt-2">{rootError}</p>}
         </SettingsCard>
 
         {/* Detected Tools */}
         <SettingsCard
           title="Detected Tools"
-          description="Editors, terminals, and AI coding tools found on your system"
+          description="Editors, terminals, and AI coding tools found on your system, plus your own"
         >
           <div className="grid grid-cols-2 gap-2">
             {tools.map((tool) => (
               <div
                 key={tool.id}
-                className="flex items-center gap-2.5 px-2.5 py-2 rounded-md bg-base"
+                className="group flex items-center gap-2.5 px-2.5 py-2 rounded-md bg-base"
               >
                 <span
                   className={`w-1.5 h-1.5 rounded-full shrink-0 ${
@@ -498,15 +1198,53 @@ export function SettingsPage({ active }: { active: boolean }) {
                   }`}
                 />
                 <span
-                  className={`text-xs ${
+                  className={`text-xs flex-1 truncate ${
                     tool.available ? "text-text-primary" : "text-text-tertiary"
                   }`}
+                  title={[tool.command, ...tool.args].join(" ")}
                 >
                   {tool.name}
+                  {tool.custom && (
+                    <span className="ml-1.5 text-[10px] text-text-tertiary">
+                      {tool.builtin ? "customized" : "custom"}
+                    </span>
+                  )}
                 </span>
+                <button
+                  onClick={() => setEditing(tool)}
+                  className="text-[11px] text-text-tertiary hover:text-text-primary opacity-0 group-hover:opacity-100 transition-opacity"
+                >
+                  Edit
+                </button>
+                {tool.custom && (
+                  <button
+                    onClick={() => removeTool(tool.id)}
+                    className="text-[11px] text-text-tertiary hover:text-status-red opacity-0 group-hover:opacity-100 transition-opacity"
+                  >
+                    {tool.builtin ? "Reset" : "Remove"}
+                  </button>
+                )}
               </div>
             ))}
           </div>
+          {editing ? (
+            <SessionToolForm
+              key={editing === "new" ? "new" : editing.id}
+              tool={editing === "new" ? null : editing}
+              onSaved={() => {
+                setEditing(null);
+                loadTools();
+              }}
+              onCancel={() => setEditing(null)}
+            />
+          ) : (
+            <button
+              onClick={() => setEditing("new")}
+              className="mt-3 text-xs text-accent/80 hover:text-accent transition-colors"
+            >
+              Add tool
+            </button>
+          )}
         </SettingsCard>
       </div>
     </div>
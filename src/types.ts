@@ -3,7 +3,6 @@ export interface AuthStatus {
   cli_authenticated: boolean;
   username: string | null;
   auth_method: "cli" | "pat" | "none";
-  token: string | null;
 }
 
 export interface GitHubUser {
@@ -59,6 +58,8 @@ export interface AzureWorkItem {
   changed_date: string;
   tags: string[];
   url: string;
+  organization: string;
+  project: string;
 }
 
 export interface AzurePullRequest {
@@ -71,6 +72,30 @@ export interface AzurePullRequest {
   target_branch: string;
   creation_date: string;
   url: string;
+  role: "author" | "reviewer" | "required_reviewer";
+  vote: "approved" | "approved_with_suggestions" | "no_vote" | "waiting_for_author" | "rejected" | null;
+  organization: string;
+  project: string;
+}
+
+export interface ProjectFailure {
+  organization: string;
+  project: string;
+  error: AuraError;
+}
+
+/** Results from several projects; a failing project doesn't hide the others */
+export interface AcrossProjects<T> {
+  items: T[];
+  failures: ProjectFailure[];
+}
+
+/** An organization/project pair fetched by an Azure DevOps connection */
+export interface AzureProject {
+  id: string;
+  connection_id: string;
+  organization: string;
+  project: string;
 }
 
 // -- Jira Cloud --
@@ -79,6 +104,7 @@ export interface JiraAuthStatus {
   valid: boolean;
   display_name: string | null;
   email: string | null;
+  deployment: "cloud" | "server" | "data_center" | null;
 }
 
 export interface JiraIssue {
@@ -94,12 +120,6 @@ export interface JiraIssue {
   project: string;
 }
 
-export interface JiraConfig {
-  instance_url: string;
-  email: string;
-  api_token: string;
-}
-
 // -- FogBugz --
 
 export interface FogBugzAuthStatus {
@@ -121,8 +141,319 @@ export interface FogBugzCase {
   is_open: boolean;
 }
 
-export interface FogBugzConfig {
-  instance_url: string;
-  email: string;
-  password: string;
+// -- Connections --
+
+export type AuthMethod = "cli" | "pat" | "api_token" | "password";
+
+export interface ConnectionConfig {
+  instance_url?: string;
+  email?: string;
+  /** GitHub Enterprise Server hostname; github.com when unset */
+  host?: string;
+  /** Most Jira issues to fetch; 1000 when unset */
+  max_results?: number;
+}
+
+/** A saved account on a provider. Secrets stay in the OS keychain. */
+export interface Connection {
+  id: string;
+  provider: ProviderKind;
+  label: string;
+  auth_method: AuthMethod;
+  username: string | null;
+  config: ConnectionConfig;
+  has_secret: boolean;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface NewConnection {
+  provider: ProviderKind;
+  label: string;
+  auth_method: AuthMethod;
+  config: ConnectionConfig;
+  secret?: string;
+}
+
+/** Fields left out are kept as they are */
+export interface ConnectionUpdate {
+  label?: string;
+  config?: ConnectionConfig;
+  secret?: string;
+}
+
+/** A provider-specific item tagged with the connection it came from */
+export type FromConnection<T> = T & { connection_id: string };
+
+// -- Saved queries --
+
+/** A named query in the connection's provider syntax (JQL, WIQL, ...) */
+export interface SavedQuery {
+  id: string;
+  connection_id: string;
+  provider: ProviderKind;
+  name: string;
+  query: string;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface NewSavedQuery {
+  connection_id: string;
+  name: string;
+  query: string;
+}
+
+export interface SavedQueryUpdate {
+  name?: string;
+  query?: string;
+}
+
+// -- Links (FR-003) --
+
+export type LinkKind = "branch" | "pull_request" | "repo";
+
+export interface Link {
+  id: string;
+  /** Unified work item id, `{connection_id}:{provider_id}` */
+  work_item_id: string;
+  kind: LinkKind;
+  repo_path: string | null;
+  /** Branch name or pull request id; empty for repo links */
+  target: string;
+  manual: boolean;
+  work_item_title: string | null;
+  work_item_url: string | null;
+  work_item_key: string | null;
+  target_title: string | null;
+  target_url: string | null;
+}
+
+export interface NewLink {
+  work_item_id: string;
+  kind: LinkKind;
+  repo_path?: string;
+  target?: string;
+}
+
+// -- Start work --
+
+export interface StartWorkOptions {
+  /** Check the branch out in a sibling worktree instead of the repo itself */
+  worktree?: boolean;
+  /** Branch name to use instead of the template */
+  branch?: string;
+}
+
+export interface StartedWork {
+  branch: string;
+  /** Ref the branch was created from; null if it already existed */
+  base: string | null;
+  /** Directory to open with `launch_session` */
+  path: string;
+  worktree: boolean;
+}
+
+// -- Session tools --
+
+export type SessionToolCategory = "editor" | "terminal" | "ai" | "other";
+
+/** How an AI tool receives work item context */
+export type ContextDelivery = { via: "args"; args: string[] } | { via: "stdin" };
+
+export interface SessionTool {
+  id: string;
+  name: string;
+  command: string;
+  /** May use `{repo}`, `{branch}` and `{issue_key}` */
+  args: string[];
+  available: boolean;
+  category: SessionToolCategory;
+  /** Null for tools that can't take work item context */
+  context: ContextDelivery | null;
+  env: Record<string, string>;
+  /** Ships with Aura; when also `custom`, it has been overridden */
+  builtin: boolean;
+  custom: boolean;
+}
+
+export interface SessionToolInput {
+  name: string;
+  command: string;
+  args: string[];
+  category: SessionToolCategory;
+  env: Record<string, string>;
+  context: ContextDelivery | null;
+}
+
+// -- Scan roots --
+
+/**
+ * A directory searched for Git repos. Patterns are globs (`*`, `?`, `**`);
+ * without a `/` they match a directory name, with one a path relative to the
+ * root. `include` wins over `exclude`.
+ */
+export interface ScanRoot {
+  id: string;
+  path: string;
+  max_depth: number;
+  include: string[];
+  exclude: string[];
+  /** Symlinked directories are walked once each, so links can't loop */
+  follow_symlinks: boolean;
+  created_at: string;
+}
+
+export interface NewScanRoot {
+  path: string;
+  max_depth?: number;
+  include?: string[];
+  /** Defaults to hidden directories, `node_modules` and `target` */
+  exclude?: string[];
+  follow_symlinks?: boolean;
+}
+
+export type ScanRootUpdate = Partial<NewScanRoot>;
+
+// -- Sessions --
+
+export type SessionStatus = "running" | "exited" | "killed" | "lost";
+
+export interface Session {
+  id: string;
+  tool_id: string;
+  tool_name: string;
+  repo_path: string;
+  work_item_id: string | null;
+  work_item_title: string | null;
+  pid: number;
+  /** `lost` when the app quit while the session was running */
+  status: SessionStatus;
+  exit_code: number | null;
+  started_at: string;
+  ended_at: string | null;
+  /** Up to now for running sessions */
+  duration_seconds: number;
+}
+
+export interface SessionFilter {
+  running?: boolean;
+  work_item_id?: string;
+  repo_path?: string;
+  /** `YYYY-MM-DD[ HH:MM:SS]`, UTC */
+  since?: string;
+  limit?: number;
+}
+
+// -- Time tracking --
+
+export interface TrackedTime {
+  work_item_id: string;
+  work_item_title: string | null;
+  /** Finished sessions; overlapping sessions count once */
+  session_seconds: number;
+  adjustment_seconds: number;
+  exported_seconds: number;
+  /** Part of exported_seconds whose export failed midway; may not be booked */
+  unconfirmed_seconds: number;
+  /** Tracked but not booked with the tracker yet */
+  pending_seconds: number;
+  /** A session is still running and isn't counted yet */
+  running: boolean;
+  last_worked_at: string | null;
+}
+
+export interface WorklogExport {
+  /** Zero when less than a minute was left to book */
+  booked_seconds: number;
+  remote_id: string | null;
+  tracked: TrackedTime;
+}
+
+// -- Unified work items (FR-004) --
+
+export type ProviderKind = "github" | "jira" | "fogbugz" | "azure_devops";
+
+export type StatusCategory = "open" | "in_progress" | "resolved" | "closed";
+
+export interface WorkItem {
+  id: string;
+  connection_id: string;
+  provider: ProviderKind;
+  provider_id: string;
+  kind: "issue" | "pull_request";
+  url: string;
+  title: string;
+  description: string | null;
+  status: string;
+  status_category: StatusCategory;
+  priority: string | null;
+  item_type: string | null;
+  assignee: string | null;
+  author: string | null;
+  labels: string[];
+  project: string;
+  created_at: string | null;
+  updated_at: string;
+  extra: Record<string, unknown>;
+}
+
+export interface ProviderStatus {
+  connection_id: string;
+  provider: ProviderKind;
+  authenticated: boolean;
+  account: string | null;
+}
+
+export interface ProviderFailure {
+  connection_id: string;
+  provider: ProviderKind;
+  /** The part of the connection that failed, e.g. an Azure DevOps project */
+  source: string | null;
+  error: AuraError;
+}
+
+export interface WorkItemList {
+  items: WorkItem[];
+  errors: ProviderFailure[];
+}
+
+export interface ConnectionSyncStatus {
+  connection_id: string;
+  provider: ProviderKind;
+  label: string;
+  interval_minutes: number;
+  last_synced_at: string | null;
+  last_error: string | null;
+  syncing: boolean;
+}
+
+export interface CachedWorkItems {
+  items: WorkItem[];
+  sync: ConnectionSyncStatus[];
+}
+
+// -- Errors --
+
+export interface AuraError {
+  kind:
+    | "not_authenticated"
+    | "rate_limited"
+    | "network"
+    | "provider_api"
+    | "cli_missing"
+    | "cli_failed"
+    | "parse"
+    | "not_configured"
+    | "database"
+    | "keychain"
+    | "update"
+    | "io"
+    | "invalid_input";
+  message: string;
+  retryable: boolean;
+  provider?: ProviderKind;
+  status?: number;
+  reset_at?: string | null;
+  tool?: string;
 }